@@ -0,0 +1,251 @@
+use anyhow::{anyhow, Context, Result};
+use regex::Regex;
+use serde_json::json;
+use std::cell::Cell;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config::AlertConfig;
+use crate::duration::parse_duration;
+use crate::filter::detect_log_level;
+
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(10);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What an alert rule does when a line matches.
+#[derive(Debug, Clone)]
+enum Action {
+    /// POST a JSON body to a webhook; `text` makes it show up in Slack and friends
+    Post(String),
+    /// Run through `sh -c` with the line on stdin and in `FT_LINE`
+    Exec(String),
+}
+
+/// `--alert` and `[[alert]]` rules: each fires its action for lines that arrive while
+/// following and match its pattern, at most once per cooldown.
+pub struct Alerts {
+    rules: Vec<Rule>,
+}
+
+struct Rule {
+    pattern: Regex,
+    action: Action,
+    cooldown: Duration,
+    last_fired: Cell<Option<Instant>>,
+    /// Lines matched and times fired, for `--metrics-listen`
+    counters: Arc<AlertCounters>,
+}
+
+/// How often an alert rule's pattern matched and its action fired.
+#[derive(Default)]
+pub struct AlertCounters {
+    pub matched: AtomicU64,
+    pub fired: AtomicU64,
+}
+
+impl Alerts {
+    /// Rules from the command line (`PATTERN=POST URL` or `PATTERN=exec COMMAND`) and the
+    /// config; `None` when there are none.
+    pub fn new(specs: &[String], configs: &[AlertConfig]) -> Result<Option<Self>> {
+        let mut rules = Vec::new();
+        for spec in specs {
+            rules.push(Rule::parse(spec).with_context(|| format!("Invalid --alert '{}'", spec))?);
+        }
+        for (i, config) in configs.iter().enumerate() {
+            rules.push(Rule::from_config(config).with_context(|| format!("Invalid [[alert]] #{}", i + 1))?);
+        }
+        Ok((!rules.is_empty()).then_some(Self { rules }))
+    }
+
+    /// Fire every rule that one of `lines`, newly read from `path`, matches and that
+    /// isn't cooling down. Actions run in the background so the tail never waits on them.
+    pub fn check(&self, path: &Path, lines: &[String]) {
+        for rule in &self.rules {
+            let mut matching = lines.iter().filter(|line| rule.pattern.is_match(line));
+            let Some(line) = matching.next() else {
+                continue;
+            };
+            rule.counters.matched.fetch_add(1 + matching.count() as u64, Ordering::Relaxed);
+            if rule.last_fired.get().is_some_and(|at| at.elapsed() < rule.cooldown) {
+                continue;
+            }
+            rule.last_fired.set(Some(Instant::now()));
+            rule.counters.fired.fetch_add(1, Ordering::Relaxed);
+            rule.fire(path, line);
+        }
+    }
+
+    /// Each rule's pattern and counters, which stay up to date as lines are checked.
+    pub fn counters(&self) -> Vec<(String, Arc<AlertCounters>)> {
+        self.rules.iter().map(|rule| (rule.pattern.as_str().to_string(), rule.counters.clone())).collect()
+    }
+}
+
+impl Rule {
+    fn parse(spec: &str) -> Result<Self> {
+        let split = Regex::new(r"(?i)=(post|exec)\s+").unwrap();
+        let caps = split.captures(spec)
+            .ok_or_else(|| anyhow!("expected PATTERN=POST URL or PATTERN=exec COMMAND"))?;
+        let whole = caps.get(0).unwrap();
+        let pattern = &spec[..whole.start()];
+        let target = spec[whole.end()..].trim().to_string();
+        let action = if caps[1].eq_ignore_ascii_case("post") { Action::Post(target) } else { Action::Exec(target) };
+        Self::new(pattern, action, None)
+    }
+
+    fn from_config(config: &AlertConfig) -> Result<Self> {
+        let action = match (&config.post, &config.exec) {
+            (Some(url), None) => Action::Post(url.clone()),
+            (None, Some(command)) => Action::Exec(command.clone()),
+            _ => return Err(anyhow!("set one of post or exec")),
+        };
+        Self::new(&config.pattern, action, config.cooldown.as_deref())
+    }
+
+    fn new(pattern: &str, action: Action, cooldown: Option<&str>) -> Result<Self> {
+        if pattern.is_empty() {
+            return Err(anyhow!("empty pattern"));
+        }
+        let pattern = Regex::new(pattern).with_context(|| format!("Invalid alert pattern: {}", pattern))?;
+        match &action {
+            Action::Post(url) => {
+                Url::parse(url)?;
+            }
+            Action::Exec(command) if command.is_empty() => return Err(anyhow!("empty command")),
+            Action::Exec(_) => {}
+        }
+        let cooldown = match cooldown {
+            Some(spec) => parse_duration(spec).with_context(|| format!("Invalid alert cooldown: {}", spec))?,
+            None => DEFAULT_COOLDOWN,
+        };
+        Ok(Self { pattern, action, cooldown, last_fired: Cell::new(None), counters: Arc::default() })
+    }
+
+    fn fire(&self, path: &Path, line: &str) {
+        let file = path.display().to_string();
+        let level = detect_log_level(line).map(|l| format!("{:?}", l).to_uppercase()).unwrap_or_default();
+        let rule = self.pattern.as_str().to_string();
+        let line = line.to_string();
+        match self.action.clone() {
+            Action::Post(url) => {
+                let body = json!({
+                    "text": format!("ft alert ({}) in {}: {}", rule, file, line),
+                    "rule": rule,
+                    "file": file,
+                    "line": line,
+                    "level": level,
+                }).to_string();
+                thread::spawn(move || {
+                    if let Err(e) = post(&url, &body) {
+                        eprintln!("ft: alert POST to {} failed: {:#}", url, e);
+                    }
+                });
+            }
+            Action::Exec(command) => {
+                let spawned = Command::new("sh")
+                    .arg("-c")
+                    .arg(&command)
+                    .env("FT_EVENT", "alert")
+                    .env("FT_PID", std::process::id().to_string())
+                    .env("FT_RULE", &rule)
+                    .env("FT_FILE", &file)
+                    .env("FT_LINE", &line)
+                    .env("FT_LEVEL", &level)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn();
+                match spawned {
+                    Ok(mut child) => {
+                        let stdin = child.stdin.take();
+                        // Feed and reap in the background so a slow command never stalls the tail
+                        thread::spawn(move || {
+                            if let Some(mut stdin) = stdin {
+                                let _ = writeln!(stdin, "{}", line);
+                            }
+                            child.wait()
+                        });
+                    }
+                    Err(e) => eprintln!("ft: warning: could not run alert command: {}", e),
+                }
+            }
+        }
+    }
+}
+
+/// The parts of a webhook URL needed to send a request.
+struct Url {
+    tls: bool,
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl Url {
+    fn parse(url: &str) -> Result<Self> {
+        let (scheme, rest) = url.split_once("://").ok_or_else(|| anyhow!("Invalid URL '{}'", url))?;
+        let tls = match scheme.to_lowercase().as_str() {
+            "http" => false,
+            "https" if cfg!(feature = "tls") => true,
+            "https" => return Err(anyhow!("This ft was built without TLS support (the `tls` feature)")),
+            _ => return Err(anyhow!("Invalid URL '{}': expected http:// or https://", url)),
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) if !host.contains(':') || host.ends_with(']') => {
+                (host, port.parse().with_context(|| format!("Invalid port in '{}'", url))?)
+            }
+            _ => (authority, if tls { 443 } else { 80 }),
+        };
+        if host.is_empty() {
+            return Err(anyhow!("Invalid URL '{}': no host", url));
+        }
+        Ok(Self { tls, host: host.to_string(), port, path: path.to_string() })
+    }
+}
+
+/// POST `body` as JSON and check for a 2xx answer.
+fn post(url: &str, body: &str) -> Result<()> {
+    let url = Url::parse(url)?;
+    let addr = (url.host.trim_start_matches('[').trim_end_matches(']'), url.port).to_socket_addrs()?
+        .next()
+        .ok_or_else(|| anyhow!("{} doesn't resolve", url.host))?;
+    let stream = TcpStream::connect_timeout(&addr, HTTP_TIMEOUT)?;
+    stream.set_read_timeout(Some(HTTP_TIMEOUT))?;
+    stream.set_write_timeout(Some(HTTP_TIMEOUT))?;
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: ft/{}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        url.path, url.host, env!("CARGO_PKG_VERSION"), body.len(), body
+    );
+    let mut response = Vec::new();
+    if url.tls {
+        #[cfg(feature = "tls")]
+        {
+            let host = url.host.trim_start_matches('[').trim_end_matches(']');
+            let mut stream = native_tls::TlsConnector::new()?.connect(host, stream)
+                .map_err(|e| anyhow!("TLS handshake failed: {}", e))?;
+            stream.write_all(request.as_bytes())?;
+            let _ = stream.read_to_end(&mut response);
+        }
+    } else {
+        let mut stream = stream;
+        stream.write_all(request.as_bytes())?;
+        let _ = stream.read_to_end(&mut response);
+    }
+    let response = String::from_utf8_lossy(&response);
+    let status = response.lines().next().unwrap_or_default();
+    match status.split_whitespace().nth(1) {
+        Some(code) if code.starts_with('2') => Ok(()),
+        Some(_) => Err(anyhow!("{}", status)),
+        None => Err(anyhow!("no response")),
+    }
+}
@@ -0,0 +1,55 @@
+use regex::Regex;
+use std::borrow::Cow;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// Escape sequences other programs put in their output: CSI (colors, cursor movement),
+/// OSC (titles, hyperlinks) and the two-character ones.
+fn escapes() -> &'static Regex {
+    static ESCAPES: OnceLock<Regex> = OnceLock::new();
+    ESCAPES.get_or_init(|| {
+        Regex::new(r"\x1b\[[0-?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)|\x1b[@-Z\\-_]").unwrap()
+    })
+}
+
+/// `line` without escape sequences, as `--strip-ansi` reads it.
+pub fn strip(line: &str) -> Cow<'_, str> {
+    if !line.contains('\x1b') {
+        return Cow::Borrowed(line);
+    }
+    escapes().replace_all(line, "")
+}
+
+/// `line` as plain text, and the parts of it its own SGR sequences color with the
+/// sequences in effect there, so ft can color the rest and put those back on top.
+pub fn split(line: &str) -> (String, Vec<(Range<usize>, String)>) {
+    let mut plain = String::with_capacity(line.len());
+    let mut styled = Vec::new();
+    // SGR sequences since the last reset
+    let mut active = String::new();
+    let mut last = 0;
+    for m in escapes().find_iter(line) {
+        let start = plain.len();
+        plain.push_str(&line[last..m.start()]);
+        if !active.is_empty() && plain.len() > start {
+            styled.push((start..plain.len(), active.clone()));
+        }
+        last = m.end();
+        let escape = m.as_str();
+        if !escape.starts_with("\x1b[") || !escape.ends_with('m') {
+            continue;
+        }
+        let params = &escape[2..escape.len() - 1];
+        if params.split(';').all(|p| p.is_empty() || p == "0") {
+            active.clear();
+        } else {
+            active.push_str(escape);
+        }
+    }
+    let start = plain.len();
+    plain.push_str(&line[last..]);
+    if !active.is_empty() && plain.len() > start {
+        styled.push((start..plain.len(), active));
+    }
+    (plain, styled)
+}
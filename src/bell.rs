@@ -0,0 +1,49 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::cell::Cell;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use is_terminal::IsTerminal;
+
+/// Rings closer together than this are one ring, so a burst of matches beeps once
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// `--bell`: rings the terminal bell when a line matching one of the patterns arrives
+/// while following.
+pub struct Bell {
+    patterns: Vec<Regex>,
+    last_rung: Cell<Option<Instant>>,
+}
+
+impl Bell {
+    pub fn new(patterns: &[String]) -> Result<Option<Self>> {
+        if patterns.is_empty() {
+            return Ok(None);
+        }
+        let patterns = patterns.iter()
+            .map(|p| Regex::new(p).with_context(|| format!("Invalid --bell pattern: {}", p)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Some(Self { patterns, last_rung: Cell::new(None) }))
+    }
+
+    pub fn check(&self, lines: &[String]) {
+        if self.last_rung.get().is_some_and(|at| at.elapsed() < MIN_INTERVAL) {
+            return;
+        }
+        if lines.iter().any(|line| self.patterns.iter().any(|p| p.is_match(line))) {
+            self.last_rung.set(Some(Instant::now()));
+            ring();
+        }
+    }
+}
+
+/// BEL to whichever of stderr and stdout is a terminal, so it never ends up in piped output.
+fn ring() {
+    if io::stderr().is_terminal() {
+        let _ = io::stderr().write_all(b"\x07");
+    } else if io::stdout().is_terminal() {
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+    }
+}
@@ -0,0 +1,144 @@
+use anyhow::{anyhow, Context, Result};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Ways of putting text on the clipboard, chosen by `detect` or the
+/// `clipboard` setting in the `[general]` config section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// OSC 52 escape sequence: the terminal sets the clipboard, which also works over SSH
+    Osc52,
+    WlCopy,
+    Xclip,
+    Xsel,
+    Pbcopy,
+    /// `clip.exe`, on Windows and from inside WSL
+    Windows,
+}
+
+impl Backend {
+    pub fn from_name(name: &str) -> Option<Backend> {
+        match name.to_lowercase().as_str() {
+            "osc52" | "osc-52" | "terminal" => Some(Backend::Osc52),
+            "wl-copy" | "wayland" => Some(Backend::WlCopy),
+            "xclip" | "x11" => Some(Backend::Xclip),
+            "xsel" => Some(Backend::Xsel),
+            "pbcopy" | "macos" => Some(Backend::Pbcopy),
+            "windows" | "clip" | "clip.exe" => Some(Backend::Windows),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Osc52 => "OSC 52",
+            Backend::WlCopy => "wl-copy",
+            Backend::Xclip => "xclip",
+            Backend::Xsel => "xsel",
+            Backend::Pbcopy => "pbcopy",
+            Backend::Windows => "clip.exe",
+        }
+    }
+
+    /// Pick the backend most likely to reach the user's clipboard. Over SSH the
+    /// local terminal is the only clipboard that matters, so OSC 52 wins there.
+    pub fn detect() -> Backend {
+        let env = |name: &str| std::env::var_os(name).is_some_and(|v| !v.is_empty());
+        if env("SSH_TTY") || env("SSH_CONNECTION") {
+            return Backend::Osc52;
+        }
+        if cfg!(windows) || (env("WSL_DISTRO_NAME") && on_path("clip.exe")) {
+            return Backend::Windows;
+        }
+        if cfg!(target_os = "macos") && on_path("pbcopy") {
+            return Backend::Pbcopy;
+        }
+        if env("WAYLAND_DISPLAY") && on_path("wl-copy") {
+            return Backend::WlCopy;
+        }
+        if env("DISPLAY") {
+            if on_path("xclip") {
+                return Backend::Xclip;
+            }
+            if on_path("xsel") {
+                return Backend::Xsel;
+            }
+        }
+        Backend::Osc52
+    }
+}
+
+/// Copy `text` with the configured backend, or an auto-detected one.
+/// Returns the backend used so callers can report it.
+pub fn copy(text: &str, configured: Option<&str>) -> Result<Backend> {
+    let backend = match configured.filter(|name| !name.eq_ignore_ascii_case("auto")) {
+        Some(name) => Backend::from_name(name)
+            .ok_or_else(|| anyhow!("Unknown clipboard backend '{}' (expected auto, osc52, wl-copy, xclip, xsel, pbcopy or windows)", name))?,
+        None => Backend::detect(),
+    };
+
+    match backend {
+        Backend::Osc52 => copy_osc52(text)?,
+        Backend::WlCopy => pipe_to("wl-copy", &[], text)?,
+        Backend::Xclip => pipe_to("xclip", &["-selection", "clipboard"], text)?,
+        Backend::Xsel => pipe_to("xsel", &["--clipboard", "--input"], text)?,
+        Backend::Pbcopy => pipe_to("pbcopy", &[], text)?,
+        Backend::Windows => pipe_to("clip.exe", &[], text)?,
+    }
+    Ok(backend)
+}
+
+fn pipe_to(program: &str, args: &[&str], text: &str) -> Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", program))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())
+            .with_context(|| format!("Failed to write to {}", program))?;
+    }
+    let status = child.wait().with_context(|| format!("Failed to run {}", program))?;
+    if !status.success() {
+        return Err(anyhow!("{} exited with {}", program, status));
+    }
+    Ok(())
+}
+
+fn copy_osc52(text: &str) -> Result<()> {
+    let mut sequence = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+    // tmux only forwards the sequence to the outer terminal when wrapped in a passthrough
+    if std::env::var_os("TMUX").is_some() {
+        sequence = format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"));
+    }
+    let mut stdout = std::io::stdout();
+    stdout.write_all(sequence.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH").is_some_and(|paths| {
+        std::env::split_paths(&paths).any(|dir| Path::new(&dir).join(program).is_file())
+    })
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
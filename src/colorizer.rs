@@ -1,94 +1,530 @@
-use crate::theme::{Theme, Color, ColorRule};
-use regex::Regex;
+use crate::ansi;
+use crate::filter::{detect_log_level, LogLevel};
+use crate::jsonview::{self, JsonLayout, JsonPalette};
+use crate::theme::{AgeRule, Attributes, Theme, Color, ColorDepth, ColorRule, HashFieldRule, Style};
+use regex::{Regex, RegexSet};
+use std::cell::OnceCell;
+use std::ops::Range;
+use std::path::Path;
+use std::time::Duration;
 
+/// Palette used for hash-colored fields when the theme doesn't define `hash_palette:`.
+pub(crate) const DEFAULT_HASH_PALETTE: [u8; 12] = [75, 114, 175, 180, 141, 216, 80, 222, 147, 209, 115, 183];
+
+/// `--json-view` colors for keys, strings, numbers and literals when the theme has no `json_*:`.
+const DEFAULT_JSON_COLORS: [u8; 4] = [75, 114, 216, 175];
+
+/// `--age-colors` when the theme has no `age:` rules: green under a minute old,
+/// yellow under ten minutes, gray after that.
+const DEFAULT_AGE_COLORS: [(Option<u64>, u8); 3] = [(Some(60), 114), (Some(600), 229), (None, 244)];
+
+/// `--color`: when ft writes colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Only to a terminal, and not when `NO_COLOR` is set (unless `CLICOLOR_FORCE` is)
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        match spec.trim().to_lowercase().as_str() {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(anyhow::anyhow!("Invalid color mode '{}': expected auto, always or never", spec)),
+        }
+    }
+
+    /// Whether to color output going to a terminal (`terminal`) or elsewhere. An explicit
+    /// `--color` wins over the environment, as https://no-color.org asks.
+    pub fn enabled(self, terminal: bool) -> bool {
+        let set = |name: &str| std::env::var_os(name).is_some_and(|v| !v.is_empty() && v != "0");
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto if set("NO_COLOR") => false,
+            ColorChoice::Auto => terminal || set("CLICOLOR_FORCE"),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Colorizer {
     theme: Theme,
     no_color: bool,
-    ansi_span_regex: Regex,
+    sgr_regex: Regex,
+    /// The theme's rules as one matcher each for line and word rules; `None` when a
+    /// theme is too big to combine, and every rule is tried in turn
+    rule_index: Option<RuleIndex>,
+    /// `--highlight` rules, painted over the theme's colors
+    highlights: Vec<ColorRule>,
+    /// Colors the terminal can show; richer colors are swapped for the nearest it has
+    depth: ColorDepth,
+    /// `--logfmt-colors`: `key=value` pairs, colored when a line has two or more
+    logfmt: Option<Regex>,
+    /// `--emphasize`: lines detected at this level or worse get the theme's emphasis style
+    emphasize: Option<LogLevel>,
+}
+
+/// Where the SGR sequences in a partly colored line are: `(start, end, restore)`,
+/// in order, with `restore` set for those returning to the base color.
+struct SpanMap {
+    sgrs: Vec<(usize, usize, bool)>,
+}
+
+impl SpanMap {
+    /// Whether `pos` falls inside an escape sequence or text an earlier rule colored,
+    /// i.e. the last SGR before it is a rule's style rather than a return to the base.
+    fn is_colored(&self, pos: usize) -> bool {
+        let before = self.sgrs.partition_point(|&(start, _, _)| start < pos);
+        match before.checked_sub(1).map(|i| self.sgrs[i]) {
+            Some((_, end, _)) if pos < end => true,
+            Some((_, _, restore)) => !restore,
+            None => false,
+        }
+    }
+}
+
+/// One pass over a line tells which of the theme's rules can match it at all.
+#[derive(Clone)]
+struct RuleIndex {
+    line: RegexSet,
+    word: RegexSet,
+}
+
+impl RuleIndex {
+    fn new(theme: &Theme) -> Option<Self> {
+        let set = |rules: &[ColorRule]| RegexSet::new(rules.iter().map(|rule| rule.pattern.as_str())).ok();
+        Some(Self {
+            line: set(&theme.line_rules)?,
+            word: set(&theme.word_rules)?,
+        })
+    }
 }
 
 impl Colorizer {
     pub fn new(theme: Theme, no_color: bool) -> Self {
-        // Regex to match existing ANSI colored spans to avoid double-coloring
-        let ansi_span_regex = Regex::new(r"\x1b\[[0-9;]*m.*?\x1b\[0m").unwrap();
+        // SGR sequences, to find text an earlier rule already colored
+        let sgr_regex = Regex::new(r"\x1b\[[0-9;]*m").unwrap();
         
         Self {
+            rule_index: RuleIndex::new(&theme),
             theme,
             no_color,
-            ansi_span_regex,
+            sgr_regex,
+            highlights: Vec::new(),
+            depth: ColorDepth::TrueColor,
+            logfmt: None,
+            emphasize: None,
         }
     }
+
+    /// Fit the colors the colorizer adds on top of the theme (highlights, the base
+    /// color, the default hash palette) to a terminal of `depth`. See `Theme::downgrade`.
+    pub fn with_color_depth(mut self, depth: ColorDepth) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// `--logfmt-colors`: in `key=value` lines, give each key a color of its own (the
+    /// same one on every line) and values the `--json-view` color for their type.
+    pub fn with_logfmt(mut self) -> Self {
+        self.logfmt = Some(Regex::new(r#"(^|\s)([A-Za-z_][\w.-]*)=("(?:[^"\\]|\\.)*"|[^\s"]*)"#).unwrap());
+        self
+    }
+
+    /// `--emphasize`: mark whole lines at `level` or more severe with the theme's
+    /// `emphasis_style:` (bold by default), on top of their rule colors.
+    pub fn with_emphasis(mut self, level: LogLevel) -> Self {
+        self.emphasize = Some(level);
+        self
+    }
+
+    /// Swap in a reloaded theme; highlights and the color depth stay as they were.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.rule_index = RuleIndex::new(&theme);
+        self.theme = theme;
+    }
+
+    /// Add a session-only `REGEX=COLOR` rule that wins over every theme rule.
+    pub fn add_highlight(&mut self, mut rule: ColorRule) {
+        rule.style.downgrade(self.depth);
+        self.highlights.push(rule);
+    }
     
     pub fn colorize_line(&self, line: &str) -> String {
         if self.no_color {
             return line.to_string();
         }
-        
-        // 1. Check for line-level matches first (first match wins)
-        for rule in &self.theme.line_rules {
-            if rule.pattern.is_match(line) {
-                return self.wrap_entire_line(line, &rule.color);
+        if line.contains('\x1b') {
+            // Color what the line doesn't color itself, so theme rules never match inside its
+            // escape sequences, then put its own colors back over the top
+            let (plain, styled) = ansi::split(line);
+            let mut result = self.colorize_plain(&plain);
+            for (range, sgr) in &styled {
+                result = overlay_ranges(&result, &plain, std::slice::from_ref(range), sgr);
+            }
+            return result;
+        }
+        self.colorize_plain(line)
+    }
+
+    fn colorize_plain(&self, line: &str) -> String {
+        let mut result = self.colorize_with_theme(line);
+        for rule in &self.highlights {
+            result = overlay_matches(&result, line, std::slice::from_ref(&rule.pattern), &rule.style.to_ansi());
+        }
+        let severe = |threshold: LogLevel| detect_log_level(line).is_some_and(|level| level.priority() <= threshold.priority());
+        if self.emphasize.is_some_and(severe) {
+            let default = Style { attributes: Attributes { bold: true, ..Attributes::default() }, ..Style::default() };
+            let emphasis = self.theme.emphasis_style.as_ref().unwrap_or(&default).to_ansi();
+            // Every full reset inside the line would drop the emphasis, so it's set again after each
+            let reset = Color::to_ansi_reset();
+            let body = result.strip_suffix(reset).unwrap_or(&result);
+            result = format!("{}{}{}", emphasis, body.replace(reset, &format!("{}{}", reset, emphasis)), reset);
+        }
+        result
+    }
+
+    /// `--json-view`: lay out a JSON line and color its keys and values by type.
+    /// Returns the plain text the layout produced along with the colored text,
+    /// or `None` when the line isn't JSON.
+    pub fn colorize_json(&self, line: &str, layout: JsonLayout) -> Option<(String, String)> {
+        let [key, string, number, literal] = self.json_styles();
+        let palette = JsonPalette { key: &key, string: &string, number: &number, literal: &literal };
+        let (plain, mut colored) = jsonview::render(line, layout, (!self.no_color).then_some(palette))?;
+        if !self.no_color {
+            for rule in &self.highlights {
+                colored = overlay_matches(&colored, &plain, std::slice::from_ref(&rule.pattern), &rule.style.to_ansi());
             }
         }
+        Some((plain, colored))
+    }
+
+    /// The theme's `json_*:` styles for keys, strings, numbers and literals, or ft's defaults.
+    fn json_styles(&self) -> [Style; 4] {
+        let json = &self.theme.json_styles;
+        let [key, string, number, literal] = DEFAULT_JSON_COLORS.map(|n| Style::from(Color::Xterm256(n).downgrade(self.depth)));
+        [
+            json.key.clone().unwrap_or(key),
+            json.string.clone().unwrap_or(string),
+            json.number.clone().unwrap_or(number),
+            json.literal.clone().unwrap_or(literal),
+        ]
+    }
+
+    fn colorize_with_theme(&self, line: &str) -> String {
+        // 1. Check for line-level matches first (first match wins)
+        let line_rule = match &self.rule_index {
+            Some(index) => index.line.matches(line).iter().next(),
+            None => self.theme.line_rules.iter().position(|rule| rule.pattern.is_match(line)),
+        };
+        if let Some(i) = line_rule {
+            return self.wrap_entire_line(line, &self.theme.line_rules[i].style);
+        }
         
         // 2. Apply word-level coloring
         let mut result = line.to_string();
         
-        for rule in &self.theme.word_rules {
-            result = self.apply_word_rule(&result, rule);
+        // Hash-colored fields go first so word rules don't steal their values
+        for rule in &self.theme.hash_fields {
+            result = self.apply_hash_field(&result, rule);
+        }
+        
+        // Then the level keyword, in the color the theme gives the line's level
+        if !self.theme.level_rules.is_empty() {
+            let level = detect_log_level(line);
+            if let Some(level) = self.theme.level_rules.iter().find(|rule| Some(rule.level) == level) {
+                result = self.apply_word_rule(&result, &level.rule);
+            }
+        }
+        
+        // Only rules that match somewhere in the line are run on their own
+        let word_hits = self.rule_index.as_ref().map(|index| index.word.matches(line));
+        for (i, rule) in self.theme.word_rules.iter().enumerate() {
+            if word_hits.as_ref().is_none_or(|hits| hits.matched(i)) {
+                result = self.apply_word_rule(&result, rule);
+            }
+        }
+        
+        // logfmt pairs last, so the theme's colors for values (levels, IPs...) stay
+        if let Some(pattern) = self.logfmt.as_ref().filter(|pattern| pattern.find_iter(line).nth(1).is_some()) {
+            result = self.apply_logfmt(&result, pattern);
         }
         
         // 3. Apply base color to the whole line (preserves inner highlights)
-        if let Some(base_color) = self.theme.base_color {
-            result = format!("\x1b[38;5;{}m{}\x1b[0m", base_color, result);
+        if let Some(base) = self.base_ansi() {
+            result = format!("{}{}\x1b[0m", base, result);
         }
         
         result
     }
     
-    fn wrap_entire_line(&self, line: &str, color: &Color) -> String {
-        format!("{}{}{}", color.to_ansi_fg(), line, Color::to_ansi_reset())
+    fn wrap_entire_line(&self, line: &str, style: &Style) -> String {
+        format!("{}{}{}", style.to_ansi(), line, Color::to_ansi_reset())
     }
     
     fn apply_word_rule(&self, text: &str, rule: &ColorRule) -> String {
         // Replace matches while avoiding already-colored segments
+        let spans = OnceCell::new();
         rule.pattern.replace_all(text, |caps: &regex::Captures| {
-            let matched_text = caps.get(0).unwrap().as_str();
+            let whole = caps.get(0).unwrap();
+            // With a group, only that part of the match is colored; the rest is kept as is
+            let target = match rule.group {
+                Some(n) => match caps.get(n) {
+                    Some(group) => group,
+                    None => return whole.as_str().to_string(),
+                },
+                None => whole,
+            };
             
             // Check if this match is inside an existing ANSI sequence
-            if self.is_inside_ansi_sequence(text, caps.get(0).unwrap().start()) {
-                matched_text.to_string()
-            } else {
-                self.wrap_with_base_restore(matched_text, &rule.color)
+            if spans.get_or_init(|| self.span_map(text)).is_colored(target.start()) {
+                return whole.as_str().to_string();
             }
+            let prefix = &text[whole.start()..target.start()];
+            let suffix = &text[target.end()..whole.end()];
+            format!("{}{}{}", prefix, self.wrap_with_base_restore(target.as_str(), &rule.style), suffix)
         }).to_string()
     }
     
-    fn is_inside_ansi_sequence(&self, text: &str, pos: usize) -> bool {
-        for ansi_match in self.ansi_span_regex.find_iter(text) {
-            if pos >= ansi_match.start() && pos < ansi_match.end() {
-                return true;
+    fn apply_hash_field(&self, text: &str, rule: &HashFieldRule) -> String {
+        let spans = OnceCell::new();
+        rule.pattern.replace_all(text, |caps: &regex::Captures| {
+            let whole = caps.get(0).unwrap();
+            let value = caps.get(1).unwrap();
+
+            if spans.get_or_init(|| self.span_map(text)).is_colored(value.start()) {
+                return whole.as_str().to_string();
             }
+
+            let prefix = &text[whole.start()..value.start()];
+            let suffix = &text[value.end()..whole.end()];
+            let colored = self.wrap_with_base_restore(value.as_str(), &self.hash_style(value.as_str()));
+            format!("{}{}{}", prefix, colored, suffix)
+        }).to_string()
+    }
+    
+    fn apply_logfmt(&self, text: &str, pattern: &Regex) -> String {
+        let spans = OnceCell::new();
+        let [_, string, number, literal] = self.json_styles();
+        pattern.replace_all(text, |caps: &regex::Captures| {
+            let spans = spans.get_or_init(|| self.span_map(text));
+            let (key, value) = (caps.get(2).unwrap(), caps.get(3).unwrap());
+            let key = match spans.is_colored(key.start()) {
+                true => key.as_str().to_string(),
+                false => self.wrap_with_base_restore(key.as_str(), &self.hash_style(key.as_str())),
+            };
+            // A value starting with an escape sequence was colored by a theme rule
+            let value = match value.as_str() {
+                "" => String::new(),
+                v if v.starts_with('\x1b') || spans.is_colored(value.start()) => v.to_string(),
+                v if v.starts_with('"') => self.wrap_with_base_restore(v, &string),
+                v if matches!(v, "true" | "false" | "null") => self.wrap_with_base_restore(v, &literal),
+                v if v.parse::<f64>().is_ok() => self.wrap_with_base_restore(v, &number),
+                v => self.wrap_with_base_restore(v, &string),
+            };
+            format!("{}{}={}", &caps[1], key, value)
+        }).to_string()
+    }
+
+    /// `text` (a file's name) in the color `path` hashes to in the theme's `source_palette:`,
+    /// else the hash palette, so each source keeps one color in headers and prefixes.
+    pub fn paint_source(&self, path: &Path, text: &str) -> String {
+        if self.no_color {
+            return text.to_string();
+        }
+        let path = path.to_string_lossy();
+        let style = match self.theme.source_palette.as_slice() {
+            [] => self.hash_style(&path),
+            palette => palette[Self::hash_value(&path) % palette.len()].clone().into(),
+        };
+        format!("{}{}{}", style.to_ansi(), text, Color::to_ansi_reset())
+    }
+
+    /// The `--age-colors` style for a timestamp `age` old, from the theme's `age:` rules
+    /// or ft's defaults. None when it's older than every rule and there's no `older`.
+    pub fn age_style(&self, age: Duration) -> Option<Style> {
+        let defaults;
+        let rules = match self.theme.age_rules.as_slice() {
+            [] => {
+                defaults = DEFAULT_AGE_COLORS.map(|(secs, color)| AgeRule {
+                    max_age: secs.map(Duration::from_secs),
+                    style: Color::Xterm256(color).downgrade(self.depth).into(),
+                });
+                &defaults[..]
+            }
+            rules => rules,
+        };
+        rules.iter()
+            .filter(|rule| rule.max_age.is_none_or(|max| age < max))
+            .min_by_key(|rule| rule.max_age.unwrap_or(Duration::MAX))
+            .map(|rule| rule.style.clone())
+    }
+
+    /// The palette color `value` hashes to: the theme's `hash_palette:`, else ft's.
+    fn hash_style(&self, value: &str) -> Style {
+        match self.theme.hash_palette.as_slice() {
+            [] => {
+                let idx = Self::hash_value(value) % DEFAULT_HASH_PALETTE.len();
+                Color::Xterm256(DEFAULT_HASH_PALETTE[idx]).downgrade(self.depth).into()
+            }
+            palette => palette[Self::hash_value(value) % palette.len()].clone().into(),
         }
-        false
     }
     
-    fn wrap_with_base_restore(&self, text: &str, color: &Color) -> String {
-        let reset = if let Some(base) = self.theme.base_color {
-            format!("\x1b[38;5;{}m", base)
-        } else {
+    /// FNV-1a, chosen because it is stable across runs and Rust versions.
+    fn hash_value(value: &str) -> usize {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in value.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        hash as usize
+    }
+    
+    /// Scan `text` once for the SGR sequences earlier rules left in it, so every
+    /// match a rule finds is checked against them without rescanning.
+    fn span_map(&self, text: &str) -> SpanMap {
+        let base = self.base_ansi();
+        let sgrs = self.sgr_regex.find_iter(text)
+            .map(|sgr| {
+                // The sequences `wrap_with_base_restore` ends a colored match with
+                let restore = matches!(sgr.as_str(), "\x1b[0m" | "\x1b[39m") || base.as_deref() == Some(sgr.as_str());
+                (sgr.start(), sgr.end(), restore)
+            })
+            .collect();
+        SpanMap { sgrs }
+    }
+    
+    /// The sequence that sets the theme's base color, if it has one.
+    fn base_ansi(&self) -> Option<String> {
+        self.theme.base_color.map(|base| Color::Xterm256(base).downgrade(self.depth).to_ansi_fg())
+    }
+
+    fn wrap_with_base_restore(&self, text: &str, style: &Style) -> String {
+        let restore_base = self.base_ansi().unwrap_or_default();
+        let reset = if style.needs_reset() {
+            // Attributes and backgrounds need a full reset; the base color comes back after it
+            format!("{}{}", Color::to_ansi_reset(), restore_base)
+        } else if restore_base.is_empty() {
             "\x1b[39m".to_string() // Reset to default
+        } else {
+            restore_base
         };
         
-        format!("{}{}{}", color.to_ansi_fg(), text, reset)
+        format!("{}{}{}", style.to_ansi(), text, reset)
     }
     
-    pub fn theme_name(&self) -> &str {
-        &self.theme.name
+    pub fn colors_enabled(&self) -> bool {
+        !self.no_color
     }
     
+    
     // For testing and debugging
     pub fn get_theme(&self) -> &Theme {
         &self.theme
     }
-}
\ No newline at end of file
+}
+/// Paint every match of `patterns` in the raw line with the `on` sequence on top of
+/// an already colored rendering of it, then restore whatever style was active.
+/// Decorations before the raw text (lanes, badges) are skipped.
+pub fn overlay_matches(colored: &str, raw: &str, patterns: &[Regex], on: &str) -> String {
+    let ranges: Vec<Range<usize>> = patterns.iter()
+        .flat_map(|re| re.find_iter(raw))
+        .filter(|m| !m.is_empty())
+        .map(|m| m.range())
+        .collect();
+    overlay_ranges(colored, raw, &ranges, on)
+}
+
+/// `overlay_matches` for byte ranges of the raw line found some other way.
+pub fn overlay_ranges(colored: &str, raw: &str, ranges: &[Range<usize>], on: &str) -> String {
+    // Match positions in characters, so they line up with visible columns
+    let char_index = |byte: usize| raw[..byte].chars().count();
+    let match_ranges: Vec<(usize, usize)> = ranges.iter()
+        .map(|range| (char_index(range.start), char_index(range.end)))
+        .collect();
+    if match_ranges.is_empty() {
+        return colored.to_string();
+    }
+
+    let lead = visible_char_count(colored).saturating_sub(raw.chars().filter(|c| !c.is_control()).count());
+
+    let mut result = String::with_capacity(colored.len() + match_ranges.len() * 16);
+    // SGR sequences since the last reset: replayed to restore the line's own style after a match
+    let mut active_style = String::new();
+    let mut escape = String::new();
+    let mut visible_pos = 0usize;
+    let mut in_highlight = false;
+
+    for ch in colored.chars() {
+        if !escape.is_empty() {
+            escape.push(ch);
+            if ch.is_ascii_alphabetic() {
+                if escape == "\x1b[0m" || escape == "\x1b[m" {
+                    active_style.clear();
+                } else {
+                    active_style.push_str(&escape);
+                }
+                result.push_str(&escape);
+                escape.clear();
+                // The line's own colors may reset or recolor mid-match
+                if in_highlight {
+                    result.push_str(on);
+                }
+            }
+            continue;
+        }
+
+        if ch == '\x1b' {
+            escape.push(ch);
+            continue;
+        }
+
+        if ch.is_control() {
+            result.push(ch);
+            continue;
+        }
+
+        let should_highlight = visible_pos >= lead
+            && match_ranges.iter().any(|(s, e)| visible_pos - lead >= *s && visible_pos - lead < *e);
+
+        if should_highlight && !in_highlight {
+            result.push_str(on);
+            in_highlight = true;
+        } else if !should_highlight && in_highlight {
+            result.push_str("\x1b[0m");
+            result.push_str(&active_style);
+            in_highlight = false;
+        }
+
+        result.push(ch);
+        visible_pos += 1;
+    }
+
+    result.push_str(&escape);
+    if in_highlight {
+        result.push_str("\x1b[0m");
+    }
+
+    result
+}
+
+/// Visible characters in a colored line: everything but escape sequences and controls.
+fn visible_char_count(colored: &str) -> usize {
+    let mut count = 0;
+    let mut in_escape = false;
+    for ch in colored.chars() {
+        if in_escape {
+            in_escape = !ch.is_ascii_alphabetic();
+        } else if ch == '\x1b' {
+            in_escape = true;
+        } else if !ch.is_control() {
+            count += 1;
+        }
+    }
+    count
+}
@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::fs;
 
@@ -7,6 +8,28 @@ use std::fs;
 pub struct Config {
     pub general: GeneralConfig,
     pub themes: ThemeConfig,
+    /// Named filter sets from `[preset.NAME]` tables, activated with `--preset NAME`
+    #[serde(default, rename = "preset", skip_serializing_if = "BTreeMap::is_empty")]
+    pub presets: BTreeMap<String, Preset>,
+    /// Column layouts for CSV/JSON output from `[schema.NAME]` tables, chosen with
+    /// `--schema NAME`; a schema named `default` applies without the flag
+    #[serde(default, rename = "schema", skip_serializing_if = "BTreeMap::is_empty")]
+    pub schemas: BTreeMap<String, SchemaConfig>,
+    /// Log formats from `[parser.NAME]` tables, and which files use them
+    #[serde(default, rename = "parser", skip_serializing_if = "BTreeMap::is_empty")]
+    pub parsers: BTreeMap<String, ParserConfig>,
+    /// Commands run on session events, from the `[hooks]` table
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<HooksConfig>,
+    /// Webhooks and commands fired by matching lines, from `[[alert]]` tables
+    #[serde(default, rename = "alert", skip_serializing_if = "Vec::is_empty")]
+    pub alerts: Vec<AlertConfig>,
+    /// Extra patterns for `--redact`, from the `[redact]` table
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redact: Option<RedactConfig>,
+    /// Settings for the sources matching a glob, from `[profile."GLOB"]` tables
+    #[serde(default, rename = "profile", skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ProfileConfig>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -14,6 +37,15 @@ pub struct GeneralConfig {
     pub theme: String,
     pub buffer_size: Option<usize>,
     pub follow_retry_interval: Option<u64>,
+    /// Clipboard backend for yanking: auto (default), osc52, wl-copy, xclip, xsel, pbcopy or windows
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clipboard: Option<String>,
+    /// Color IPs, URLs, UUIDs, hashes, durations and sizes in every theme (overrides the theme's `smart_highlight:`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smart_highlight: Option<bool>,
+    /// Make URLs and file paths clickable in terminals that support OSC 8 links, like `--hyperlinks`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hyperlinks: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -22,6 +54,97 @@ pub struct ThemeConfig {
     pub user_path: PathBuf,
 }
 
+/// A named set of filters. Lists add to those given on the command line;
+/// single values apply unless the command line sets them.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Preset {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub match_mode: Option<String>,
+    pub ignore_case: bool,
+    pub level: Option<String>,
+    pub level_exact: bool,
+    pub level_invert: bool,
+    pub fields: Vec<String>,
+    pub filter: Option<String>,
+}
+
+/// A log format. `pattern`'s named groups become the line's fields; a built-in
+/// format (json, logfmt, syslog, accesslog, generic) takes only `files`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ParserConfig {
+    pub pattern: Option<String>,
+    /// Globs (`*/nginx/access*.log`) of the sources this format applies to
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+}
+
+/// How sources matching a `[profile."*/nginx/*"]` glob are shown. A profile's theme
+/// can `extends:` the main one to only add or change a few rules for those files.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileConfig {
+    pub theme: String,
+}
+
+/// Shell commands run on session events. `alert_pattern` (a regex) decides which
+/// lines fire `on_alert`; without it, lines at `alert_level` (default ERROR) or worse do.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksConfig {
+    pub on_start: Option<String>,
+    pub on_rotate: Option<String>,
+    pub on_exit: Option<String>,
+    pub on_alert: Option<String>,
+    pub alert_pattern: Option<String>,
+    pub alert_level: Option<String>,
+    /// Minimum time between two `on_alert` runs (default 10s)
+    pub alert_cooldown: Option<String>,
+}
+
+/// An alert rule: lines matching `pattern` (a regex) while following POST to the
+/// `post` webhook or run `exec`, at most once per `cooldown` (default 10s).
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertConfig {
+    pub pattern: String,
+    pub post: Option<String>,
+    pub exec: Option<String>,
+    pub cooldown: Option<String>,
+}
+
+/// Secrets to hide besides the built-in ones: each regex in `patterns` has its match,
+/// or its first capture group, replaced. `always` redacts without `--redact`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RedactConfig {
+    pub always: bool,
+    pub patterns: Vec<String>,
+}
+
+/// Output columns for structured formats, in order. `pattern` adds its named
+/// capture groups to the fields the built-in parser extracts.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SchemaConfig {
+    pub pattern: Option<String>,
+    pub columns: Vec<ColumnConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnConfig {
+    pub name: String,
+    /// string (default), int, float, bool or timestamp
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// Used when the field is missing or doesn't convert to the column's type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<toml::Value>,
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -29,6 +152,9 @@ impl Default for Config {
                 theme: "catppuccin".to_string(),
                 buffer_size: Some(8192),
                 follow_retry_interval: Some(1000),
+                clipboard: None,
+                smart_highlight: None,
+                hyperlinks: None,
             },
             themes: ThemeConfig {
                 builtin_path: PathBuf::from("/etc/fuzzytail/themes"),
@@ -36,6 +162,13 @@ impl Default for Config {
                     .unwrap_or_else(|| PathBuf::from("~/.config"))
                     .join("fuzzytail/themes"),
             },
+            presets: BTreeMap::new(),
+            schemas: BTreeMap::new(),
+            parsers: BTreeMap::new(),
+            hooks: None,
+            alerts: Vec::new(),
+            redact: None,
+            profiles: BTreeMap::new(),
         }
     }
 }
@@ -65,7 +198,7 @@ impl Config {
         }
     }
 
-    fn default_config_path() -> Result<PathBuf> {
+    pub fn default_config_path() -> Result<PathBuf> {
         let config_dir = dirs::config_dir()
             .context("Failed to find config directory")?
             .join("fuzzytail");
@@ -92,21 +225,38 @@ impl Config {
         Ok(())
     }
 
+    pub fn preset(&self, name: &str) -> Result<&Preset> {
+        self.presets.get(name).with_context(|| {
+            let known: Vec<&str> = self.presets.keys().map(String::as_str).collect();
+            if known.is_empty() {
+                format!("Unknown preset '{}' (no [preset.NAME] tables in config)", name)
+            } else {
+                format!("Unknown preset '{}' (available: {})", name, known.join(", "))
+            }
+        })
+    }
+
+    /// The `--schema` to use: the one named, else `[schema.default]` if there is one.
+    pub fn schema(&self, name: Option<&str>) -> Result<Option<&SchemaConfig>> {
+        let Some(name) = name else {
+            return Ok(self.schemas.get("default"));
+        };
+        self.schemas.get(name).map(Some).with_context(|| {
+            let known: Vec<&str> = self.schemas.keys().map(String::as_str).collect();
+            if known.is_empty() {
+                format!("Unknown schema '{}' (no [schema.NAME] tables in config)", name)
+            } else {
+                format!("Unknown schema '{}' (available: {})", name, known.join(", "))
+            }
+        })
+    }
+
     pub fn get_theme_path(&self, theme_name: &str) -> Option<PathBuf> {
-        let theme_file = format!("ft.conf.{}", theme_name);
-        
-        // Check user path first
-        let user_theme = self.themes.user_path.join(&theme_file);
-        if user_theme.exists() {
-            return Some(user_theme);
-        }
-        
-        // Check builtin path
-        let builtin_theme = self.themes.builtin_path.join(&theme_file);
-        if builtin_theme.exists() {
-            return Some(builtin_theme);
-        }
-        
-        None
+        let theme_files = [format!("ft.conf.{}", theme_name), format!("ft.theme.{}.toml", theme_name)];
+
+        // Check user path first, then builtin path
+        [&self.themes.user_path, &self.themes.builtin_path].into_iter()
+            .flat_map(|dir| theme_files.iter().map(move |file| dir.join(file)))
+            .find(|path| path.exists())
     }
 }
\ No newline at end of file
@@ -0,0 +1,167 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+
+use crate::config::Preset;
+use crate::filter::{FilterOptions, LineFilter};
+use crate::parser::Parser;
+
+/// How long a client gets to send its request, so one that never does can't hold up the rest
+#[cfg(unix)]
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// A `ft ctl set-filter` request. Each setting given replaces the running one and
+/// the rest are kept, so `--level WARN` alone leaves the include patterns alone.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FilterUpdate {
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    pub match_mode: Option<String>,
+    pub level: Option<String>,
+    pub filter: Option<String>,
+    /// Drop the running patterns, level and expression before applying the rest
+    pub clear: bool,
+}
+
+impl FilterUpdate {
+    fn apply(self, options: &mut FilterOptions) {
+        if self.clear {
+            options.includes.clear();
+            options.excludes.clear();
+            options.match_mode = None;
+            options.level = None;
+            options.expression = None;
+        }
+        if let Some(include) = self.include {
+            options.includes = include;
+        }
+        if let Some(exclude) = self.exclude {
+            options.excludes = exclude;
+        }
+        if self.match_mode.is_some() {
+            options.match_mode = self.match_mode;
+        }
+        if self.level.is_some() {
+            options.level = self.level;
+        }
+        if self.filter.is_some() {
+            options.expression = self.filter;
+        }
+    }
+}
+
+/// Where `--control` listens and `ft ctl` connects when no path is given.
+pub fn default_socket_path() -> PathBuf {
+    dirs::runtime_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("ft.sock")
+}
+
+/// `--control`: a Unix socket that `ft ctl` sends filter changes to. Requests are
+/// checked and compiled on the listener thread; the tail loop picks up the result
+/// between lines, so files keep their offsets and no line sees half a filter.
+pub struct ControlServer {
+    path: PathBuf,
+    filters: mpsc::Receiver<LineFilter>,
+}
+
+impl ControlServer {
+    /// Listen at `path`. `options` and `presets` are what the filter was built from
+    /// at startup; updates are applied on top of them.
+    #[cfg(unix)]
+    pub fn bind(path: &Path, options: FilterOptions, presets: Vec<Preset>, parser: Arc<dyn Parser>) -> Result<Self> {
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::net::{UnixListener, UnixStream};
+
+        if path.exists() {
+            if UnixStream::connect(path).is_ok() {
+                return Err(anyhow!("Another ft is already listening on {:?}", path));
+            }
+            // Left behind by an ft that didn't exit cleanly
+            let _ = std::fs::remove_file(path);
+        }
+        let listener = UnixListener::bind(path)
+            .with_context(|| format!("Failed to listen on {:?}", path))?;
+
+        let (tx, filters) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut options = options;
+            let presets: Vec<&Preset> = presets.iter().collect();
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                let _ = stream.set_read_timeout(Some(REQUEST_TIMEOUT));
+                let mut request = String::new();
+                if BufReader::new(&stream).read_line(&mut request).is_err() {
+                    continue;
+                }
+                let result = serde_json::from_str::<FilterUpdate>(&request)
+                    .context("Invalid request")
+                    .and_then(|update| {
+                        let mut updated = options.clone();
+                        update.apply(&mut updated);
+                        let filter = LineFilter::with_presets(updated.clone(), &presets)?
+                            .with_parser(parser.clone());
+                        Ok((updated, filter))
+                    });
+                let reply = match result {
+                    Ok((updated, filter)) => {
+                        if tx.send(filter).is_err() {
+                            break;
+                        }
+                        options = updated;
+                        "ok".to_string()
+                    }
+                    Err(e) => format!("error: {:#}", e),
+                };
+                let _ = write!(stream, "{}", reply);
+            }
+        });
+
+        Ok(Self { path: path.to_path_buf(), filters })
+    }
+
+    #[cfg(not(unix))]
+    pub fn bind(_path: &Path, _options: FilterOptions, _presets: Vec<Preset>, _parser: Arc<dyn Parser>) -> Result<Self> {
+        Err(anyhow!("--control needs Unix sockets, which this platform doesn't have"))
+    }
+
+    /// The most recent filter sent since the last call, if any.
+    pub fn next_filter(&self) -> Option<LineFilter> {
+        self.filters.try_iter().last()
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// `ft ctl set-filter`: send `update` to the ft listening at `path` and wait for its answer.
+#[cfg(unix)]
+pub fn send(path: &Path, update: &FilterUpdate) -> Result<()> {
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(path)
+        .with_context(|| format!("No ft is listening on {:?} (start it with --control)", path))?;
+    writeln!(stream, "{}", serde_json::to_string(update)?)
+        .with_context(|| format!("Failed to write to {:?}", path))?;
+    // The reply is everything up to the close, since errors can span lines
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)
+        .with_context(|| format!("Failed to read from {:?}", path))?;
+    match reply.trim() {
+        "ok" => Ok(()),
+        reply => Err(anyhow!("{}", reply.strip_prefix("error: ").unwrap_or(reply))),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn send(_path: &Path, _update: &FilterUpdate) -> Result<()> {
+    Err(anyhow!("ft ctl needs Unix sockets, which this platform doesn't have"))
+}
@@ -0,0 +1,59 @@
+use chrono::{DateTime, Local};
+use std::time::{Duration, Instant};
+
+use crate::duration::format_duration;
+
+/// Width of the gap column, so lines stay aligned
+const WIDTH: usize = 7;
+/// Gaps at least this long are marked in yellow, and in red from ten times that
+const SLOW: Duration = Duration::from_secs(1);
+
+/// `--delta`: the time since the previous line, from the lines' timestamps when both
+/// have one and from when ft read them otherwise, to make stalls stand out.
+pub struct DeltaClock {
+    last_timestamp: Option<DateTime<Local>>,
+    last_arrival: Option<Instant>,
+}
+
+impl DeltaClock {
+    pub fn new() -> Self {
+        Self { last_timestamp: None, last_arrival: None }
+    }
+
+    /// Record a line and return the prefix showing how long after the previous one it came.
+    pub fn stamp(&mut self, timestamp: Option<DateTime<Local>>, color: bool) -> String {
+        let now = Instant::now();
+        let (gap, backwards) = match (timestamp, self.last_timestamp) {
+            (Some(time), Some(last)) => match (time - last).to_std() {
+                Ok(gap) => (gap, false),
+                // Out of order: shown as negative rather than as a stall
+                Err(_) => ((last - time).to_std().unwrap_or_default(), true),
+            },
+            _ => (self.last_arrival.map_or(Duration::ZERO, |last| now - last), false),
+        };
+        if timestamp.is_some() {
+            self.last_timestamp = timestamp;
+        }
+        self.last_arrival = Some(now);
+
+        let label = format!("{:>width$}", format!("{}{}", if backwards { "-" } else { "+" }, format_gap(gap)), width = WIDTH);
+        match color {
+            true if backwards || gap < SLOW => format!("\x1b[2m{}\x1b[0m ", label),
+            true if gap < SLOW * 10 => format!("\x1b[33m{}\x1b[0m ", label),
+            true => format!("\x1b[1;31m{}\x1b[0m ", label),
+            false => format!("{} ", label),
+        }
+    }
+}
+
+/// Milliseconds under a second and hundredths under a minute, where the differences
+/// between lines usually are; `format_duration` after that.
+fn format_gap(gap: Duration) -> String {
+    if gap < Duration::from_secs(1) {
+        format!("{}ms", gap.as_millis())
+    } else if gap < Duration::from_secs(60) {
+        format!("{:.2}s", gap.as_secs_f64())
+    } else {
+        format_duration(gap)
+    }
+}
@@ -0,0 +1,66 @@
+use anyhow::{anyhow, Result};
+use std::time::Duration;
+
+/// Parse a human duration such as `500ms`, `2s`, `10m`, `1h30m`, `2 days` or a bare number of seconds.
+pub fn parse_duration(input: &str) -> Result<Duration> {
+    let text = input.trim().to_lowercase();
+    if text.is_empty() {
+        return Err(anyhow!("Empty duration"));
+    }
+    if let Ok(secs) = text.parse::<f64>() {
+        return duration_from_secs(secs, input);
+    }
+
+    let mut total = 0f64;
+    let mut rest = text.as_str();
+    while !rest.is_empty() {
+        rest = rest.trim_start();
+        let num_len = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+        if num_len == 0 {
+            return Err(anyhow!("Invalid duration: {}", input));
+        }
+        let value: f64 = rest[..num_len].parse()
+            .map_err(|_| anyhow!("Invalid duration: {}", input))?;
+        rest = rest[num_len..].trim_start();
+
+        let unit_len = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+        let unit = &rest[..unit_len];
+        rest = &rest[unit_len..];
+
+        let scale = match unit {
+            "ms" | "msec" | "msecs" | "millisecond" | "milliseconds" => 0.001,
+            "" | "s" | "sec" | "secs" | "second" | "seconds" => 1.0,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60.0,
+            "h" | "hr" | "hrs" | "hour" | "hours" => 3600.0,
+            "d" | "day" | "days" => 86400.0,
+            "w" | "week" | "weeks" => 604800.0,
+            _ => return Err(anyhow!("Invalid duration unit '{}' in: {}", unit, input)),
+        };
+        total += value * scale;
+    }
+
+    duration_from_secs(total, input)
+}
+
+fn duration_from_secs(secs: f64, input: &str) -> Result<Duration> {
+    if !secs.is_finite() || secs < 0.0 {
+        return Err(anyhow!("Invalid duration: {}", input));
+    }
+    Ok(Duration::from_secs_f64(secs))
+}
+
+/// Format a duration compactly, e.g. `850ms`, `42s`, `3m12s`, `2h05m`, `3d04h`.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs == 0 {
+        format!("{}ms", duration.as_millis())
+    } else if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else if secs < 86400 {
+        format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60)
+    } else {
+        format!("{}d{:02}h", secs / 86400, (secs % 86400) / 3600)
+    }
+}
@@ -0,0 +1,96 @@
+use anyhow::{anyhow, Context, Result};
+use crossterm::{
+    cursor::{Hide, Show},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use regex::Regex;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+
+/// A `path/to/file.rs:123` location mentioned in a log line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceRef {
+    pub path: PathBuf,
+    pub line: usize,
+}
+
+impl std::fmt::Display for SourceRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.path.display(), self.line)
+    }
+}
+
+fn reference_patterns() -> [Regex; 2] {
+    [
+        // src/main.rs:12, /app/server.py:88:5, at handler (lib/x.js:3:14)
+        Regex::new(r"([\w./~-]*[\w-]\.[A-Za-z][A-Za-z0-9]*):(\d+)").unwrap(),
+        // Python: File "app/views.py", line 42
+        Regex::new(r#"File "([^"]+)", line (\d+)"#).unwrap(),
+    ]
+}
+
+/// Every file:line reference in `line` whose file exists, in order of appearance.
+pub fn find_references(line: &str) -> Vec<SourceRef> {
+    let mut found: Vec<(usize, SourceRef)> = Vec::new();
+    for pattern in &reference_patterns() {
+        for caps in pattern.captures_iter(line) {
+            let path = PathBuf::from(&caps[1]);
+            let Ok(number) = caps[2].parse::<usize>() else { continue };
+            if !path.is_file() {
+                continue;
+            }
+            let reference = SourceRef { path, line: number };
+            if !found.iter().any(|(_, r)| *r == reference) {
+                found.push((caps.get(0).map_or(0, |m| m.start()), reference));
+            }
+        }
+    }
+    found.sort_by_key(|(pos, _)| *pos);
+    found.into_iter().map(|(_, r)| r).collect()
+}
+
+/// Open `reference` in $VISUAL / $EDITOR (default vi), suspending the full-screen UI
+/// until the editor exits.
+pub fn open(reference: &SourceRef) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().ok_or_else(|| anyhow!("$EDITOR is empty"))?;
+    let mut command = Command::new(program);
+    command.args(words).args(editor_args(program, reference));
+
+    let status = run_suspended(&mut command)
+        .with_context(|| format!("Failed to start editor '{}'", program))?;
+    if !status.success() {
+        return Err(anyhow!("Editor '{}' exited with {}", program, status));
+    }
+    Ok(())
+}
+
+/// Run an interactive program with the full-screen UI suspended, restoring it afterwards.
+pub fn run_suspended(command: &mut Command) -> io::Result<ExitStatus> {
+    let mut stdout = io::stdout();
+    let _ = execute!(stdout, Show, LeaveAlternateScreen);
+    let _ = disable_raw_mode();
+    let status = command.status();
+    let _ = enable_raw_mode();
+    let _ = execute!(stdout, EnterAlternateScreen, Hide);
+    status
+}
+
+/// How to ask an editor to jump to a line: `+LINE FILE` for the vi/emacs/nano family,
+/// `FILE:LINE` for GUI editors that understand it.
+fn editor_args(program: &str, reference: &SourceRef) -> Vec<String> {
+    let name = Path::new(program)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(program);
+    match name {
+        "code" | "code-insiders" | "codium" => vec!["-g".to_string(), reference.to_string()],
+        "subl" | "zed" | "hx" => vec![reference.to_string()],
+        _ => vec![format!("+{}", reference.line), reference.path.display().to_string()],
+    }
+}
@@ -0,0 +1,133 @@
+use serde_json::json;
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// Lines read but not printed yet that each source remembers the position of. Filtered
+/// out lines are dropped when a later line prints; this bounds a long run of them.
+const MAX_PENDING: usize = 100_000;
+
+/// `--format ndjson`: wraps each record with where it came from (path, byte offset,
+/// line number, host and when ft read it) for log shippers.
+pub struct Envelope {
+    host: String,
+}
+
+/// Where a line starts in its source.
+#[derive(Clone, Copy)]
+pub struct Position {
+    pub offset: u64,
+    /// 1-based
+    pub line: u64,
+}
+
+/// Follows where the lines read from each source start, so the position of a line can be
+/// given when it's printed, for `--format ndjson`, `--line-numbers` and `--byte-offsets`.
+pub struct Positions {
+    cursors: RefCell<HashMap<PathBuf, Cursor>>,
+}
+
+#[derive(Default)]
+struct Cursor {
+    /// (hash of the line, byte offset, line number) of lines read, oldest first
+    pending: VecDeque<(u64, u64, u64)>,
+    next_offset: u64,
+    next_line: u64,
+}
+
+impl Positions {
+    pub fn new() -> Self {
+        Self { cursors: RefCell::new(HashMap::new()) }
+    }
+
+    /// Note a line read from `path`, `bytes` long with its line ending. `offset` is where
+    /// it starts, or None to carry on from the previous line (stdin); reading from 0 starts
+    /// the line count over, as after rotation or truncation.
+    pub fn read(&self, path: &Path, offset: Option<u64>, line: &str, bytes: usize) {
+        let mut cursors = self.cursors.borrow_mut();
+        let cursor = cursors.entry(path.to_path_buf()).or_default();
+        if let Some(offset) = offset {
+            if offset == 0 {
+                cursor.next_line = 0;
+                cursor.pending.clear();
+            }
+            cursor.next_offset = offset;
+        }
+        cursor.next_line += 1;
+        cursor.pending.push_back((hash(line), cursor.next_offset, cursor.next_line));
+        cursor.next_offset += bytes as u64;
+        if cursor.pending.len() > MAX_PENDING {
+            cursor.pending.pop_front();
+        }
+    }
+
+    /// Before printing the last of the lines read, `records`: forget the lines before them,
+    /// so a record isn't given the position of an identical line further up.
+    pub fn align(&self, path: &Path, records: &[String]) {
+        let mut cursors = self.cursors.borrow_mut();
+        let Some(cursor) = cursors.get_mut(path) else {
+            return;
+        };
+        let mut end = cursor.pending.len();
+        for record in records.iter().rev() {
+            let first = hash(record.split('\n').next().unwrap_or_default());
+            match cursor.pending.iter().take(end).rposition(|(h, _, _)| *h == first) {
+                Some(found) => end = found,
+                None => return,
+            }
+        }
+        cursor.pending.drain(..end);
+    }
+
+    /// Where `line`, about to be printed, starts: that of a record's first line, or None
+    /// for lines ft made up, like repeat summaries. Lines read before it were filtered out
+    /// and are forgotten.
+    pub fn take(&self, path: &Path, line: &str) -> Option<Position> {
+        let first = line.split('\n').next().unwrap_or_default();
+        self.cursors.borrow_mut().get_mut(path).and_then(|cursor| {
+            let found = cursor.pending.iter().position(|(h, _, _)| *h == hash(first))?;
+            cursor.pending.drain(..found);
+            cursor.pending.pop_front().map(|(_, offset, line)| Position { offset, line })
+        })
+    }
+}
+
+impl Envelope {
+    pub fn new() -> Self {
+        Self { host: hostname() }
+    }
+
+    /// `record` (one JSON object) wrapped with its metadata; a null position for lines ft
+    /// made up.
+    pub fn wrap(&self, path: &Path, position: Option<Position>, record: &str) -> String {
+        let file = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let captured = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        format!(
+            "{{\"path\":{},\"offset\":{},\"line\":{},\"host\":{},\"captured_at\":{},\"record\":{}}}",
+            json!(file.display().to_string()),
+            json!(position.map(|position| position.offset)),
+            json!(position.map(|position| position.line)),
+            json!(self.host),
+            json!(captured),
+            record,
+        )
+    }
+}
+
+fn hash(line: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    line.trim_end().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// This machine's name, from `$HOSTNAME` or the kernel, for the `host` field.
+pub fn hostname() -> String {
+    std::env::var("HOSTNAME").ok()
+        .or_else(|| std::fs::read_to_string("/proc/sys/kernel/hostname").ok())
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "localhost".to_string())
+}
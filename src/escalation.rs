@@ -0,0 +1,57 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use crate::filter::LogLevel;
+use crate::theme::{Color, EscalationRule};
+
+/// Thresholds used when the theme doesn't define any `escalate:` rules.
+const DEFAULT_THRESHOLDS: [(usize, u8); 3] = [(5, 229), (20, 214), (100, 196)];
+const DEFAULT_WINDOW: Duration = Duration::from_secs(10);
+
+/// Tracks how densely WARN-or-worse lines arrive so bursts can be marked with a
+/// progressively more intense badge.
+pub struct EscalationTracker {
+    window_ms: i64,
+    rules: Vec<EscalationRule>,
+    events: HashMap<LogLevel, VecDeque<i64>>,
+}
+
+impl EscalationTracker {
+    pub fn new(window: Option<Duration>, mut rules: Vec<EscalationRule>) -> Self {
+        if rules.is_empty() {
+            rules = DEFAULT_THRESHOLDS.iter()
+                .map(|&(threshold, color)| EscalationRule { threshold, color: Color::Xterm256(color) })
+                .collect();
+        }
+        rules.sort_by_key(|rule| rule.threshold);
+
+        Self {
+            window_ms: window.unwrap_or(DEFAULT_WINDOW).as_millis() as i64,
+            rules,
+            events: HashMap::new(),
+        }
+    }
+
+    /// Record a line of `level` seen at `time_ms` and return the badge to prefix it
+    /// with once its level's count within the window crosses a threshold.
+    pub fn observe(&mut self, level: LogLevel, time_ms: i64, color: bool) -> Option<String> {
+        if level.priority() > LogLevel::Warning.priority() {
+            return None;
+        }
+
+        let events = self.events.entry(level).or_default();
+        events.push_back(time_ms);
+        let newest = events.iter().copied().max().unwrap_or(time_ms);
+        while events.front().is_some_and(|&t| t < newest - self.window_ms) {
+            events.pop_front();
+        }
+
+        let count = events.len();
+        let rule = self.rules.iter().rev().find(|rule| count >= rule.threshold)?;
+        Some(if color {
+            format!("{}\x1b[38;5;16m ×{} \x1b[0m ", rule.color.to_ansi_bg(), count)
+        } else {
+            format!("[×{}] ", count)
+        })
+    }
+}
@@ -0,0 +1,212 @@
+use anyhow::{anyhow, Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Placeholder file names in the examples, swapped for generated samples by `--run`.
+const SAMPLE_LOG: &str = "app.log";
+const SAMPLE_JSON: &str = "app.jsonl";
+
+struct Example {
+    command: &'static str,
+    about: &'static str,
+}
+
+struct Category {
+    name: &'static str,
+    examples: &'static [Example],
+}
+
+const CATEGORIES: &[Category] = &[
+    Category {
+        name: "Basics",
+        examples: &[
+            Example { command: "ft app.log", about: "Last 10 lines, colorized" },
+            Example { command: "ft -n 50 app.log", about: "Last 50 lines" },
+            Example { command: "ft -f app.log", about: "Follow the file as it grows" },
+            Example { command: "ft /var/log/syslog /var/log/auth.log", about: "Split panes, one per file" },
+            Example { command: "journalctl -f | ft", about: "Colorize any stream" },
+        ],
+    },
+    Category {
+        name: "Filtering",
+        examples: &[
+            Example { command: "ft --level ERROR app.log", about: "ERROR and more severe" },
+            Example { command: "ft --level WARN..ERROR app.log", about: "A severity range" },
+            Example { command: "ft --include nginx --exclude healthcheck app.log", about: "Regex include/exclude" },
+            Example { command: "ft --filter 'level >= WARN and msg =~ \"timeout\"' app.log", about: "Expression over parsed fields" },
+            Example { command: "ft --field 'status>=500' app.jsonl", about: "Filter JSON lines by field" },
+            Example { command: "ft --since '10 minutes ago' app.log", about: "Only recent lines" },
+            Example { command: "ft --focus 'req=(\\w+)' app.log", about: "Follow one request id once it appears" },
+        ],
+    },
+    Category {
+        name: "Shaping output",
+        examples: &[
+            Example { command: "ft --squash-repeats app.log", about: "Collapse repeated lines" },
+            Example { command: "ft --multiline app.log", about: "Keep stack traces with their error" },
+            Example { command: "ft --sample 1/5 app.log", about: "Every 5th line" },
+            Example { command: "ft --hash-field req app.log", about: "Same request id, same color" },
+            Example { command: "ft --escalate app.log", about: "Badge bursts of errors" },
+        ],
+    },
+    Category {
+        name: "Output formats",
+        examples: &[
+            Example { command: "ft --format json app.log", about: "Structured JSON per line" },
+            Example { command: "ft --format csv app.log", about: "CSV for spreadsheets" },
+            Example { command: "ft --no-color app.log", about: "Plain text" },
+        ],
+    },
+    Category {
+        name: "Scripting",
+        examples: &[
+            Example { command: "ft -f -n 0 --level ERROR -m 1 app.log", about: "Wait for the next error, then exit" },
+            Example { command: "ft -m 1 --level ERROR app.log", about: "First matching line of the tail" },
+            Example { command: "ft -f --max-rate 200/s app.log", about: "Cap a firehose at 200 lines/s" },
+        ],
+    },
+];
+
+/// Print the examples, numbered for `--run`, optionally only one category.
+pub fn print(topic: Option<&str>) -> Result<()> {
+    let mut number = 0;
+    let mut shown = false;
+    for category in CATEGORIES {
+        let selected = topic.is_none_or(|t| category.name.to_lowercase().contains(&t.to_lowercase()));
+        if !selected {
+            number += category.examples.len();
+            continue;
+        }
+        shown = true;
+        println!("{}:", category.name);
+        for example in category.examples {
+            number += 1;
+            println!("  {:>2}. {:<60} # {}", number, example.command, example.about);
+        }
+        println!();
+    }
+    if !shown {
+        let names: Vec<&str> = CATEGORIES.iter().map(|c| c.name).collect();
+        return Err(anyhow!("No example category matches '{}' (categories: {})", topic.unwrap_or_default(), names.join(", ")));
+    }
+    println!("Run one against a generated sample log with: ft examples --run N");
+    Ok(())
+}
+
+/// Run example `number` against generated sample logs in a temporary directory.
+pub fn run(number: usize) -> Result<()> {
+    let example = CATEGORIES.iter()
+        .flat_map(|c| c.examples)
+        .nth(number.wrapping_sub(1))
+        .ok_or_else(|| anyhow!("No example {} (see `ft examples`)", number))?;
+
+    let mut args = split_args(example.command)?;
+    let uses_sample = args.iter().any(|a| a == SAMPLE_LOG || a == SAMPLE_JSON);
+    if !uses_sample || args.iter().any(|a| a == "-f") {
+        return Err(anyhow!("Example {} needs a live log or stream; copy it and point it at your own: {}", number, example.command));
+    }
+
+    let dir = std::env::temp_dir().join(format!("ft-examples-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create {:?}", dir))?;
+    write_samples(&dir)?;
+
+    for arg in args.iter_mut() {
+        if arg == SAMPLE_LOG || arg == SAMPLE_JSON {
+            *arg = dir.join(arg.as_str()).to_string_lossy().into_owned();
+        }
+    }
+
+    println!("$ {}", example.command);
+    let exe = std::env::current_exe().context("Failed to locate the ft executable")?;
+    let status = Command::new(exe)
+        .args(&args[1..])
+        .status()
+        .context("Failed to run example")?;
+    let _ = std::fs::remove_dir_all(&dir);
+    if !status.success() {
+        return Err(anyhow!("Example {} exited with {}", number, status));
+    }
+    Ok(())
+}
+
+fn write_samples(dir: &Path) -> Result<()> {
+    let (log, json) = samples();
+    std::fs::write(dir.join(SAMPLE_LOG), log).context("Failed to write sample log")?;
+    std::fs::write(dir.join(SAMPLE_JSON), json).context("Failed to write sample log")?;
+    Ok(())
+}
+
+/// A short, varied plain-text log: levels, services, IPs, request ids and a stack trace.
+pub fn sample_log() -> String {
+    samples().0
+}
+
+/// Matching plain-text and JSON-lines sample logs, timestamped over the last 20 minutes.
+fn samples() -> (String, String) {
+    let now = chrono::Local::now();
+    let mut log = String::new();
+    let mut json = String::new();
+    let events = [
+        ("INFO", "nginx", "GET /index.html 200", 200),
+        ("DEBUG", "api", "cache hit for user 42", 200),
+        ("INFO", "api", "GET /healthcheck 200", 200),
+        ("WARN", "db", "slow query took 1200ms", 200),
+        ("ERROR", "api", "upstream timeout after 30s", 504),
+        ("INFO", "nginx", "POST /login 302", 302),
+        ("ERROR", "db", "connection refused", 500),
+        ("INFO", "api", "GET /healthcheck 200", 200),
+        ("INFO", "api", "GET /healthcheck 200", 200),
+        ("WARN", "nginx", "client closed request 499", 499),
+    ];
+    for i in 0..30usize {
+        let (level, service, message, status) = events[i % events.len()];
+        let ts = now - chrono::Duration::seconds(((30 - i) * 40) as i64);
+        let req = format!("r{:03}", i % 4);
+        log.push_str(&format!("{} {} {}[{}]: 10.0.0.{} req={} {}\n",
+            ts.format("%Y-%m-%dT%H:%M:%S"), level, service, 1000 + i % 3, i % 7 + 1, req, message));
+        if level == "ERROR" {
+            log.push_str("    at handler (src/server.rs:88)\n    at main (src/main.rs:12)\n");
+        }
+        json.push_str(&format!("{{\"time\":\"{}\",\"level\":\"{}\",\"service\":\"{}\",\"status\":{},\"latency_ms\":{},\"msg\":\"{}\"}}\n",
+            ts.format("%Y-%m-%dT%H:%M:%S"), level.to_lowercase(), service, status, 20 + i * 13 % 400, message));
+    }
+    (log, json)
+}
+
+/// Split a command line on whitespace, honoring single and double quotes.
+fn split_args(command: &str) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut in_arg = false;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') => current.extend(chars.next()),
+            (Some(_), c) => current.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err(anyhow!("Unterminated quote in: {}", command));
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
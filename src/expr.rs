@@ -0,0 +1,338 @@
+use anyhow::{anyhow, Context, Result};
+use regex::Regex;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use crate::filter::{detect_log_level, json_path, LogLevel};
+use crate::output::parse_fields;
+use crate::parser::{GenericParser, Parser as LineParser};
+
+/// Fields every input format provides. Others, like a `[parser.NAME]` group or a
+/// dotted path into a JSON line (`user.id`), are looked up as written.
+const FIELDS: &[&str] = &["timestamp", "level", "service", "ip", "status", "message", "raw"];
+
+/// A parsed `--filter` expression such as
+/// `level >= WARN and (service == "nginx" or msg =~ "timeout") and not msg =~ "healthz"`.
+#[derive(Clone)]
+pub struct FilterExpr {
+    root: Node,
+    parser: Arc<dyn LineParser>,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+    Compare { field: String, op: Op, value: String },
+    Matches { field: String, regex: Regex, negate: bool },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Str(String),
+    Op(String),
+    LParen,
+    RParen,
+}
+
+impl FilterExpr {
+    pub fn parse(source: &str) -> Result<Self> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let root = parser.parse_or()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(anyhow!("Unexpected {:?} in filter expression", token));
+        }
+        Ok(Self {
+            root,
+            parser: Arc::new(GenericParser::new()),
+        })
+    }
+
+    /// Read fields with `parser` rather than the generic one.
+    pub fn with_parser(mut self, parser: Arc<dyn LineParser>) -> Self {
+        self.parser = parser;
+        self
+    }
+
+    pub fn matches(&self, line: &str) -> bool {
+        let fields = parse_fields(self.parser.as_ref(), line);
+        self.root.eval(line, &fields)
+    }
+}
+
+impl Node {
+    fn eval(&self, line: &str, fields: &Value) -> bool {
+        match self {
+            Node::And(a, b) => a.eval(line, fields) && b.eval(line, fields),
+            Node::Or(a, b) => a.eval(line, fields) || b.eval(line, fields),
+            Node::Not(inner) => !inner.eval(line, fields),
+            Node::Matches { field, regex, negate } => {
+                let matched = field_value(field, fields).is_some_and(|v| regex.is_match(&v));
+                matched != *negate
+            }
+            Node::Compare { field, op, value } => {
+                if field == "level" {
+                    return compare_level(line, fields, *op, value);
+                }
+                let actual = field_value(field, fields);
+                match op {
+                    Op::Eq => actual.is_some_and(|a| a.eq_ignore_ascii_case(value)),
+                    Op::Ne => !actual.is_some_and(|a| a.eq_ignore_ascii_case(value)),
+                    _ => actual.is_some_and(|a| op.holds(compare_values(&a, value))),
+                }
+            }
+        }
+    }
+}
+
+impl Op {
+    pub fn from_symbol(symbol: &str) -> Option<Op> {
+        match symbol {
+            "==" | "=" => Some(Op::Eq),
+            "!=" => Some(Op::Ne),
+            "<" => Some(Op::Lt),
+            "<=" => Some(Op::Le),
+            ">" => Some(Op::Gt),
+            ">=" => Some(Op::Ge),
+            _ => None,
+        }
+    }
+
+    pub fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Op::Eq => ordering == Ordering::Equal,
+            Op::Ne => ordering != Ordering::Equal,
+            Op::Lt => ordering == Ordering::Less,
+            Op::Le => ordering != Ordering::Greater,
+            Op::Gt => ordering == Ordering::Greater,
+            Op::Ge => ordering != Ordering::Less,
+        }
+    }
+}
+
+/// A field's value as text: a key of the parsed fields, else a dotted path into them.
+fn field_value(field: &str, fields: &Value) -> Option<String> {
+    let key = if field == "status" { "status_code" } else { field };
+    let value = fields.get(key).or_else(|| json_path(fields, key))?;
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Null | Value::Array(_) | Value::Object(_) => None,
+        other => Some(other.to_string()),
+    }
+}
+
+/// Numbers compare numerically, anything else as text.
+pub fn compare_values(actual: &str, expected: &str) -> Ordering {
+    match (actual.parse::<f64>(), expected.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => actual.cmp(expected),
+    }
+}
+
+/// Levels compare by severity, so `level >= WARN` means WARN or worse.
+/// Uses the parsed `level` field, else the same detection as `--level`.
+fn compare_level(line: &str, fields: &Value, op: Op, expected: &str) -> bool {
+    let Some(expected) = LogLevel::from_name(expected) else {
+        return false;
+    };
+    let level = fields["level"].as_str()
+        .and_then(LogLevel::from_name)
+        .or_else(|| detect_log_level(line));
+    match level {
+        // Lower priority numbers are more severe
+        Some(level) => op.holds(expected.priority().cmp(&level.priority())),
+        None => op == Op::Ne,
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => match chars.next() {
+                            Some(escaped) if escaped == c || escaped == '\\' => text.push(escaped),
+                            Some(other) => {
+                                // Keep regex escapes such as \d intact
+                                text.push('\\');
+                                text.push(other);
+                            }
+                            None => return Err(anyhow!("Unterminated string in filter expression")),
+                        },
+                        Some(ch) if ch == c => break,
+                        Some(ch) => text.push(ch),
+                        None => return Err(anyhow!("Unterminated string in filter expression")),
+                    }
+                }
+                tokens.push(Token::Str(text));
+            }
+            '=' | '!' | '<' | '>' | '&' | '|' => {
+                let mut op = String::new();
+                op.push(c);
+                chars.next();
+                if let Some(&next) = chars.peek() {
+                    if matches!((c, next), ('=', '=') | ('=', '~') | ('!', '=') | ('!', '~') | ('<', '=') | ('>', '=') | ('&', '&') | ('|', '|')) {
+                        op.push(next);
+                        chars.next();
+                    }
+                }
+                tokens.push(Token::Op(op));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_whitespace() || "()\"'=!<>&|".contains(ch) {
+                        break;
+                    }
+                    word.push(ch);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat_keyword(&mut self, word: &str, symbol: &str) -> bool {
+        let matched = match self.peek() {
+            Some(Token::Word(w)) => w.eq_ignore_ascii_case(word),
+            Some(Token::Op(op)) => op == symbol,
+            _ => false,
+        };
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn parse_or(&mut self) -> Result<Node> {
+        let mut node = self.parse_and()?;
+        while self.eat_keyword("or", "||") {
+            node = Node::Or(Box::new(node), Box::new(self.parse_and()?));
+        }
+        Ok(node)
+    }
+
+    fn parse_and(&mut self) -> Result<Node> {
+        let mut node = self.parse_not()?;
+        while self.eat_keyword("and", "&&") {
+            node = Node::And(Box::new(node), Box::new(self.parse_not()?));
+        }
+        Ok(node)
+    }
+
+    fn parse_not(&mut self) -> Result<Node> {
+        if self.eat_keyword("not", "!") {
+            return Ok(Node::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Node> {
+        match self.next() {
+            Some(Token::LParen) => {
+                let node = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(node),
+                    _ => Err(anyhow!("Missing ')' in filter expression")),
+                }
+            }
+            Some(Token::Word(name)) => self.parse_comparison(&name),
+            Some(token) => Err(anyhow!("Expected a field name, found {:?}", token)),
+            None => Err(anyhow!("Unexpected end of filter expression")),
+        }
+    }
+
+    fn parse_comparison(&mut self, name: &str) -> Result<Node> {
+        let field = resolve_field(name)?;
+        let op = match self.next() {
+            Some(Token::Op(op)) => op,
+            _ => return Err(anyhow!("Expected an operator after '{}'", name)),
+        };
+        let value = match self.next() {
+            Some(Token::Str(s)) | Some(Token::Word(s)) => s,
+            _ => return Err(anyhow!("Expected a value after '{} {}'", name, op)),
+        };
+
+        let op = match op.as_str() {
+            "=~" | "!~" => {
+                let regex = Regex::new(&value)
+                    .with_context(|| format!("Invalid regex in filter expression: {}", value))?;
+                return Ok(Node::Matches { field, regex, negate: op == "!~" });
+            }
+            other => Op::from_symbol(other)
+                .ok_or_else(|| anyhow!("Unknown operator in filter expression: {}", op))?,
+        };
+        if field == "level" && LogLevel::from_name(&value).is_none() {
+            return Err(anyhow!("Invalid log level: {}", value));
+        }
+        Ok(Node::Compare { field, op, value })
+    }
+}
+
+fn resolve_field(name: &str) -> Result<String> {
+    let lower = name.to_lowercase();
+    let lower = match lower.as_str() {
+        "msg" => "message",
+        "status_code" | "code" => "status",
+        "time" | "ts" => "timestamp",
+        other => other,
+    };
+    if FIELDS.contains(&lower) {
+        return Ok(lower.to_string());
+    }
+    let valid = name.split('.').all(|part| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || "_-@".contains(c)));
+    if !valid {
+        return Err(anyhow!("Invalid field '{}' in filter expression (known: {}, or a field path like user.id)", name, FIELDS.join(", ")));
+    }
+    Ok(name.to_string())
+}
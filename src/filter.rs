@@ -1,14 +1,131 @@
-use anyhow::{Context, Result};
-use regex::Regex;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+use regex::{Regex, RegexBuilder};
+use serde_json::Value;
+use std::cell::Cell;
+use std::sync::{Arc, OnceLock};
+
+use crate::ansi;
+use crate::config::Preset;
+use crate::duration::parse_duration;
+use crate::expr::{compare_values, FilterExpr, Op};
+use crate::output::parse_fields;
+use crate::parser::{GenericParser, Parser};
 
 #[derive(Clone)]
 pub struct LineFilter {
-    include_regex: Option<Regex>,
-    exclude_regex: Option<Regex>,
-    level_filter: Option<LogLevel>,
+    include_regexes: Vec<Regex>,
+    exclude_regexes: Vec<Regex>,
+    match_mode: MatchMode,
+    invert_match: bool,
+    highlight_matches: bool,
+    level_filter: Option<LevelSet>,
+    time_window: Option<TimeWindow>,
+    expression: Option<FilterExpr>,
+    field_conditions: Vec<FieldCondition>,
+    where_conditions: Vec<WhereCondition>,
+    // The `--input-format` parser, whose `level` field --level checks before the keywords
+    level_parser: Option<Arc<dyn Parser>>,
 }
 
+/// The set of severities a level filter lets through, as a bitmask over priorities.
 #[derive(Debug, Clone, Copy)]
+struct LevelSet {
+    mask: u8,
+}
+
+/// `--since`/`--until` bounds, checked against timestamps parsed from each line.
+#[derive(Clone)]
+struct TimeWindow {
+    since: Option<DateTime<Local>>,
+    until: Option<DateTime<Local>>,
+    // Reads the `timestamp` field; `timestamps` turns its text (or the line's) into a time
+    parser: Arc<dyn Parser>,
+    timestamps: GenericParser,
+    // Lines without a timestamp (e.g. stack trace continuations) inherit the last one seen
+    last_seen: Cell<Option<DateTime<Local>>>,
+}
+
+/// A `--field KEY<op>VALUE` condition checked against a JSON log line.
+/// KEY may be a dotted path into nested objects and arrays (`user.id`, `items.0.id`).
+#[derive(Debug, Clone)]
+struct FieldCondition {
+    path: String,
+    op: Op,
+    value: String,
+}
+
+/// A `--where FIELD<op>VALUE` condition on a value found anywhere in a line: a named
+/// capture group of an --include pattern, a JSON field, a `key=value` / `key: value`
+/// pair, or a field the input format's parser extracts (`status_code`, `ip`, `level`, ...).
+#[derive(Clone)]
+struct WhereCondition {
+    condition: FieldCondition,
+    key_value: Regex,
+    parser: Arc<dyn Parser>,
+}
+
+/// Raw filter settings as given on the command line.
+#[derive(Debug, Clone, Default)]
+pub struct FilterOptions {
+    pub includes: Vec<String>,
+    pub excludes: Vec<String>,
+    pub match_mode: Option<String>,
+    pub level: Option<String>,
+    pub ignore_case: bool,
+    pub fixed_strings: bool,
+    pub invert_match: bool,
+    pub highlight_matches: bool,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub level_exact: bool,
+    pub level_invert: bool,
+    pub expression: Option<String>,
+    pub fields: Vec<String>,
+    pub wheres: Vec<String>,
+}
+
+impl FilterOptions {
+    /// Merge a preset in: patterns and field conditions are added, expressions are
+    /// and-ed together, and single settings fill in only what is still unset.
+    fn apply_preset(&mut self, preset: &Preset) {
+        self.includes.extend(preset.include.iter().cloned());
+        self.excludes.extend(preset.exclude.iter().cloned());
+        self.fields.extend(preset.fields.iter().cloned());
+        self.expression = match (self.expression.take(), &preset.filter) {
+            (Some(own), Some(other)) => Some(format!("({}) and ({})", own, other)),
+            (own, other) => own.or_else(|| other.clone()),
+        };
+        if self.match_mode.is_none() {
+            self.match_mode = preset.match_mode.clone();
+        }
+        if self.level.is_none() {
+            self.level = preset.level.clone();
+            self.level_exact |= preset.level_exact;
+            self.level_invert |= preset.level_invert;
+        }
+        self.ignore_case |= preset.ignore_case;
+    }
+}
+
+/// How multiple include patterns combine. Excludes always hide a line on any match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchMode {
+    Any,
+    All,
+}
+
+impl MatchMode {
+    fn from_str(mode: &str) -> Option<MatchMode> {
+        match mode.to_lowercase().as_str() {
+            "any" | "or" => Some(MatchMode::Any),
+            "all" | "and" => Some(MatchMode::All),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum LogLevel {
     Emergency,
     Alert,
@@ -21,7 +138,13 @@ pub enum LogLevel {
 }
 
 impl LogLevel {
-    fn from_str(level: &str) -> Option<LogLevel> {
+    /// Every level, most severe first
+    pub const ALL: [LogLevel; 8] = [
+        LogLevel::Emergency, LogLevel::Alert, LogLevel::Critical, LogLevel::Error,
+        LogLevel::Warning, LogLevel::Notice, LogLevel::Info, LogLevel::Debug,
+    ];
+
+    pub fn from_name(level: &str) -> Option<LogLevel> {
         match level.to_uppercase().as_str() {
             "EMERG" | "EMERGENCY" => Some(LogLevel::Emergency),
             "ALERT" => Some(LogLevel::Alert),
@@ -35,7 +158,27 @@ impl LogLevel {
         }
     }
 
-    fn priority(&self) -> u8 {
+    /// `from_name` for command-line values, with an error naming the levels.
+    pub fn parse(name: &str) -> Result<LogLevel> {
+        Self::from_name(name.trim())
+            .ok_or_else(|| anyhow!("Invalid log level '{}': expected EMERG, ALERT, CRIT, ERROR, WARN, NOTICE, INFO or DEBUG", name))
+    }
+
+    /// The words a line names this level with, as `from_name` accepts them.
+    pub fn keywords(&self) -> &'static [&'static str] {
+        match self {
+            LogLevel::Emergency => &["EMERG", "EMERGENCY"],
+            LogLevel::Alert => &["ALERT"],
+            LogLevel::Critical => &["CRIT", "CRITICAL"],
+            LogLevel::Error => &["ERR", "ERROR"],
+            LogLevel::Warning => &["WARN", "WARNING"],
+            LogLevel::Notice => &["NOTICE"],
+            LogLevel::Info => &["INFO"],
+            LogLevel::Debug => &["DEBUG", "TRACE"],
+        }
+    }
+
+    pub fn priority(&self) -> u8 {
         match self {
             LogLevel::Emergency => 0,
             LogLevel::Alert => 1,
@@ -49,102 +192,465 @@ impl LogLevel {
     }
 }
 
+impl LevelSet {
+    /// Parse a level spec: `ERROR` (that level and more severe, or only it when `exact`),
+    /// `WARN..ERROR` (an inclusive range), or a comma list of levels/ranges (`DEBUG,NOTICE`).
+    fn parse(spec: &str, exact: bool) -> Result<Self> {
+        let items: Vec<&str> = spec.split(',').map(str::trim).filter(|s| !s.is_empty()).collect();
+        if items.is_empty() {
+            return Err(anyhow!("Invalid log level: {}", spec));
+        }
+
+        let parse_level = |name: &str| {
+            LogLevel::from_name(name).with_context(|| format!("Invalid log level: {}", name))
+        };
+
+        let mut mask = 0u8;
+        for item in &items {
+            if let Some((from, to)) = item.split_once("..") {
+                let a = parse_level(from.trim())?.priority();
+                let b = parse_level(to.trim())?.priority();
+                for p in a.min(b)..=a.max(b) {
+                    mask |= 1 << p;
+                }
+            } else if exact || items.len() > 1 {
+                mask |= 1 << parse_level(item)?.priority();
+            } else {
+                // Show messages at this level or higher priority (lower number)
+                for p in 0..=parse_level(item)?.priority() {
+                    mask |= 1 << p;
+                }
+            }
+        }
+
+        Ok(Self { mask })
+    }
+
+    fn inverted(self) -> Self {
+        Self { mask: !self.mask }
+    }
+
+    fn contains(&self, level: LogLevel) -> bool {
+        self.mask & (1 << level.priority()) != 0
+    }
+}
+
 impl LineFilter {
-    pub fn new(
-        include: Option<String>,
-        exclude: Option<String>,
-        level: Option<String>,
-    ) -> Result<Self> {
-        let include_regex = if let Some(pattern) = include {
-            Some(Regex::new(&pattern).context("Invalid include regex pattern")?)
+    /// Build a filter from command-line options combined with named presets.
+    pub fn with_presets(mut options: FilterOptions, presets: &[&Preset]) -> Result<Self> {
+        for preset in presets {
+            options.apply_preset(preset);
+        }
+        Self::new(options)
+    }
+
+    pub fn new(options: FilterOptions) -> Result<Self> {
+        let include_regexes = options.includes.iter()
+            .map(|pattern| {
+                Self::compile_pattern(pattern, &options)
+                    .with_context(|| format!("Invalid include regex pattern: {}", pattern))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let exclude_regexes = options.excludes.iter()
+            .map(|pattern| {
+                Self::compile_pattern(pattern, &options)
+                    .with_context(|| format!("Invalid exclude regex pattern: {}", pattern))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let match_mode = if let Some(mode) = options.match_mode {
+            MatchMode::from_str(&mode)
+                .with_context(|| format!("Invalid match mode: {} (expected any or all)", mode))?
         } else {
-            None
+            MatchMode::Any
         };
 
-        let exclude_regex = if let Some(pattern) = exclude {
-            Some(Regex::new(&pattern).context("Invalid exclude regex pattern")?)
+        let level_filter = if let Some(level_str) = &options.level {
+            let set = LevelSet::parse(level_str, options.level_exact)?;
+            Some(if options.level_invert { set.inverted() } else { set })
         } else {
             None
         };
 
-        let level_filter = if let Some(level_str) = level {
-            LogLevel::from_str(&level_str)
-                .with_context(|| format!("Invalid log level: {}", level_str))?
-                .into()
+        let since = options.since.as_deref()
+            .map(|spec| parse_time_point(spec).context("Invalid --since value"))
+            .transpose()?;
+        let until = options.until.as_deref()
+            .map(|spec| parse_time_point(spec).context("Invalid --until value"))
+            .transpose()?;
+        let time_window = if since.is_some() || until.is_some() {
+            Some(TimeWindow {
+                since,
+                until,
+                parser: Arc::new(GenericParser::new()),
+                timestamps: GenericParser::new(),
+                last_seen: Cell::new(None),
+            })
         } else {
             None
         };
 
+        let expression = options.expression.as_deref()
+            .map(|source| FilterExpr::parse(source).context("Invalid --filter expression"))
+            .transpose()?;
+
+        let field_conditions = options.fields.iter()
+            .map(|spec| FieldCondition::parse(spec))
+            .collect::<Result<Vec<_>>>()?;
+
+        let where_conditions = options.wheres.iter()
+            .map(|spec| WhereCondition::parse(spec))
+            .collect::<Result<Vec<_>>>()?;
+
         Ok(Self {
-            include_regex,
-            exclude_regex,
+            include_regexes,
+            exclude_regexes,
+            match_mode,
+            invert_match: options.invert_match,
+            highlight_matches: options.highlight_matches,
             level_filter,
+            time_window,
+            expression,
+            field_conditions,
+            where_conditions,
+            level_parser: None,
         })
     }
 
+    /// Read `--filter` and `--where` fields and `--since`/`--until` timestamps with `parser`
+    /// rather than the generic one, and take --level from the level it parses when it's
+    /// an `--input-format` rather than `auto`.
+    pub fn with_parser(mut self, parser: Arc<dyn Parser>) -> Self {
+        self.level_parser = (parser.name() != "auto").then(|| parser.clone());
+        if let Some(window) = &mut self.time_window {
+            window.parser = parser.clone();
+        }
+        self.expression = self.expression.map(|expression| expression.with_parser(parser.clone()));
+        for condition in &mut self.where_conditions {
+            condition.parser = parser.clone();
+        }
+        self
+    }
+
+    /// Compile an include/exclude pattern, honoring --ignore-case and --fixed-strings.
+    fn compile_pattern(pattern: &str, options: &FilterOptions) -> Result<Regex> {
+        let source = if options.fixed_strings {
+            regex::escape(pattern)
+        } else {
+            pattern.to_string()
+        };
+        Ok(RegexBuilder::new(&source)
+            .case_insensitive(options.ignore_case)
+            .build()?)
+    }
+
     pub fn should_show_line(&self, line: &str) -> bool {
-        // Check exclude pattern first (most restrictive)
-        if let Some(exclude_regex) = &self.exclude_regex {
-            if exclude_regex.is_match(line) {
+        // Check exclude patterns first (most restrictive)
+        if self.exclude_regexes.iter().any(|re| re.is_match(line)) {
+            return false;
+        }
+
+        // Check include patterns (--invert-match shows the lines they would hide)
+        if !self.include_regexes.is_empty() && !self.highlight_matches {
+            let included = match self.match_mode {
+                MatchMode::Any => self.include_regexes.iter().any(|re| re.is_match(line)),
+                MatchMode::All => self.include_regexes.iter().all(|re| re.is_match(line)),
+            };
+            if included == self.invert_match {
                 return false;
             }
         }
 
-        // Check include pattern
-        if let Some(include_regex) = &self.include_regex {
-            if !include_regex.is_match(line) {
+        // Check log level filter
+        if let Some(levels) = self.level_filter {
+            if !self.line_matches_level(line, levels) {
                 return false;
             }
         }
 
-        // Check log level filter
-        if let Some(target_level) = self.level_filter {
-            if !self.line_matches_level(line, target_level) {
+        // Check time window
+        if let Some(window) = &self.time_window {
+            if !window.contains(line) {
                 return false;
             }
         }
 
+        // Check filter expression
+        if let Some(expression) = &self.expression {
+            if !expression.matches(line) {
+                return false;
+            }
+        }
+
+        // Check JSON field conditions; lines that are not JSON objects never match
+        if !self.field_conditions.is_empty() {
+            match serde_json::from_str::<Value>(line) {
+                Ok(json) if json.is_object() => {
+                    if !self.field_conditions.iter().all(|condition| condition.matches(&json)) {
+                        return false;
+                    }
+                }
+                _ => return false,
+            }
+        }
+
+        // Check --where conditions against captured and parsed fields
+        if !self.where_conditions.iter().all(|condition| condition.matches(line, &self.include_regexes)) {
+            return false;
+        }
+
         true
     }
 
-    fn line_matches_level(&self, line: &str, target_level: LogLevel) -> bool {
-        let detected_level = self.detect_log_level(line);
-        
+    fn line_matches_level(&self, line: &str, levels: LevelSet) -> bool {
+        // A line the input format parses has the level it gives, or none; keywords are only
+        // for lines it can't parse
+        let detected_level = match self.level_parser.as_ref().and_then(|parser| parser.parse(&ansi::strip(line))) {
+            Some(fields) => fields["level"].as_str().and_then(LogLevel::from_name),
+            None => detect_log_level(line),
+        };
+
         if let Some(detected) = detected_level {
-            // Show messages at target level or higher priority (lower number)
-            detected.priority() <= target_level.priority()
+            levels.contains(detected)
         } else {
-            // If no level detected, show for DEBUG and INFO levels only
-            matches!(target_level, LogLevel::Debug | LogLevel::Info)
-        }
-    }
-
-    fn detect_log_level(&self, line: &str) -> Option<LogLevel> {
-        let line_upper = line.to_uppercase();
-        
-        // Check for common log level patterns
-        if line_upper.contains("EMERG") || line_upper.contains("EMERGENCY") {
-            Some(LogLevel::Emergency)
-        } else if line_upper.contains("ALERT") {
-            Some(LogLevel::Alert)
-        } else if line_upper.contains("CRIT") || line_upper.contains("CRITICAL") {
-            Some(LogLevel::Critical)
-        } else if line_upper.contains("ERROR") || line_upper.contains("ERR") {
-            Some(LogLevel::Error)
-        } else if line_upper.contains("WARN") || line_upper.contains("WARNING") {
-            Some(LogLevel::Warning)
-        } else if line_upper.contains("NOTICE") {
-            Some(LogLevel::Notice)
-        } else if line_upper.contains("INFO") {
-            Some(LogLevel::Info)
-        } else if line_upper.contains("DEBUG") || line_upper.contains("TRACE") {
-            Some(LogLevel::Debug)
-        } else {
-            None
+            // If no level detected, show only when INFO or DEBUG lines are wanted
+            levels.contains(LogLevel::Info) || levels.contains(LogLevel::Debug)
         }
     }
 
+    /// The --include patterns to emphasize when `--highlight-matches` keeps every line.
+    pub fn highlight_patterns(&self) -> Option<&[Regex]> {
+        self.highlight_matches.then_some(self.include_regexes.as_slice())
+    }
+
     pub fn is_active(&self) -> bool {
-        self.include_regex.is_some() || self.exclude_regex.is_some() || self.level_filter.is_some()
+        !self.include_regexes.is_empty() || !self.exclude_regexes.is_empty() || self.level_filter.is_some()
+            || self.expression.is_some() || !self.field_conditions.is_empty() || !self.where_conditions.is_empty()
+    }
+}
+
+/// Detect the severity of a line from the level keywords it contains.
+pub fn detect_log_level(line: &str) -> Option<LogLevel> {
+    let line = &*ansi::strip(line);
+    if let Some(level) = json_level(line) {
+        return Some(level);
+    }
+    let line_upper = line.to_uppercase();
+    
+    // Check for common log level patterns
+    if line_upper.contains("EMERG") || line_upper.contains("EMERGENCY") {
+        Some(LogLevel::Emergency)
+    } else if line_upper.contains("ALERT") {
+        Some(LogLevel::Alert)
+    } else if line_upper.contains("CRIT") || line_upper.contains("CRITICAL") {
+        Some(LogLevel::Critical)
+    } else if line_upper.contains("ERROR") || line_upper.contains("ERR") {
+        Some(LogLevel::Error)
+    } else if line_upper.contains("WARN") || line_upper.contains("WARNING") {
+        Some(LogLevel::Warning)
+    } else if line_upper.contains("NOTICE") {
+        Some(LogLevel::Notice)
+    } else if line_upper.contains("INFO") {
+        Some(LogLevel::Info)
+    } else if line_upper.contains("DEBUG") || line_upper.contains("TRACE") {
+        Some(LogLevel::Debug)
+    } else {
+        None
+    }
+}
+
+/// The `level` field (or `severity`, `lvl`...) of a JSON line, which names the line's
+/// level outright where a keyword elsewhere in it might not.
+fn json_level(line: &str) -> Option<LogLevel> {
+    static LEVEL_FIELD: OnceLock<Regex> = OnceLock::new();
+    let line = crate::parser::cri_payload(line).unwrap_or(line);
+    if !line.trim_start().starts_with('{') {
+        return None;
+    }
+    let level_field = LEVEL_FIELD.get_or_init(|| {
+        Regex::new(r#""(?i:level|lvl|severity|loglevel|log_level)"\s*:\s*"([^"]*)""#).unwrap()
+    });
+    LogLevel::from_name(level_field.captures(line)?[1].trim())
+}
+
+/// Follow a dotted path (`user.id`, `tags.0`) into nested objects and arrays.
+pub fn json_path<'a>(json: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(json, |current, key| match current {
+        Value::Object(map) => map.get(key),
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    })
+}
+
+impl FieldCondition {
+    /// Parse `KEY=VALUE`, `KEY!=VALUE`, `KEY>=VALUE`, `KEY<VALUE` and so on.
+    fn parse(spec: &str) -> Result<Self> {
+        let op_start = spec.find(['=', '!', '<', '>'])
+            .with_context(|| format!("Invalid field filter, expected KEY=VALUE: {}", spec))?;
+        let (key, rest) = spec.split_at(op_start);
+        let op_len = if rest[1..].starts_with('=') { 2 } else { 1 };
+        let (symbol, value) = rest.split_at(op_len);
+
+        let op = Op::from_symbol(symbol)
+            .with_context(|| format!("Invalid operator in field filter: {}", spec))?;
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(anyhow!("Invalid field filter, missing key: {}", spec));
+        }
+
+        Ok(Self {
+            path: key.to_string(),
+            op,
+            value: value.trim().to_string(),
+        })
+    }
+
+    fn matches(&self, json: &Value) -> bool {
+        let Some(found) = json_path(json, &self.path) else {
+            return self.op == Op::Ne;
+        };
+        let actual = match found {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        self.holds_for(&actual)
+    }
+
+    /// Compare a value found for this condition's key.
+    fn holds_for(&self, actual: &str) -> bool {
+        let ordering = compare_values(actual, &self.value);
+        match self.op {
+            // Ordering comparisons only make sense between numbers
+            Op::Lt | Op::Le | Op::Gt | Op::Ge if actual.parse::<f64>().is_err() || self.value.parse::<f64>().is_err() => false,
+            op => op.holds(ordering),
+        }
+    }
+}
+
+impl WhereCondition {
+    /// Parse `FIELD OP VALUE`, e.g. `status_code >= 500` or `duration_ms>1000`.
+    fn parse(spec: &str) -> Result<Self> {
+        let mut condition = FieldCondition::parse(spec)
+            .with_context(|| format!("Invalid --where condition: {}", spec))?;
+        condition.value = condition.value.trim_matches(|c| c == '"' || c == '\'').to_string();
+        let key = regex::escape(&condition.path);
+        let key_value = Regex::new(&format!(r#"(?:^|[\s,;{{(\[])"?{}"?\s*[=:]\s*"?([^\s,;"}})\]]+)"#, key))
+            .with_context(|| format!("Invalid --where field: {}", spec))?;
+        Ok(Self {
+            condition,
+            key_value,
+            parser: Arc::new(GenericParser::new()),
+        })
     }
-}
\ No newline at end of file
+
+    fn matches(&self, line: &str, include_regexes: &[Regex]) -> bool {
+        match self.lookup(line, include_regexes) {
+            Some(actual) => self.condition.holds_for(numeric_prefix(&actual).unwrap_or(&actual)),
+            None => self.condition.op == Op::Ne,
+        }
+    }
+
+    fn lookup(&self, line: &str, include_regexes: &[Regex]) -> Option<String> {
+        let key = &self.condition.path;
+
+        // Named capture groups in --include patterns, e.g. (?P<duration_ms>\d+)ms
+        for re in include_regexes {
+            if let Some(m) = re.captures(line).and_then(|caps| caps.name(key)) {
+                return Some(m.as_str().to_string());
+            }
+        }
+
+        if let Ok(json) = serde_json::from_str::<Value>(line) {
+            if let Some(value) = json_path(&json, key) {
+                return Some(match value {
+                    Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                });
+            }
+        }
+
+        if let Some(caps) = self.key_value.captures(line) {
+            return Some(caps[1].to_string());
+        }
+
+        let parsed_key = match key.as_str() {
+            "status" | "code" => "status_code",
+            "msg" => "message",
+            other => other,
+        };
+        json_path(&parse_fields(self.parser.as_ref(), line), parsed_key)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    }
+}
+
+/// The leading number of a value such as `1234ms` or `0.25s`, so units don't
+/// stop a numeric comparison.
+fn numeric_prefix(value: &str) -> Option<&str> {
+    let end = value
+        .char_indices()
+        .take_while(|(i, c)| c.is_ascii_digit() || *c == '.' || (*i == 0 && (*c == '-' || *c == '+')))
+        .last()
+        .map(|(i, c)| i + c.len_utf8())?;
+    let number = &value[..end];
+    number.parse::<f64>().ok().map(|_| number)
+}
+
+impl TimeWindow {
+    fn contains(&self, line: &str) -> bool {
+        let parsed = self.parser.parse(line)
+            .and_then(|fields| fields["timestamp"].as_str().and_then(|text| self.timestamps.parse_timestamp(text)));
+        let ts = match parsed.or_else(|| self.timestamps.parse_timestamp(line)) {
+            Some(ts) => {
+                self.last_seen.set(Some(ts));
+                ts
+            }
+            None => match self.last_seen.get() {
+                Some(ts) => ts,
+                None => return true,
+            },
+        };
+        self.since.is_none_or(|since| ts >= since) && self.until.is_none_or(|until| ts <= until)
+    }
+}
+
+/// Parse a `--since`/`--until` value: `now`, `today`, `yesterday`, a relative
+/// `10 minutes ago` / `-10m`, or an absolute date/time such as `2024-05-01T00:00`.
+fn parse_time_point(spec: &str) -> Result<DateTime<Local>> {
+    let spec = spec.trim();
+    let now = Local::now();
+    let midnight = |date: NaiveDate| {
+        Local.from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap()).earliest()
+    };
+
+    match spec.to_lowercase().as_str() {
+        "now" => return Ok(now),
+        "today" => return midnight(now.date_naive()).context("Invalid local date"),
+        "yesterday" => {
+            let date = now.date_naive().pred_opt().context("Invalid local date")?;
+            return midnight(date).context("Invalid local date");
+        }
+        _ => {}
+    }
+
+    let relative = spec.strip_suffix("ago").map(str::trim).or_else(|| spec.strip_prefix('-'));
+    if let Some(relative) = relative {
+        let duration = parse_duration(relative)?;
+        return Ok(now - chrono::Duration::from_std(duration)?);
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(spec) {
+        return Ok(dt.with_timezone(&Local));
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(spec, format) {
+            return Local.from_local_datetime(&naive).earliest().context("Invalid local time");
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(spec, "%Y-%m-%d") {
+        return midnight(date).context("Invalid local date");
+    }
+
+    Err(anyhow!("Unrecognized time: {} (try \"10 minutes ago\" or 2024-05-01T00:00)", spec))
+}
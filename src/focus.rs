@@ -0,0 +1,72 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+
+#[derive(Debug, Clone)]
+enum FocusState {
+    /// Waiting for the pattern to match; everything is shown.
+    Armed,
+    /// Only lines mentioning the captured term, as a whole token, are shown.
+    Engaged(String, Regex),
+    /// Focus was released by the user; everything is shown.
+    Released,
+}
+
+/// `--focus PATTERN`: once PATTERN first matches, hide every line that does not
+/// mention the matched term (or its first capture group, e.g. a request id).
+pub struct FocusFilter {
+    pattern: Regex,
+    state: FocusState,
+}
+
+impl FocusFilter {
+    pub fn new(pattern: &str) -> Result<Self> {
+        let pattern = Regex::new(pattern)
+            .with_context(|| format!("Invalid focus regex pattern: {}", pattern))?;
+        Ok(Self {
+            pattern,
+            state: FocusState::Armed,
+        })
+    }
+
+    /// Whether `line` should be shown, engaging focus on the first match.
+    pub fn admits(&mut self, line: &str) -> bool {
+        match &self.state {
+            FocusState::Engaged(_, token) => token.is_match(line),
+            FocusState::Released => true,
+            FocusState::Armed => {
+                if let Some(caps) = self.pattern.captures(line) {
+                    let term = caps.get(1).or_else(|| caps.get(0)).map(|m| m.as_str()).unwrap_or_default();
+                    if let Some(token) = token_regex(term) {
+                        self.state = FocusState::Engaged(term.to_string(), token);
+                    }
+                }
+                true
+            }
+        }
+    }
+
+    /// The term currently focused on, if focus is engaged.
+    pub fn term(&self) -> Option<&str> {
+        match &self.state {
+            FocusState::Engaged(term, _) => Some(term),
+            _ => None,
+        }
+    }
+
+    /// Release an engaged focus, or re-arm a released one.
+    pub fn toggle(&mut self) {
+        self.state = match self.state {
+            FocusState::Engaged(..) => FocusState::Released,
+            _ => FocusState::Armed,
+        };
+    }
+}
+
+/// Matches `term` only as a whole token, so a short id like `2` doesn't match every
+/// timestamp. Word boundaries only go on ends of the term that are word characters.
+fn token_regex(term: &str) -> Option<Regex> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let start = if term.starts_with(is_word) { r"\b" } else { "" };
+    let end = if term.ends_with(is_word) { r"\b" } else { "" };
+    Regex::new(&format!("{}{}{}", start, regex::escape(term), end)).ok()
+}
@@ -0,0 +1,179 @@
+use anyhow::{anyhow, Context, Result};
+use std::io::Write;
+use std::net::{TcpStream, UdpSocket};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::envelope::hostname;
+use crate::filter::{detect_log_level, LogLevel};
+
+/// `user` facility: these are application logs being passed on
+const FACILITY: u8 = 1;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long to wait before connecting again after the collector went away
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+/// The largest UDP message collectors are required to take (RFC 5426 allows more, but
+/// 2048 is the safe size on the wire); longer lines are cut
+const MAX_UDP_MESSAGE: usize = 2048;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transport {
+    Udp,
+    Tcp,
+    Tls,
+}
+
+/// `--forward`: sends each line ft shows to a syslog collector as an RFC 5424 message.
+pub struct Forwarder {
+    transport: Transport,
+    host: String,
+    port: u16,
+    hostname: String,
+    connection: Option<Connection>,
+    /// When a failed send may try to connect again
+    retry_at: Option<Instant>,
+}
+
+enum Connection {
+    Udp(UdpSocket),
+    Tcp(TcpStream),
+    #[cfg(feature = "tls")]
+    Tls(Box<native_tls::TlsStream<TcpStream>>),
+}
+
+impl Forwarder {
+    /// Parse `syslog://host[:port]` (UDP), `syslog+tcp://...` or `syslog+tls://...`.
+    /// The port defaults to 514, or 6514 over TLS.
+    pub fn parse(url: &str) -> Result<Self> {
+        let (scheme, address) = url.split_once("://")
+            .ok_or_else(|| anyhow!("Invalid --forward '{}': expected syslog://HOST[:PORT]", url))?;
+        let transport = match scheme.to_lowercase().as_str() {
+            "syslog" | "syslog+udp" | "udp" => Transport::Udp,
+            "syslog+tcp" | "tcp" => Transport::Tcp,
+            "syslog+tls" | "tls" => Transport::Tls,
+            _ => return Err(anyhow!("Invalid --forward scheme '{}': expected syslog, syslog+tcp or syslog+tls", scheme)),
+        };
+        if transport == Transport::Tls && !cfg!(feature = "tls") {
+            return Err(anyhow!("This ft was built without TLS support (the `tls` feature)"));
+        }
+        let address = address.trim_end_matches('/');
+        let default_port = if transport == Transport::Tls { 6514 } else { 514 };
+        // `[::1]:514` and `host:514`; a bare IPv6 address has no port
+        let (host, port) = match address.rsplit_once(':') {
+            Some((host, port)) if !host.contains(':') || host.ends_with(']') => {
+                let port = port.parse().with_context(|| format!("Invalid --forward port '{}'", port))?;
+                (host, port)
+            }
+            _ => (address, default_port),
+        };
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        if host.is_empty() {
+            return Err(anyhow!("Invalid --forward '{}': no host", url));
+        }
+        Ok(Self {
+            transport,
+            host: host.to_string(),
+            port,
+            hostname: hostname(),
+            connection: None,
+            retry_at: None,
+        })
+    }
+
+    /// Connect now, so a wrong address is reported before tailing starts.
+    pub fn connect(&mut self) -> Result<()> {
+        self.connection = Some(self.open()?);
+        Ok(())
+    }
+
+    /// Send `line`, read from `source`, with a severity from its detected level. A collector
+    /// that went away is reported once and retried every few seconds; lines are dropped meanwhile.
+    pub fn send(&mut self, source: &Path, line: &str) {
+        if self.connection.is_none() {
+            if self.retry_at.is_some_and(|at| Instant::now() < at) {
+                return;
+            }
+            match self.open() {
+                Ok(connection) => {
+                    self.connection = Some(connection);
+                    if self.retry_at.take().is_some() {
+                        eprintln!("ft: --forward reconnected to {}:{}", self.host, self.port);
+                    }
+                }
+                Err(e) => {
+                    self.retry_at = Some(Instant::now() + RETRY_INTERVAL);
+                    eprintln!("ft: --forward: {:#}", e);
+                    return;
+                }
+            }
+        }
+        let message = self.message(source, line);
+        let Some(connection) = self.connection.as_mut() else {
+            return;
+        };
+        let sent = match connection {
+            Connection::Udp(socket) => {
+                let mut end = message.len().min(MAX_UDP_MESSAGE);
+                while !message.is_char_boundary(end) {
+                    end -= 1;
+                }
+                socket.send(&message.as_bytes()[..end]).map(|_| ())
+            }
+            // RFC 6587 octet counting, so multiline records arrive whole
+            Connection::Tcp(stream) => write!(stream, "{} {}", message.len(), message),
+            #[cfg(feature = "tls")]
+            Connection::Tls(stream) => write!(stream, "{} {}", message.len(), message),
+        };
+        if let Err(e) = sent {
+            eprintln!("ft: --forward to {}:{} failed: {}", self.host, self.port, e);
+            self.connection = None;
+            self.retry_at = Some(Instant::now() + RETRY_INTERVAL);
+        }
+    }
+
+    fn open(&self) -> Result<Connection> {
+        let address = (self.host.as_str(), self.port);
+        let tcp = || -> Result<TcpStream> {
+            let addr = std::net::ToSocketAddrs::to_socket_addrs(&address)?
+                .next()
+                .ok_or_else(|| anyhow!("{} doesn't resolve", self.host))?;
+            Ok(TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)?)
+        };
+        let connection = match self.transport {
+            Transport::Udp => {
+                let socket = UdpSocket::bind(("0.0.0.0", 0)).or_else(|_| UdpSocket::bind(("::", 0)))?;
+                socket.connect(address)?;
+                Connection::Udp(socket)
+            }
+            Transport::Tcp => Connection::Tcp(tcp()?),
+            #[cfg(feature = "tls")]
+            Transport::Tls => {
+                let connector = native_tls::TlsConnector::new()?;
+                let stream = connector.connect(&self.host, tcp()?)
+                    .map_err(|e| anyhow!("TLS handshake failed: {}", e))?;
+                Connection::Tls(Box::new(stream))
+            }
+            #[cfg(not(feature = "tls"))]
+            Transport::Tls => unreachable!("rejected by Forwarder::parse"),
+        };
+        Ok(connection)
+    }
+
+    /// `<PRI>1 TIMESTAMP HOSTNAME APP-NAME PROCID MSGID SD MSG`, with the source's file
+    /// name as MSGID.
+    fn message(&self, source: &Path, line: &str) -> String {
+        let severity = detect_log_level(line).unwrap_or(LogLevel::Notice) as u8;
+        let timestamp = chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Micros, false);
+        let msgid = source.file_name()
+            .map(|name| header_field(&name.to_string_lossy(), 32))
+            .unwrap_or_else(|| "-".to_string());
+        format!("<{}>1 {} {} ft {} {} - {}",
+            FACILITY * 8 + severity, timestamp, header_field(&self.hostname, 255), std::process::id(), msgid, line)
+    }
+}
+
+/// A header field: printable ASCII without spaces, at most `max` long, or `-` (NILVALUE).
+fn header_field(text: &str, max: usize) -> String {
+    let field: String = text.chars().filter(|c| c.is_ascii_graphic()).take(max).collect();
+    if field.is_empty() { "-".to_string() } else { field }
+}
@@ -0,0 +1,62 @@
+/// Points for each matched character, and the bonuses that make some matches better
+const MATCH: i64 = 16;
+const CONSECUTIVE: i64 = 12;
+const WORD_START: i64 = 10;
+/// Taken off for each character skipped between the first and last match
+const GAP: i64 = 1;
+
+/// fzf-style matching: whether `pattern`'s characters appear in `text` in order, and if
+/// so how well and at which character positions. Matches in runs and at word starts score
+/// higher, spread-out ones lower. Case is ignored unless the pattern has capitals.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let pattern: Vec<char> = pattern.chars().filter(|c| !c.is_whitespace()).collect();
+    if pattern.is_empty() {
+        return Some((0, Vec::new()));
+    }
+    let smart_case = !pattern.iter().any(|c| c.is_uppercase());
+    let chars: Vec<char> = text.chars().collect();
+    let same = |a: char, b: char| match smart_case {
+        true => a.to_lowercase().eq(b.to_lowercase()),
+        false => a == b,
+    };
+
+    // The first place the whole pattern fits, going forward...
+    let mut p = 0;
+    let mut end = None;
+    for (i, &c) in chars.iter().enumerate() {
+        if same(c, pattern[p]) {
+            p += 1;
+            if p == pattern.len() {
+                end = Some(i);
+                break;
+            }
+        }
+    }
+    let end = end?;
+    // ...then back from where it ended, for the tightest window ending there
+    let mut positions = vec![0; pattern.len()];
+    let mut p = pattern.len();
+    for i in (0..=end).rev() {
+        if same(chars[i], pattern[p - 1]) {
+            p -= 1;
+            positions[p] = i;
+            if p == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut score = 0;
+    for (n, &i) in positions.iter().enumerate() {
+        score += MATCH;
+        if n > 0 && positions[n - 1] + 1 == i {
+            score += CONSECUTIVE;
+        }
+        if i == 0 || !chars[i - 1].is_alphanumeric() || (chars[i - 1].is_lowercase() && chars[i].is_uppercase()) {
+            score += WORD_START;
+        }
+    }
+    let span = positions[positions.len() - 1] - positions[0] + 1;
+    score -= (span - positions.len()) as i64 * GAP;
+    Some((score, positions))
+}
@@ -0,0 +1,126 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config::HooksConfig;
+use crate::duration::parse_duration;
+use crate::filter::{detect_log_level, LogLevel};
+
+const DEFAULT_ALERT_COOLDOWN: Duration = Duration::from_secs(10);
+
+/// Commands from the `[hooks]` config table, run through `sh -c` when the session
+/// starts, a followed file rotates, ft exits, or an alerting line arrives. Each
+/// command sees `FT_EVENT`, `FT_PID` and event-specific `FT_*` variables.
+pub struct Hooks {
+    on_start: Option<String>,
+    on_rotate: Option<String>,
+    on_exit: Option<String>,
+    on_alert: Option<String>,
+    alert_pattern: Option<Regex>,
+    alert_level: LogLevel,
+    alert_cooldown: Duration,
+    last_alert: Cell<Option<Instant>>,
+}
+
+impl Hooks {
+    /// Build hooks from config; `None` when no hook command is set.
+    pub fn new(config: &HooksConfig) -> Result<Option<Self>> {
+        if config.on_start.is_none() && config.on_rotate.is_none() && config.on_exit.is_none() && config.on_alert.is_none() {
+            return Ok(None);
+        }
+        let alert_pattern = config.alert_pattern.as_deref()
+            .map(Regex::new)
+            .transpose()
+            .context("Invalid hooks.alert_pattern")?;
+        let alert_level = match config.alert_level.as_deref() {
+            Some(name) => LogLevel::from_name(name)
+                .with_context(|| format!("Invalid hooks.alert_level: {}", name))?,
+            None => LogLevel::Error,
+        };
+        let alert_cooldown = match config.alert_cooldown.as_deref() {
+            Some(spec) => parse_duration(spec).context("Invalid hooks.alert_cooldown")?,
+            None => DEFAULT_ALERT_COOLDOWN,
+        };
+        Ok(Some(Self {
+            on_start: config.on_start.clone(),
+            on_rotate: config.on_rotate.clone(),
+            on_exit: config.on_exit.clone(),
+            on_alert: config.on_alert.clone(),
+            alert_pattern,
+            alert_level,
+            alert_cooldown,
+            last_alert: Cell::new(None),
+        }))
+    }
+
+    /// `on_start`, with the sources being tailed in `FT_FILES` (one per line).
+    pub fn start(&self, files: &[PathBuf]) {
+        let files: Vec<String> = files.iter().map(|f| f.display().to_string()).collect();
+        self.run(&self.on_start, "start", &[("FT_FILES", files.join("\n"))], false);
+    }
+
+    /// `on_rotate`, with `FT_ROTATION` set to `rotated` (a new file at the path) or `truncated`.
+    pub fn rotate(&self, path: &Path, how: &str) {
+        self.run(&self.on_rotate, "rotate", &[("FT_FILE", path.display().to_string()), ("FT_ROTATION", how.to_string())], false);
+    }
+
+    /// `on_exit`, waited for so it finishes before ft does; `FT_LINES` is the number of lines printed.
+    pub fn exit(&self, printed: usize) {
+        self.run(&self.on_exit, "exit", &[("FT_LINES", printed.to_string())], true);
+    }
+
+    /// `on_alert` for the first new line that matches `alert_pattern`, or failing a
+    /// pattern is at `alert_level` or more severe. At most one alert per cooldown.
+    pub fn check_alert(&self, path: &Path, lines: &[String]) {
+        if self.on_alert.is_none() || self.last_alert.get().is_some_and(|at| at.elapsed() < self.alert_cooldown) {
+            return;
+        }
+        let alerting = lines.iter().find_map(|line| {
+            let level = detect_log_level(line);
+            let hit = match &self.alert_pattern {
+                Some(pattern) => pattern.is_match(line),
+                None => level.is_some_and(|l| l.priority() <= self.alert_level.priority()),
+            };
+            hit.then_some((line, level))
+        });
+        if let Some((line, level)) = alerting {
+            self.last_alert.set(Some(Instant::now()));
+            let level = level.map(|l| format!("{:?}", l).to_uppercase()).unwrap_or_default();
+            self.run(&self.on_alert, "alert", &[
+                ("FT_FILE", path.display().to_string()),
+                ("FT_LINE", line.clone()),
+                ("FT_LEVEL", level),
+            ], false);
+        }
+    }
+
+    fn run(&self, command: &Option<String>, event: &str, vars: &[(&str, String)], wait: bool) {
+        let Some(command) = command else {
+            return;
+        };
+        let spawned = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .env("FT_EVENT", event)
+            .env("FT_PID", std::process::id().to_string())
+            .envs(vars.iter().map(|(k, v)| (k, v)))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        match spawned {
+            Ok(mut child) if wait => {
+                let _ = child.wait();
+            }
+            Ok(mut child) => {
+                // Reap in the background so a slow hook never stalls the tail
+                thread::spawn(move || child.wait());
+            }
+            Err(e) => eprintln!("ft: warning: could not run {} hook: {}", event, e),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use crate::svg::{escape, hex, parse_ansi, BACKGROUND, FOREGROUND};
+
+/// `--format html`: opens the block colorized lines go in, with the colors of
+/// `ft themes render --svg` so a snippet pastes into a wiki or incident report as shown.
+pub fn header() -> String {
+    format!(
+        r#"<pre style="background:{};color:{};padding:12px;border-radius:6px;font-family:ui-monospace,'SFMono-Regular',Menlo,Consolas,monospace;font-size:13px;line-height:1.4;white-space:pre-wrap">"#,
+        BACKGROUND, FOREGROUND
+    )
+}
+
+pub fn footer() -> &'static str {
+    "</pre>"
+}
+
+/// An ANSI-colored line as HTML, each styled run a `<span>` with inline CSS.
+pub fn line(colored: &str) -> String {
+    // Runs with the same style are joined; the colorizer often restates a color
+    let mut runs: Vec<(String, String)> = Vec::new();
+    for span in parse_ansi(colored) {
+        let (fg, bg) = span.colors();
+        let mut css = Vec::new();
+        if let Some(fg) = fg {
+            css.push(format!("color:{}", hex(fg)));
+        }
+        if let Some(bg) = bg {
+            css.push(format!("background:{}", hex(bg)));
+        }
+        if span.bold {
+            css.push("font-weight:bold".to_string());
+        }
+        if span.italic {
+            css.push("font-style:italic".to_string());
+        }
+        if span.underline {
+            css.push("text-decoration:underline".to_string());
+        }
+        let css = css.join(";");
+        match runs.last_mut() {
+            Some((last, text)) if *last == css => text.push_str(&span.text),
+            _ => runs.push((css, span.text)),
+        }
+    }
+    runs.iter()
+        .map(|(css, text)| match css.as_str() {
+            "" => escape(text),
+            css => format!(r#"<span style="{}">{}</span>"#, css, escape(text)),
+        })
+        .collect()
+}
@@ -0,0 +1,91 @@
+use regex::Regex;
+use std::path::Path;
+
+/// `--hyperlinks`: makes URLs and file paths in output clickable with OSC 8 links.
+pub struct Hyperlinker {
+    pattern: Regex,
+}
+
+impl Hyperlinker {
+    pub fn new() -> Self {
+        // URLs, or absolute paths at the start of a line or after a space, quote, `=` or bracket
+        let pattern = Regex::new(r#"(?:https?|ftp|file)://[^\s"'<>`]+|(?:^|[\s"'=(\[])(/[\w.\-~+@%/]+)"#).unwrap();
+        Self { pattern }
+    }
+
+    /// Wrap each link found in the raw `line` in an OSC 8 sequence in its colored
+    /// rendering. Paths are only linked when they exist, so URL routes like
+    /// `/api/users` in access logs stay plain. Decorations before the text are skipped.
+    pub fn link(&self, colored: &str, line: &str) -> String {
+        // (first, last) visible characters of each link, and where it points
+        let visible = |byte: usize| line[..byte].chars().filter(|c| !c.is_control()).count();
+        let links: Vec<(usize, usize, String)> = self.pattern.captures_iter(line)
+            .filter_map(|caps| {
+                let (m, target) = match caps.get(1) {
+                    Some(path) => {
+                        let text = path.as_str().trim_end_matches(['.', ',']);
+                        if text.len() < 2 || !Path::new(text).exists() {
+                            return None;
+                        }
+                        (path, format!("file://{}", text))
+                    }
+                    None => {
+                        let url = caps.get(0)?;
+                        (url, url.as_str().trim_end_matches(['.', ',', ';', ':', ')', ']']).to_string())
+                    }
+                };
+                let shown = target.strip_prefix("file://").filter(|_| caps.get(1).is_some()).unwrap_or(&target);
+                let start = visible(m.start());
+                Some((start, start + shown.chars().count(), target))
+            })
+            .collect();
+        if links.is_empty() {
+            return colored.to_string();
+        }
+
+        let lead = visible_chars(colored).saturating_sub(visible(line.len()));
+        let mut result = String::with_capacity(colored.len() + links.len() * 32);
+        let mut links = links.into_iter().peekable();
+        let mut open = None;
+        let mut in_escape = false;
+        let mut pos = 0usize;
+        for ch in colored.chars() {
+            if in_escape {
+                in_escape = !ch.is_ascii_alphabetic();
+            } else if ch == '\x1b' {
+                in_escape = true;
+            } else if !ch.is_control() {
+                if open.is_some_and(|end| pos == end + lead) {
+                    result.push_str("\x1b]8;;\x1b\\");
+                    open = None;
+                }
+                if let Some((start, end, target)) = links.next_if(|(start, _, _)| pos == start + lead) {
+                    result.push_str(&format!("\x1b]8;;{}\x1b\\", target));
+                    open = Some(end).filter(|end| *end > start);
+                }
+                pos += 1;
+            }
+            result.push(ch);
+        }
+        if open.is_some() {
+            result.push_str("\x1b]8;;\x1b\\");
+        }
+        result
+    }
+}
+
+/// Characters a colored line shows: everything but SGR sequences and controls.
+fn visible_chars(colored: &str) -> usize {
+    let mut count = 0;
+    let mut in_escape = false;
+    for ch in colored.chars() {
+        if in_escape {
+            in_escape = !ch.is_ascii_alphabetic();
+        } else if ch == '\x1b' {
+            in_escape = true;
+        } else if !ch.is_control() {
+            count += 1;
+        }
+    }
+    count
+}
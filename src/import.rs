@@ -0,0 +1,212 @@
+use anyhow::{anyhow, Context, Result};
+use regex::Regex;
+use std::path::Path;
+
+/// `ft themes import --from`: which tool's colorizer config to read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// grc's `regexp=`/`colours=` entries (e.g. /usr/share/grc/conf.*)
+    Grc,
+    /// ccze's `KEYWORD [ATTRIBUTE] COLOR [on_COLOR]` lines
+    Ccze,
+}
+
+impl ImportFormat {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "grc" => Ok(ImportFormat::Grc),
+            "ccze" => Ok(ImportFormat::Ccze),
+            _ => Err(anyhow!("Invalid import format '{}': expected grc or ccze", name)),
+        }
+    }
+}
+
+/// ccze colors fixed kinds of token rather than regexes; these are the ones ft has a pattern for.
+const CCZE_PATTERNS: [(&str, &str); 13] = [
+    ("date", r"(Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)\s+[0-9]{1,2}\s+[0-9]{2}:[0-9]{2}:[0-9]{2}"),
+    ("email", r"[A-Za-z0-9._%+\-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}"),
+    ("url", r"(?:https?|ftp)://[^\s]+"),
+    ("uri", r"(?:https?|ftp)://[^\s]+"),
+    ("ip", r"\b(?:[0-9]{1,3}\.){3}[0-9]{1,3}\b"),
+    ("mac", r"\b(?:[0-9A-Fa-f]{2}:){5}[0-9A-Fa-f]{2}\b"),
+    ("pid", r"\[[0-9]+\]"),
+    ("size", r"\b[0-9]+(?:\.[0-9]+)?\s?(?:[KMGT]i?B|bytes?)\b"),
+    ("dir", r"(?:^|\s)(/[^\s]*)"),
+    ("get", r"\bGET\b"),
+    ("post", r"\bPOST\b"),
+    ("head", r"\bHEAD\b"),
+    ("put", r"\bPUT\b"),
+];
+
+/// Convert a grc or ccze config into ft theme text. What has no ft equivalent
+/// (grc's `skip=`, regexes ft's engine can't compile, blinking...) is kept as a
+/// comment saying why, so nothing disappears silently.
+pub fn import(format: ImportFormat, path: &Path) -> Result<String> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {:?}", path))?;
+    let from = match format {
+        ImportFormat::Grc => "grc",
+        ImportFormat::Ccze => "ccze",
+    };
+    let mut out = vec![format!("# Imported from {} by `ft themes import --from {}`", path.display(), from)];
+    match format {
+        ImportFormat::Grc => import_grc(&contents, &mut out),
+        ImportFormat::Ccze => import_ccze(&contents, &mut out),
+    }
+    if !out.iter().any(|line| line.starts_with("word:")) {
+        return Err(anyhow!("No rules ft can use in {:?}", path));
+    }
+    Ok(out.join("\n") + "\n")
+}
+
+fn import_grc(contents: &str, out: &mut Vec<String>) {
+    let mut entry: Vec<(&str, &str)> = Vec::new();
+    for line in contents.lines().chain(std::iter::once("-")) {
+        let line = line.trim();
+        if line.starts_with('-') || line.starts_with('=') {
+            grc_entry(&entry, out);
+            entry.clear();
+        } else if let Some((key, value)) = line.split_once('=').filter(|_| !line.starts_with('#')) {
+            entry.push((key.trim(), value.trim()));
+        }
+    }
+}
+
+/// One `regexp=`... block: a word rule for the whole match and one per colored group.
+fn grc_entry(entry: &[(&str, &str)], out: &mut Vec<String>) {
+    let value = |key: &str| entry.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+    let Some(pattern) = value("regexp") else {
+        return;
+    };
+    out.push(String::new());
+    if value("skip").is_some_and(|skip| skip == "yes") {
+        out.push(format!("# skipped: skip=yes (hiding lines) has no theme equivalent: {}", pattern));
+        return;
+    }
+    let regex = match Regex::new(pattern) {
+        Ok(regex) => regex,
+        Err(e) => {
+            let reason = e.to_string().lines().last().unwrap_or_default().to_string();
+            out.push(format!("# skipped: regex ft can't compile ({}): {}", reason.trim(), pattern));
+            return;
+        }
+    };
+    if let Some(count) = value("count").filter(|count| !matches!(*count, "more" | "once")) {
+        out.push(format!("# note: count={} is ignored", count));
+    }
+    let colours = value("colours").or_else(|| value("colors")).unwrap_or_default();
+    for (group, colour) in colours.split(',').enumerate() {
+        if group >= regex.captures_len() {
+            break;
+        }
+        let (style, dropped) = convert_style(colour.split_whitespace());
+        for word in dropped {
+            out.push(format!("# note: '{}' has no ft equivalent", word));
+        }
+        let Some(style) = style else {
+            continue;
+        };
+        let scope = if group == 0 { String::new() } else { format!("{}:", group) };
+        out.push(format!("word:{}={}{}", pattern, scope, style));
+    }
+}
+
+fn import_ccze(contents: &str, out: &mut Vec<String>) {
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        let mut keyword = words.next().unwrap_or_default();
+        if keyword == "color" {
+            keyword = words.next().unwrap_or_default();
+        }
+        let Some((_, pattern)) = CCZE_PATTERNS.iter().find(|(name, _)| *name == keyword) else {
+            out.push(format!("# skipped: no ft pattern for ccze's '{}'", keyword));
+            continue;
+        };
+        let (style, dropped) = convert_style(words);
+        for word in dropped {
+            out.push(format!("# note: '{}' has no ft equivalent", word));
+        }
+        if let Some(style) = style {
+            let scope = if pattern.contains("(/") { "1:" } else { "" };
+            out.push(format!("word:{}={}{}", pattern, scope, style));
+        }
+    }
+}
+
+/// grc/ccze color words (`bold red on_blue`, or a quoted `"\033[38;5;208m"`) as an ft
+/// style, plus the words that were dropped. None when nothing is left to color with.
+fn convert_style<'a>(words: impl Iterator<Item = &'a str>) -> (Option<String>, Vec<String>) {
+    let mut attributes = Vec::new();
+    let mut fg = None;
+    let mut bg = None;
+    let mut dropped = Vec::new();
+    for word in words {
+        let word = word.trim_matches(|c| c == '\'' || c == '"');
+        if let Some(codes) = word.strip_prefix("\\033[").or_else(|| word.strip_prefix("\\x1b[")).or_else(|| word.strip_prefix("\\e[")) {
+            convert_sgr(codes.trim_end_matches('m'), &mut attributes, &mut fg, &mut bg);
+            continue;
+        }
+        match word.to_lowercase().as_str() {
+            "default" | "unchanged" | "none" | "previous" | "" => {}
+            "bold" | "underline" | "reverse" | "italic" => attributes.push(word.to_lowercase()),
+            "dark" => attributes.push("dim".to_string()),
+            color if is_color(color) => fg = Some(color.to_string()),
+            color if color.strip_prefix("on_").is_some_and(is_color) => bg = color.strip_prefix("on_").map(str::to_string),
+            _ => dropped.push(word.to_string()),
+        }
+    }
+    if attributes.is_empty() && fg.is_none() && bg.is_none() {
+        return (None, dropped);
+    }
+    let mut style = attributes;
+    style.extend(fg);
+    if let Some(bg) = bg {
+        style.push(format!("on {}", bg));
+    }
+    (Some(style.join(" ")), dropped)
+}
+
+fn is_color(name: &str) -> bool {
+    const NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+    NAMES.contains(&name.strip_prefix("bright_").unwrap_or(name))
+}
+
+/// `1;38;5;208` and friends, from an escape sequence written into a grc config.
+fn convert_sgr(codes: &str, attributes: &mut Vec<String>, fg: &mut Option<String>, bg: &mut Option<String>) {
+    let codes: Vec<u32> = codes.split(';').filter_map(|code| code.parse().ok()).collect();
+    let mut i = 0;
+    while i < codes.len() {
+        if matches!(codes[i], 38 | 48) {
+            let target = if codes[i] == 38 { &mut *fg } else { &mut *bg };
+            match codes.get(i + 1) {
+                Some(5) => {
+                    *target = codes.get(i + 2).map(u32::to_string);
+                    i += 3;
+                }
+                Some(2) if codes.len() >= i + 5 => {
+                    *target = Some(format!("#{:02x}{:02x}{:02x}", codes[i + 2], codes[i + 3], codes[i + 4]));
+                    i += 5;
+                }
+                _ => i += 1,
+            }
+            continue;
+        }
+        match codes[i] {
+            1 => attributes.push("bold".to_string()),
+            2 => attributes.push("dim".to_string()),
+            3 => attributes.push("italic".to_string()),
+            4 => attributes.push("underline".to_string()),
+            7 => attributes.push("reverse".to_string()),
+            code @ 30..=37 => *fg = Some((code - 30).to_string()),
+            code @ 90..=97 => *fg = Some((code - 90 + 8).to_string()),
+            code @ 40..=47 => *bg = Some((code - 40).to_string()),
+            code @ 100..=107 => *bg = Some((code - 100 + 8).to_string()),
+            _ => {}
+        }
+        i += 1;
+    }
+}
@@ -0,0 +1,89 @@
+use anyhow::{anyhow, Context, Result};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Holds `--pidfile` and `--single-instance` files for the life of the process and
+/// removes them on drop, as long as they still name this process.
+pub struct InstanceGuard {
+    paths: Vec<PathBuf>,
+}
+
+impl InstanceGuard {
+    /// Write `pidfile` and take the lock for `lock_key`, refusing to start when either
+    /// belongs to another ft that is still running. Files left behind by a process
+    /// that has exited (kill -9, a crash) are taken over.
+    pub fn acquire(pidfile: Option<&Path>, lock_key: Option<&str>) -> Result<Self> {
+        let mut guard = Self { paths: Vec::new() };
+        if let Some(key) = lock_key {
+            let path = lock_path(key);
+            claim(&path).with_context(|| format!("Instance '{}' is already running", key))?;
+            guard.paths.push(path);
+        }
+        if let Some(path) = pidfile {
+            claim(path).context("Refusing to overwrite --pidfile")?;
+            guard.paths.push(path.to_path_buf());
+        }
+        Ok(guard)
+    }
+}
+
+impl Drop for InstanceGuard {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            if read_pid(path) == Some(std::process::id()) {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+}
+
+/// Where the lock for an instance key lives: the user's runtime dir if there is one.
+fn lock_path(key: &str) -> PathBuf {
+    let name: String = key.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+        .collect();
+    dirs::runtime_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(format!("ft-{}.lock", name.trim_matches('_')))
+}
+
+/// Atomically create `path` holding our pid, replacing it only if its owner is gone.
+fn claim(path: &Path) -> Result<()> {
+    for _ in 0..2 {
+        match OpenOptions::new().write(true).create_new(true).open(path) {
+            Ok(mut file) => {
+                writeln!(file, "{}", std::process::id())
+                    .with_context(|| format!("Failed to write {:?}", path))?;
+                return Ok(());
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => match read_pid(path) {
+                Some(pid) if pid != std::process::id() && is_running(pid) => {
+                    return Err(anyhow!("pid {} holds {:?}", pid, path));
+                }
+                _ => {
+                    // Stale: remove and retry; losing the race to another starter ends in the error above
+                    let _ = fs::remove_file(path);
+                }
+            },
+            Err(e) => return Err(e).with_context(|| format!("Failed to create {:?}", path)),
+        }
+    }
+    Err(anyhow!("Could not claim {:?}", path))
+}
+
+fn read_pid(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+fn is_running(pid: u32) -> bool {
+    if Path::new("/proc/self").exists() {
+        return Path::new(&format!("/proc/{}", pid)).exists();
+    }
+    Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
@@ -1,30 +1,210 @@
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use anyhow::Result;
-use std::io;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 use crate::colorizer::Colorizer;
-use crate::filter::LineFilter;
+use crate::config::Preset;
+use crate::filter::{detect_log_level, FilterOptions, LineFilter, LogLevel};
+use crate::parser::Parser;
+use crate::statusbar::StatusBar;
 
-pub struct InteractiveMode {
-    lines: Vec<String>,
+/// What the filter was built from, so `f` can rebuild it with other patterns and level
+/// while keeping the rest, presets included.
+pub struct FilterSource {
+    pub options: FilterOptions,
+    pub presets: Vec<Preset>,
+    pub parser: Arc<dyn Parser>,
+}
+
+impl FilterSource {
+    fn build(&self, options: FilterOptions) -> Result<LineFilter> {
+        let presets: Vec<&Preset> = self.presets.iter().collect();
+        Ok(LineFilter::with_presets(options, &presets)?.with_parser(self.parser.clone()))
+    }
+}
+
+/// A line in the buffer, and which source it came from
+struct Entry {
+    source: usize,
+    text: String,
+    /// Syslog priority of the line's level, found once when it's added
+    level: Option<u8>,
+    /// Whether the filter lets it through, found again only when the filter changes
+    passes: bool,
+}
+
+/// A pane of the split view: the lines of one source, or of all of them, and which of
+/// them is selected.
+struct Pane {
+    /// None for every source together
+    source: Option<usize>,
+    /// Index into the buffer of the selected line
     current_line: usize,
+    /// Share of the screen height; `+` and `-` change it and `=` evens them out
+    weight: u16,
+    /// The lines it shows, kept up as lines come and go so drawing needn't look for them
+    shown: VecDeque<usize>,
+    /// Those of them matching the `/` search
+    matches: VecDeque<usize>,
+}
+
+/// A pane's `shown` or `matches` as buffer indices. They're kept counting every line ever
+/// added, so dropping the oldest doesn't renumber them; `dropped` turns one into the other.
+#[derive(Clone, Copy)]
+struct Indices<'a> {
+    lines: &'a VecDeque<usize>,
+    dropped: usize,
+}
+
+impl<'a> Indices<'a> {
+    fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// The `n`th, which must exist.
+    fn at(&self, n: usize) -> usize {
+        self.lines[n] - self.dropped
+    }
+
+    fn get(&self, n: usize) -> Option<usize> {
+        self.lines.get(n).map(|line| line - self.dropped)
+    }
+
+    fn last(&self) -> Option<usize> {
+        self.lines.back().map(|line| line - self.dropped)
+    }
+
+    /// How many come before buffer index `index`.
+    fn before(&self, index: usize) -> usize {
+        self.lines.partition_point(|line| line - self.dropped < index)
+    }
+
+    /// From the `start`th on.
+    fn from(self, start: usize) -> impl Iterator<Item = usize> + 'a {
+        let dropped = self.dropped;
+        self.lines.range(start.min(self.lines.len())..).map(move |line| line - dropped)
+    }
+
+    fn iter(self) -> impl Iterator<Item = usize> + 'a {
+        self.from(0)
+    }
+}
+
+pub struct InteractiveMode {
+    lines: VecDeque<Entry>,
+    /// Source names, for pane titles and the `[name]` prefixes in a pane of them all
+    names: Vec<String>,
+    panes: Vec<Pane>,
+    /// The pane keys act on
+    focus: usize,
+    /// `z`: only the focused pane is drawn
+    zoomed: bool,
     paused: bool,
     colorizer: Colorizer,
     filter: LineFilter,
+    /// New lines while following, with their source, from the thread reading the sources
+    feed: Option<Receiver<(usize, String)>>,
+    /// Lines that arrived while paused, added when it resumes
+    held: Vec<(usize, String)>,
+    /// The oldest lines are dropped past this many, or past `max_bytes` of them
+    max_lines: usize,
+    max_bytes: usize,
+    bytes: usize,
+    /// How many of the oldest lines have been dropped to stay within the limits
+    dropped: usize,
+    /// How many lines in the buffer the filter lets through, kept up as lines come and go
+    passed: usize,
+    /// Set when the screen needs drawing again
+    dirty: bool,
+    /// `/`: the term searched for, highlighted and stepped through with `n` and `N`
+    search: Option<(String, regex::Regex)>,
+    filter_source: Option<FilterSource>,
+    /// Levels toggled off with `0`-`7` or `e`/`w`/`i`/`d`, one bit per syslog priority
+    hidden_levels: u8,
+    /// `W`: long lines wrap over several rows instead of being cut at the screen edge
+    wrap: bool,
+    /// Columns the lines are scrolled sideways by with `h`/`l`, when not wrapping
+    column: usize,
+    /// The `[general] clipboard` backend `y` copies with, if one is set
+    clipboard: Option<String>,
 }
 
 impl InteractiveMode {
-    pub fn new(lines: Vec<String>, colorizer: Colorizer, filter: LineFilter) -> Self {
-        Self {
-            lines,
-            current_line: 0,
+    /// Browse `lines`, each with the index in `names` of the source it came from. Several
+    /// sources start split, a pane each.
+    pub fn new(lines: Vec<(usize, String)>, names: Vec<String>, colorizer: Colorizer, filter: LineFilter) -> Self {
+        let sources: Vec<Option<usize>> = match names.len() {
+            0 | 1 => vec![None],
+            n => (0..n).map(Some).collect(),
+        };
+        let mut mode = Self {
+            bytes: 0,
+            lines: VecDeque::new(),
+            names,
+            panes: Vec::new(),
+            focus: 0,
+            zoomed: false,
             paused: false,
             colorizer,
             filter,
+            feed: None,
+            held: Vec::new(),
+            max_lines: usize::MAX,
+            max_bytes: usize::MAX,
+            dropped: 0,
+            passed: 0,
+            dirty: true,
+            search: None,
+            filter_source: None,
+            hidden_levels: 0,
+            wrap: false,
+            column: 0,
+            clipboard: None,
+        };
+        for (source, text) in lines {
+            mode.push(source, text);
+        }
+        for source in sources {
+            mode.open_pane(source);
         }
+        mode.focus = 0;
+        mode
+    }
+
+    /// Follow: add the lines `feed` sends as they come, keeping at most `max_lines` and
+    /// about `max_bytes` of them. Panes stay on their newest line unless scrolled up or
+    /// paused.
+    pub fn with_feed(mut self, feed: Receiver<(usize, String)>, max_lines: usize, max_bytes: usize) -> Self {
+        self.feed = Some(feed);
+        self.max_lines = max_lines.max(1);
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Let `f` edit the filter's include and exclude patterns and level.
+    pub fn with_filter_source(mut self, source: FilterSource) -> Self {
+        self.filter_source = Some(source);
+        self
+    }
+
+    /// Have `y` copy with the named clipboard backend rather than the detected one.
+    pub fn with_clipboard(mut self, backend: Option<String>) -> Self {
+        self.clipboard = backend;
+        self
     }
 
     pub fn run(&mut self) -> Result<()> {
@@ -44,50 +224,489 @@ impl InteractiveMode {
 
     fn run_app(&mut self) -> Result<()> {
         loop {
-            self.draw()?;
+            self.receive();
+            if self.dirty {
+                self.draw()?;
+                self.dirty = false;
+            }
 
             if event::poll(std::time::Duration::from_millis(100))? {
-                if let Event::Key(key) = event::read()? {
-                    if self.handle_key_event(key)? {
-                        break;
+                match event::read()? {
+                    Event::Key(key) => {
+                        if self.handle_key_event(key)? {
+                            break;
+                        }
+                        self.dirty = true;
                     }
+                    Event::Resize(..) => self.dirty = true,
+                    _ => {}
                 }
             }
         }
         Ok(())
     }
 
+    /// Take what the feed has sent since the last look, holding it while paused.
+    fn receive(&mut self) {
+        let Some(feed) = &self.feed else {
+            return;
+        };
+        loop {
+            match feed.try_recv() {
+                Ok((source, text)) => {
+                    self.held.push((source, text));
+                    // While paused, for the count of new lines in the status line
+                    self.dirty = true;
+                }
+                Err(TryRecvError::Empty) => break,
+                // The sources are gone; what's been read stays browsable
+                Err(TryRecvError::Disconnected) => {
+                    self.feed = None;
+                    self.dirty = true;
+                    break;
+                }
+            }
+        }
+        if !self.paused && !self.held.is_empty() {
+            self.append_held();
+        }
+    }
+
+    fn append_held(&mut self) {
+        let at_end: Vec<bool> = self.panes.iter()
+            .map(|pane| self.shown_in(pane).last().is_none_or(|last| pane.current_line >= last))
+            .collect();
+        for (source, text) in std::mem::take(&mut self.held) {
+            self.push(source, text);
+        }
+        let mut excess = 0;
+        while self.lines.len() > self.max_lines || (self.bytes > self.max_bytes && self.lines.len() > 1) {
+            let Some(entry) = self.lines.pop_front() else { break };
+            self.bytes -= entry.text.len();
+            self.passed -= usize::from(entry.passes);
+            excess += 1;
+        }
+        self.dropped += excess;
+        for pane in &mut self.panes {
+            for lines in [&mut pane.shown, &mut pane.matches] {
+                while lines.front().is_some_and(|&line| line < self.dropped) {
+                    lines.pop_front();
+                }
+            }
+        }
+        let targets: Vec<usize> = self.panes.iter().zip(at_end)
+            .map(|(pane, at_end)| match at_end {
+                true => self.last_in(pane),
+                false => pane.current_line.saturating_sub(excess),
+            })
+            .collect();
+        for (pane, target) in self.panes.iter_mut().zip(targets) {
+            pane.current_line = target;
+        }
+        self.dirty = true;
+    }
+
+    /// Add a line to the end of the buffer, noting its level and whether the filter passes
+    /// it, and to the panes that show it.
+    fn push(&mut self, source: usize, text: String) {
+        let level = detect_log_level(&text).map(|level| level.priority());
+        let passes = self.filter.should_show_line(&text);
+        self.bytes += text.len();
+        self.passed += usize::from(passes);
+        self.lines.push_back(Entry { source, text, level, passes });
+
+        let index = self.lines.len() - 1;
+        let line = self.dropped + index;
+        let panes: Vec<(bool, bool)> = self.panes.iter()
+            .map(|pane| {
+                let shown = self.shows(pane, index);
+                (shown, shown && self.matches(pane, index))
+            })
+            .collect();
+        for (pane, (shown, matched)) in self.panes.iter_mut().zip(panes) {
+            if shown {
+                pane.shown.push_back(line);
+            }
+            if matched {
+                pane.matches.push_back(line);
+            }
+        }
+    }
+
+    /// Whether `pane` shows line `index`: it's from the pane's source and the level toggles
+    /// let it through.
+    fn shows(&self, pane: &Pane, index: usize) -> bool {
+        let entry = &self.lines[index];
+        pane.source.is_none_or(|source| source == entry.source) && self.level_shown(entry)
+    }
+
+    /// Whether line `index`, as `pane` shows it, matches the `/` search.
+    fn matches(&self, pane: &Pane, index: usize) -> bool {
+        self.search.as_ref().is_some_and(|(_, re)| re.is_match(&self.display(pane, index)))
+    }
+
+    /// Work out again which lines each pane shows and which of them match the search, after
+    /// the level toggles or the search change.
+    fn reindex(&mut self) {
+        for i in 0..self.panes.len() {
+            let pane = &self.panes[i];
+            let shown: VecDeque<usize> = (0..self.lines.len()).filter(|&index| self.shows(pane, index)).collect();
+            let matches: VecDeque<usize> = shown.iter().copied().filter(|&index| self.matches(pane, index)).collect();
+            let pane = &mut self.panes[i];
+            pane.shown = shown.into_iter().map(|index| index + self.dropped).collect();
+            pane.matches = matches.into_iter().map(|index| index + self.dropped).collect();
+        }
+    }
+
+    /// Whether the level toggles let `entry` through; lines without a level always show.
+    fn level_shown(&self, entry: &Entry) -> bool {
+        entry.level.is_none_or(|priority| self.hidden_levels & (1 << priority) == 0)
+    }
+
+    /// The lines `pane` shows: those of its source the level toggles let through.
+    fn shown_in<'a>(&self, pane: &'a Pane) -> Indices<'a> {
+        Indices { lines: &pane.shown, dropped: self.dropped }
+    }
+
+    /// `shown_in` for the focused pane.
+    fn shown(&self) -> Indices<'_> {
+        self.shown_in(&self.panes[self.focus])
+    }
+
+    /// Which of the focused pane's lines match the `/` search.
+    fn search_matches(&self) -> Indices<'_> {
+        Indices { lines: &self.panes[self.focus].matches, dropped: self.dropped }
+    }
+
+    /// The last line `pane` shows, where it starts and follows from.
+    fn last_in(&self, pane: &Pane) -> usize {
+        self.shown_in(pane).last().unwrap_or(self.lines.len().saturating_sub(1))
+    }
+
+    fn current_line(&self) -> usize {
+        self.panes[self.focus].current_line
+    }
+
+    fn set_current_line(&mut self, index: usize) {
+        self.panes[self.focus].current_line = index;
+    }
+
+    /// Line `index` as `pane` shows it: with a `[name]` prefix in a pane of several sources.
+    fn display(&self, pane: &Pane, index: usize) -> String {
+        let Some(entry) = self.lines.get(index) else {
+            return String::new();
+        };
+        match pane.source.is_none() && self.names.len() > 1 {
+            true => format!("[{}] {}", self.names[entry.source], entry.text),
+            false => entry.text.clone(),
+        }
+    }
+
+    /// Move `steps` shown lines down, or up when negative, stopping at either end.
+    fn move_by(&mut self, steps: isize) {
+        let shown = self.shown();
+        if shown.is_empty() {
+            return;
+        }
+        let current = self.current_line();
+        // Where the current line is among the shown ones, or would be when it's hidden
+        let at = shown.before(current);
+        let on_shown = shown.get(at) == Some(current);
+        let target = match steps < 0 {
+            true => at.saturating_sub(steps.unsigned_abs()),
+            false if on_shown => at.saturating_add(steps as usize),
+            false => at.saturating_add(steps as usize).saturating_sub(1),
+        };
+        let target = shown.at(target.min(shown.len() - 1));
+        self.set_current_line(target);
+    }
+
+    /// Toggle the level with syslog priority `priority`, moving panes off lines that are
+    /// now hidden.
+    fn toggle_level(&mut self, priority: u8) {
+        self.hidden_levels ^= 1 << priority;
+        self.reindex();
+        let targets: Vec<Option<usize>> = self.panes.iter()
+            .map(|pane| {
+                if self.lines.get(pane.current_line).is_none_or(|entry| self.level_shown(entry)) {
+                    return None;
+                }
+                let shown = self.shown_in(pane);
+                shown.get(shown.before(pane.current_line)).or(shown.last())
+            })
+            .collect();
+        for (pane, target) in self.panes.iter_mut().zip(targets) {
+            if let Some(index) = target {
+                pane.current_line = index;
+            }
+        }
+    }
+
+    /// Add a pane for `source` (None: all of them) after the focused one, and focus it.
+    fn open_pane(&mut self, source: Option<usize>) {
+        let pane = Pane { source, current_line: 0, weight: 1, shown: VecDeque::new(), matches: VecDeque::new() };
+        let at = (self.focus + 1).min(self.panes.len());
+        self.panes.insert(at, pane);
+        self.focus = at;
+        self.reindex();
+        self.set_current_line(self.last_in(&self.panes[at]));
+    }
+
+    /// `s`: ask which source to open a pane for.
+    fn split(&mut self) -> Result<()> {
+        let colors = crate::popup::PopupColors::from_theme(self.colorizer.get_theme());
+        let mut names = self.names.clone();
+        names.push("All files".to_string());
+        if let crate::popup::PopupResult::Selected(idx) = crate::popup::popup_select_window(&names, &colors)? {
+            self.open_pane((idx < self.names.len()).then_some(idx));
+        }
+        Ok(())
+    }
+
     fn handle_key_event(&mut self, key: KeyEvent) -> Result<bool> {
         match key.code {
             KeyCode::Char('q') | KeyCode::Esc => return Ok(true),
-            KeyCode::Char(' ') => self.paused = !self.paused,
-            KeyCode::Up | KeyCode::Char('k') => {
-                if self.current_line > 0 {
-                    self.current_line -= 1;
+            KeyCode::Char(' ') => {
+                self.paused = !self.paused;
+                if !self.paused && !self.held.is_empty() {
+                    self.append_held();
                 }
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                if self.current_line < self.lines.len().saturating_sub(1) {
-                    self.current_line += 1;
+            KeyCode::Up | KeyCode::Char('k') => self.move_by(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_by(1),
+            KeyCode::Home | KeyCode::Char('g') => self.move_by(isize::MIN),
+            KeyCode::End | KeyCode::Char('G') => self.move_by(isize::MAX),
+            KeyCode::PageUp => self.move_by(-10),
+            KeyCode::PageDown => self.move_by(10),
+            KeyCode::Left | KeyCode::Char('h') if !self.wrap => self.pan(false),
+            KeyCode::Right | KeyCode::Char('l') if !self.wrap => self.pan(true),
+            KeyCode::Char('W') => {
+                self.wrap = !self.wrap;
+                self.column = 0;
+            }
+            KeyCode::Char(digit @ '0'..='7') => self.toggle_level(digit as u8 - b'0'),
+            KeyCode::Char('e') => self.toggle_level(LogLevel::Error.priority()),
+            KeyCode::Char('w') => self.toggle_level(LogLevel::Warning.priority()),
+            KeyCode::Char('i') => self.toggle_level(LogLevel::Info.priority()),
+            KeyCode::Char('d') => self.toggle_level(LogLevel::Debug.priority()),
+            // Panes
+            KeyCode::Tab => self.focus = (self.focus + 1) % self.panes.len(),
+            KeyCode::BackTab => self.focus = (self.focus + self.panes.len() - 1) % self.panes.len(),
+            KeyCode::Char('s') => self.split()?,
+            KeyCode::Char('x') if self.panes.len() > 1 => {
+                self.panes.remove(self.focus);
+                self.focus = self.focus.min(self.panes.len() - 1);
+            }
+            KeyCode::Char('z') => self.zoomed = !self.zoomed,
+            KeyCode::Char('+') => {
+                let pane = &mut self.panes[self.focus];
+                pane.weight = (pane.weight + 1).min(9);
+            }
+            KeyCode::Char('-') => {
+                let pane = &mut self.panes[self.focus];
+                pane.weight = pane.weight.saturating_sub(1).max(1);
+            }
+            KeyCode::Char('=') => {
+                for pane in &mut self.panes {
+                    pane.weight = 1;
                 }
             }
-            KeyCode::Home | KeyCode::Char('g') => {
-                self.current_line = 0;
+            KeyCode::Char('y') => {
+                let line = self.display(&self.panes[self.focus], self.current_line());
+                let colors = crate::popup::PopupColors::from_theme(self.colorizer.get_theme());
+                let message = match crate::clipboard::copy(&line, self.clipboard.as_deref()) {
+                    Ok(backend) => format!("  Copied 1 line via {}", backend.name()),
+                    Err(e) => format!("  {:#}", e),
+                };
+                crate::popup::popup_info(" Yank ", &[String::new(), message], &colors)?;
             }
-            KeyCode::End | KeyCode::Char('G') => {
-                self.current_line = self.lines.len().saturating_sub(1);
+            KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                let colors = crate::popup::PopupColors::from_theme(self.colorizer.get_theme());
+                let shown: Vec<usize> = self.shown().iter().collect();
+                let lines: Vec<String> = shown.iter().map(|&index| self.display(&self.panes[self.focus], index)).collect();
+                if let crate::popup::PopupResult::Selected(idx) = crate::popup::popup_fuzzy(" Find ", &lines, &colors)? {
+                    self.set_current_line(shown[idx]);
+                }
             }
-            KeyCode::PageUp => {
-                self.current_line = self.current_line.saturating_sub(10);
+            KeyCode::Char('f') => self.edit_filter()?,
+            KeyCode::Char('/') => {
+                let colors = crate::popup::PopupColors::from_theme(self.colorizer.get_theme());
+                let result = crate::popup::popup_input(" Search ", "Search term (empty=clear):", "", &colors)?;
+                if let crate::popup::PopupResult::Text(term) = result {
+                    self.search = match term.trim() {
+                        "" => None,
+                        term => regex::RegexBuilder::new(&regex::escape(term))
+                            .case_insensitive(true)
+                            .build()
+                            .ok()
+                            .map(|re| (term.to_string(), re)),
+                    };
+                    self.reindex();
+                    // Start from the line in view, as `less` does
+                    if !self.is_match(self.current_line()) {
+                        self.jump_to_match(true);
+                    }
+                }
             }
-            KeyCode::PageDown => {
-                self.current_line = (self.current_line + 10).min(self.lines.len().saturating_sub(1));
+            KeyCode::Char('n') => self.jump_to_match(true),
+            KeyCode::Char('N') => self.jump_to_match(false),
+            KeyCode::Char('E') => {
+                let line = self.display(&self.panes[self.focus], self.current_line());
+                if let Some(reference) = crate::editor::find_references(&line).first() {
+                    if let Err(e) = crate::editor::open(reference) {
+                        let colors = crate::popup::PopupColors::from_theme(self.colorizer.get_theme());
+                        crate::popup::popup_info(" Open in Editor ", &[String::new(), format!("  {:#}", e)], &colors)?;
+                    }
+                }
             }
             _ => {}
         }
         Ok(false)
     }
 
+    /// `f`: ask for new include and exclude patterns and level, each starting from the
+    /// current one, and swap in the filter built from them. Esc at any step leaves the
+    /// filter as it was, as does a pattern that doesn't compile, after saying why.
+    fn edit_filter(&mut self) -> Result<()> {
+        use crate::popup::{popup_info, popup_input, PopupColors, PopupResult};
+
+        let Some(source) = &self.filter_source else {
+            return Ok(());
+        };
+        let colors = PopupColors::from_theme(self.colorizer.get_theme());
+        let current = &source.options;
+        let PopupResult::Text(include) = popup_input(" Filter ", "Include regex (empty=none):", &current.includes.join("|"), &colors)? else {
+            return Ok(());
+        };
+        let PopupResult::Text(exclude) = popup_input(" Filter ", "Exclude regex (empty=none):", &current.excludes.join("|"), &colors)? else {
+            return Ok(());
+        };
+        let PopupResult::Text(level) = popup_input(" Filter ", "Level, e.g. WARN or DEBUG,ERROR (empty=all):", current.level.as_deref().unwrap_or(""), &colors)? else {
+            return Ok(());
+        };
+
+        let patterns = |text: String| match text.trim() {
+            "" => Vec::new(),
+            text => vec![text.to_string()],
+        };
+        let options = FilterOptions {
+            includes: patterns(include),
+            excludes: patterns(exclude),
+            level: Some(level.trim().to_string()).filter(|level| !level.is_empty()),
+            ..current.clone()
+        };
+        match source.build(options.clone()) {
+            Ok(filter) => {
+                self.filter = filter;
+                for entry in &mut self.lines {
+                    entry.passes = self.filter.should_show_line(&entry.text);
+                }
+                self.passed = self.lines.iter().filter(|entry| entry.passes).count();
+                if let Some(source) = &mut self.filter_source {
+                    source.options = options;
+                }
+            }
+            Err(e) => popup_info(" Filter ", &[String::new(), format!("  {:#}", e)], &colors)?,
+        }
+        Ok(())
+    }
+
+    fn is_match(&self, index: usize) -> bool {
+        let matches = self.search_matches();
+        matches.get(matches.before(index)) == Some(index)
+    }
+
+    /// Move to the next matching line in the focused pane, or the previous one, wrapping
+    /// around.
+    fn jump_to_match(&mut self, forward: bool) {
+        if self.search.is_none() {
+            return;
+        }
+        let current = self.current_line();
+        let matches = self.search_matches();
+        let found = match forward {
+            true => matches.get(matches.before(current + 1)).or(matches.get(0)),
+            false => matches.before(current).checked_sub(1).and_then(|n| matches.get(n)).or(matches.last()),
+        };
+        if let Some(index) = found {
+            self.set_current_line(index);
+        }
+    }
+
+    /// The status line's search part: which match the current line is, of how many.
+    fn search_status(&self) -> Option<String> {
+        let (term, _) = self.search.as_ref()?;
+        let total = self.search_matches().len();
+        let current = self.is_match(self.current_line())
+            .then(|| self.search_matches().before(self.current_line()) + 1);
+        Some(match (total, current) {
+            (0, _) => format!("/{}: no matches", term),
+            (_, Some(current)) => format!("/{}: {}/{}", term, current, total),
+            (_, None) => format!("/{}: {} matches", term, total),
+        })
+    }
+
+    /// Line `index` as `pane` draws it: colored, with search matches highlighted, unless
+    /// the filter hides it, which the `bool` says.
+    fn render(&self, pane: &Pane, index: usize) -> (String, bool) {
+        let line = self.display(pane, index);
+        if !self.lines[index].passes {
+            return (format!("(filtered) {}", line), true);
+        }
+        let mut colored_line = self.colorizer.colorize_line(&line);
+        if let Some((_, re)) = &self.search {
+            colored_line = crate::tail::highlight_search_matches(&colored_line, &line, re);
+        }
+        (colored_line, false)
+    }
+
+    /// How many rows line `index` takes in `pane` when wrapped at `width`.
+    fn wrapped_rows(&self, pane: &Pane, index: usize, width: usize) -> usize {
+        crate::width::wrap_ansi(&self.render(pane, index).0, width).len()
+    }
+
+    /// `l` and `h`: scroll the lines sideways by half the screen, no further than the
+    /// widest of the focused pane's lines needs.
+    fn pan(&mut self, right: bool) {
+        let width = crossterm::terminal::size().map_or(80, |(width, _)| usize::from(width));
+        let step = (width / 2).max(1);
+        self.column = match right {
+            true => {
+                let pane = &self.panes[self.focus];
+                let widest = self.shown().iter()
+                    .map(|index| crate::width::visible_width(&self.display(pane, index)))
+                    .max()
+                    .unwrap_or(0);
+                (self.column + step).min(widest.saturating_sub(width))
+            }
+            false => self.column.saturating_sub(step),
+        };
+    }
+
+    /// The panes to draw, with the row each starts at and how many rows it gets. Split,
+    /// each pane has a title row above its lines.
+    fn layout(&self, height: usize) -> Vec<(usize, usize, usize)> {
+        let titles = self.panes.len();
+        if self.panes.len() == 1 || self.zoomed || height < titles * 2 {
+            // Alone, a pane has no title; the row above the status line stays blank
+            return vec![(self.focus, 0, height.saturating_sub(1))];
+        }
+        let rows = height - titles;
+        let total: usize = self.panes.iter().map(|pane| usize::from(pane.weight)).sum();
+        let mut top = 0;
+        let mut layout = Vec::new();
+        for (index, pane) in self.panes.iter().enumerate() {
+            let share = match index + 1 == self.panes.len() {
+                // The last pane takes what rounding left over
+                true => height.saturating_sub(top + 1),
+                false => rows * usize::from(pane.weight) / total,
+            };
+            layout.push((index, top + 1, share));
+            top += share + 1;
+        }
+        layout
+    }
+
     fn draw(&self) -> Result<()> {
         use crossterm::{
             cursor::MoveTo,
@@ -96,58 +715,286 @@ impl InteractiveMode {
         };
 
         let (width, height) = size()?;
-        let content_height = height as usize - 2; // Reserve space for status line
 
         // Clear screen
         execute!(io::stdout(), Clear(ClearType::All))?;
 
-        // Show visible lines
-        let start_line = self.current_line.saturating_sub(content_height / 2);
-        let end_line = (start_line + content_height).min(self.lines.len());
+        let layout = self.layout((height as usize).saturating_sub(1)); // Reserve space for status line
+        let titled = layout.len() > 1;
+        for &(index, top, content_height) in &layout {
+            let pane = &self.panes[index];
+            let focused = index == self.focus;
 
-        for (i, line_idx) in (start_line..end_line).enumerate() {
-            if let Some(line) = self.lines.get(line_idx) {
-                execute!(io::stdout(), MoveTo(0, i as u16))?;
-                
-                // Highlight current line
-                if line_idx == self.current_line {
-                    execute!(io::stdout(), SetBackgroundColor(Color::DarkGrey))?;
+            // Show visible lines, keeping the pane full at the end of the buffer
+            let shown = self.shown_in(pane);
+            let at = shown.before(pane.current_line);
+            let mut start_line = at.saturating_sub(content_height / 2)
+                .min(shown.len().saturating_sub(content_height));
+            if self.wrap && at < shown.len() {
+                // Wrapped lines take several rows: start late enough for the current one to fit
+                let mut rows: usize = shown.from(start_line).take(at + 1 - start_line)
+                    .map(|line| self.wrapped_rows(pane, line, width as usize))
+                    .sum();
+                while rows > content_height && start_line < at {
+                    rows -= self.wrapped_rows(pane, shown.at(start_line), width as usize);
+                    start_line += 1;
                 }
-
-                // Apply filter and colorization
-                if self.filter.should_show_line(line) {
-                    let colored_line = self.colorizer.colorize_line(line);
-                    execute!(io::stdout(), Print(&colored_line))?;
-                } else {
-                    execute!(io::stdout(), SetForegroundColor(Color::DarkGrey))?;
-                    execute!(io::stdout(), Print(&format!("(filtered) {}", line)))?;
+                // ...and early enough to keep the pane full at the end of the buffer
+                let mut rows = 0;
+                for line in shown.from(start_line) {
+                    rows += self.wrapped_rows(pane, line, width as usize);
+                    if rows >= content_height {
+                        break;
+                    }
                 }
+                while start_line > 0 && rows < content_height {
+                    let above = self.wrapped_rows(pane, shown.at(start_line - 1), width as usize);
+                    if rows + above > content_height {
+                        break;
+                    }
+                    rows += above;
+                    start_line -= 1;
+                }
+            }
 
+            if titled {
+                let name = pane.source.map_or("All files", |source| self.names[source].as_str());
+                let title = format!(" {} | Line {}/{} ", name, (at + 1).min(shown.len()), shown.len());
+                let title = crate::width::truncate(&title, width as usize);
+                execute!(io::stdout(), MoveTo(0, (top - 1) as u16))?;
+                execute!(io::stdout(), SetBackgroundColor(if focused { Color::Blue } else { Color::DarkGrey }))?;
+                execute!(io::stdout(), SetForegroundColor(Color::White))?;
+                execute!(io::stdout(), Print(crate::width::pad_ansi(&title, width as usize)))?;
                 execute!(io::stdout(), ResetColor)?;
             }
+
+            let mut row = 0;
+            for line_idx in shown.from(start_line) {
+                if row >= content_height {
+                    break;
+                }
+                let (rendered, filtered) = self.render(pane, line_idx);
+                // Wrapped over several rows, or cut to the width after the sideways scroll
+                let rows = match self.wrap {
+                    true => crate::width::wrap_ansi(&rendered, width as usize),
+                    false => vec![crate::width::skip_columns(&rendered, self.column)],
+                };
+                for text in rows.into_iter().take(content_height - row) {
+                    execute!(io::stdout(), MoveTo(0, (top + row) as u16))?;
+
+                    // Highlight current line
+                    if focused && line_idx == pane.current_line {
+                        execute!(io::stdout(), SetBackgroundColor(Color::DarkGrey))?;
+                    }
+                    if filtered {
+                        execute!(io::stdout(), SetForegroundColor(Color::DarkGrey))?;
+                    }
+                    execute!(io::stdout(), Print(crate::width::pad_ansi(&text, width as usize)))?;
+
+                    execute!(io::stdout(), ResetColor)?;
+                    row += 1;
+                }
+            }
         }
 
         // Status line
-        let status = format!(
-            " Line {}/{} | {} | Press 'q' to quit, SPACE to pause, arrows to navigate ",
-            self.current_line + 1,
-            self.lines.len(),
-            if self.paused { "PAUSED" } else { "RUNNING" }
-        );
-        
+        let state = match (self.paused, self.feed.is_some()) {
+            (true, _) if !self.held.is_empty() => format!("PAUSED (+{} new)", self.held.len()),
+            (true, _) => "PAUSED".to_string(),
+            (false, true) => "FOLLOWING".to_string(),
+            (false, false) => "RUNNING".to_string(),
+        };
+        let search = self.search_status().map(|search| format!(" | {}", search)).unwrap_or_default();
+        let passed = match self.filter.is_active() {
+            true => format!(" | {} shown", self.passed),
+            false => String::new(),
+        };
+        let dropped = match self.dropped {
+            0 => String::new(),
+            dropped => format!(" | {} oldest dropped", dropped),
+        };
+        let levels = match self.hidden_levels {
+            0 => String::new(),
+            hidden => {
+                let names: Vec<&str> = LogLevel::ALL.iter()
+                    .filter(|level| hidden & (1 << level.priority()) == 0)
+                    .map(|level| level.keywords()[0])
+                    .collect();
+                format!(" | levels: {}", if names.is_empty() { "none".to_string() } else { names.join(" ") })
+            }
+        };
+        let shown = self.shown();
+        let at = shown.before(self.current_line());
+        let zoom = match self.zoomed && self.panes.len() > 1 {
+            true => " | ZOOM",
+            false => "",
+        };
+        let long_lines = match (self.wrap, self.column) {
+            (true, _) => " | WRAP".to_string(),
+            (false, 0) => String::new(),
+            (false, column) => format!(" | col {}", column + 1),
+        };
+        // Key hints go first when the terminal is narrow, the position and state never
+        let hints = [
+            " | Press 'q' to quit", ", SPACE to pause", ", arrows to navigate", ", / to search",
+            ", n/N for next/prev", ", Ctrl+F to find", ", f to filter", ", 0-7 or e/w/i/d to toggle levels",
+            ", s to split", ", TAB to switch pane", ", z to zoom", ", +/-/= to resize", ", W to wrap",
+            ", h/l to scroll sideways", ", y to yank", ", E to edit ",
+        ];
+        let mut status = StatusBar::new()
+            .left(format!(" Line {}/{}", (at + 1).min(shown.len()), shown.len()), 0)
+            .left(dropped, 0)
+            .left(passed, 1)
+            .left(format!(" | {}", state), 0)
+            .left(zoom, 1)
+            .left(long_lines, 1)
+            .left(levels, 1)
+            .left(search, 1);
+        for (i, hint) in hints.into_iter().enumerate() {
+            status = status.left(hint, 2 + i as u8);
+        }
+        let status = status.render(width as usize);
+
         execute!(io::stdout(), MoveTo(0, height - 1))?;
         execute!(io::stdout(), SetBackgroundColor(Color::Blue))?;
         execute!(io::stdout(), SetForegroundColor(Color::White))?;
         execute!(io::stdout(), Print(&status))?;
-        
-        // Fill remaining space on status line
-        let remaining = width as usize - status.len();
-        if remaining > 0 {
-            execute!(io::stdout(), Print(&" ".repeat(remaining)))?;
-        }
-        
         execute!(io::stdout(), ResetColor)?;
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// How often followed files are checked for new lines
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A source for `spawn_follower`: a file, read from `offset` on, or stdin.
+pub struct FollowSource {
+    pub path: PathBuf,
+    pub offset: u64,
+    pub stdin: bool,
+}
+
+/// `-i -f`: read what's appended to `sources` on a thread of its own and send each line,
+/// passed through `clean`, to the interactive view with its source's position in
+/// `sources`. Truncated files are read again from the start and rotated ones reopened.
+/// The thread ends when the view is closed.
+pub fn spawn_follower(sources: Vec<FollowSource>, clean: impl Fn(String) -> String + Send + 'static) -> Receiver<(usize, String)> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        // stdin blocks, so it's read on a thread of its own and picked up with the files
+        let (stdin_tx, stdin_rx) = mpsc::channel();
+        let mut files = Vec::new();
+        for source in sources {
+            if source.stdin {
+                let stdin_tx = stdin_tx.clone();
+                thread::spawn(move || {
+                    for line in io::stdin().lock().lines().map_while(Result::ok) {
+                        if stdin_tx.send(line).is_err() {
+                            break;
+                        }
+                    }
+                });
+                files.push(FollowedFile::stdin(source.path));
+            } else {
+                files.push(FollowedFile::new(source.path, source.offset));
+            }
+        }
+        loop {
+            for (source, file) in files.iter_mut().enumerate() {
+                let lines = match file.stdin {
+                    true => stdin_rx.try_iter().collect(),
+                    false => file.read_new(),
+                };
+                for line in lines {
+                    if tx.send((source, clean(line))).is_err() {
+                        return;
+                    }
+                }
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    });
+    rx
+}
+
+struct FollowedFile {
+    path: PathBuf,
+    stdin: bool,
+    reader: Option<BufReader<File>>,
+    offset: u64,
+    /// A last line still being written
+    partial: Vec<u8>,
+}
+
+impl FollowedFile {
+    fn new(path: PathBuf, offset: u64) -> Self {
+        Self { path, stdin: false, reader: None, offset, partial: Vec::new() }
+    }
+
+    fn stdin(path: PathBuf) -> Self {
+        Self { path, stdin: true, reader: None, offset: 0, partial: Vec::new() }
+    }
+
+    /// The complete lines written since the last call.
+    fn read_new(&mut self) -> Vec<String> {
+        let Ok(metadata) = std::fs::metadata(&self.path) else {
+            // Gone for now, as during rotation; it's opened again when it's back
+            self.reader = None;
+            return Vec::new();
+        };
+        let replaced = self.reader.as_ref()
+            .and_then(|reader| reader.get_ref().metadata().ok())
+            .is_some_and(|open| file_id(&open) != file_id(&metadata));
+        if replaced {
+            self.reader = None;
+            self.offset = 0;
+        }
+        if metadata.len() < self.offset {
+            // Truncated
+            self.reader = None;
+            self.offset = 0;
+        }
+        if self.reader.is_none() {
+            let Ok(mut file) = File::open(&self.path) else {
+                return Vec::new();
+            };
+            // A partial line is read again whole
+            self.offset = self.offset.saturating_sub(self.partial.len() as u64);
+            self.partial.clear();
+            if file.seek(SeekFrom::Start(self.offset)).is_err() {
+                return Vec::new();
+            }
+            self.reader = Some(BufReader::new(file));
+        }
+        let Some(reader) = self.reader.as_mut() else {
+            return Vec::new();
+        };
+        let mut lines = Vec::new();
+        loop {
+            match reader.read_until(b'\n', &mut self.partial) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    self.offset += n as u64;
+                    if self.partial.ends_with(b"\n") {
+                        let line = String::from_utf8_lossy(&self.partial);
+                        lines.push(line.trim_end_matches('\n').trim_end_matches('\r').to_string());
+                        self.partial.clear();
+                    }
+                }
+            }
+        }
+        lines
+    }
+}
+
+#[cfg(unix)]
+fn file_id(metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
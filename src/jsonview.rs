@@ -0,0 +1,222 @@
+use anyhow::anyhow;
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::fmt;
+
+use crate::theme::Style;
+
+/// `--json-view`: how JSON lines are laid out once recognized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonLayout {
+    /// One line, `{"key": value, ...}`
+    Inline,
+    /// Indented over several lines
+    Pretty,
+    /// `key.sub=value` pairs, one line
+    Flat,
+}
+
+impl JsonLayout {
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        match spec.trim().to_lowercase().as_str() {
+            "inline" => Ok(JsonLayout::Inline),
+            "pretty" => Ok(JsonLayout::Pretty),
+            "flat" => Ok(JsonLayout::Flat),
+            _ => Err(anyhow!("Invalid JSON layout '{}': expected inline, pretty or flat", spec)),
+        }
+    }
+}
+
+/// A parsed JSON value that keeps object keys in the order the line had them.
+enum Json {
+    Null,
+    Bool(bool),
+    Number(serde_json::Number),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl<'de> Deserialize<'de> for Json {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct JsonVisitor;
+
+        impl<'de> Visitor<'de> for JsonVisitor {
+            type Value = Json;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("any JSON value")
+            }
+            fn visit_unit<E>(self) -> Result<Json, E> {
+                Ok(Json::Null)
+            }
+            fn visit_bool<E>(self, v: bool) -> Result<Json, E> {
+                Ok(Json::Bool(v))
+            }
+            fn visit_i64<E>(self, v: i64) -> Result<Json, E> {
+                Ok(Json::Number(v.into()))
+            }
+            fn visit_u64<E>(self, v: u64) -> Result<Json, E> {
+                Ok(Json::Number(v.into()))
+            }
+            fn visit_f64<E>(self, v: f64) -> Result<Json, E> {
+                Ok(serde_json::Number::from_f64(v).map_or(Json::Null, Json::Number))
+            }
+            fn visit_str<E>(self, v: &str) -> Result<Json, E> {
+                Ok(Json::String(v.to_string()))
+            }
+            fn visit_string<E>(self, v: String) -> Result<Json, E> {
+                Ok(Json::String(v))
+            }
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Json, A::Error> {
+                let mut items = Vec::new();
+                while let Some(item) = seq.next_element()? {
+                    items.push(item);
+                }
+                Ok(Json::Array(items))
+            }
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Json, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(Json::Object(entries))
+            }
+        }
+
+        deserializer.deserialize_any(JsonVisitor)
+    }
+}
+
+/// Styles for each kind of token: object keys, strings, numbers, and true/false/null.
+#[derive(Clone, Copy)]
+pub struct JsonPalette<'a> {
+    pub key: &'a Style,
+    pub string: &'a Style,
+    pub number: &'a Style,
+    pub literal: &'a Style,
+}
+
+/// Builds the plain and the colored text side by side, so matches found in the
+/// plain text line up with what's shown.
+struct Writer<'a> {
+    palette: Option<JsonPalette<'a>>,
+    plain: String,
+    colored: String,
+}
+
+impl<'a> Writer<'a> {
+    /// The style `pick` chooses from the palette, if colors are on.
+    fn style(&self, pick: fn(&JsonPalette<'a>) -> &'a Style) -> Option<&'a Style> {
+        self.palette.as_ref().map(pick)
+    }
+
+    fn push(&mut self, text: &str, style: Option<&Style>) {
+        self.plain.push_str(text);
+        match style {
+            Some(style) => {
+                self.colored.push_str(&style.to_ansi());
+                self.colored.push_str(text);
+                self.colored.push_str("\x1b[0m");
+            }
+            None => self.colored.push_str(text),
+        }
+    }
+
+    fn scalar(&mut self, value: &Json) {
+        let (text, style) = match value {
+            Json::Null => ("null".to_string(), self.style(|p| p.literal)),
+            Json::Bool(b) => (b.to_string(), self.style(|p| p.literal)),
+            Json::Number(n) => (n.to_string(), self.style(|p| p.number)),
+            Json::String(s) => (quote(s), self.style(|p| p.string)),
+            Json::Array(_) | Json::Object(_) => unreachable!("containers aren't scalars"),
+        };
+        self.push(&text, style);
+    }
+
+    fn key(&mut self, key: &str) {
+        self.push(&quote(key), self.style(|p| p.key));
+    }
+
+    /// `{"a": 1, "b": [true, null]}`, or spread over lines `indent` deep when `pretty`.
+    fn nested(&mut self, value: &Json, pretty: bool, indent: usize) {
+        let newline = |depth: usize| if pretty { format!("\n{}", "  ".repeat(depth)) } else { String::new() };
+        match value {
+            Json::Array(items) if !items.is_empty() => {
+                self.push("[", None);
+                for (i, item) in items.iter().enumerate() {
+                    self.push(if i == 0 { "" } else if pretty { "," } else { ", " }, None);
+                    self.push(&newline(indent + 1), None);
+                    self.nested(item, pretty, indent + 1);
+                }
+                self.push(&newline(indent), None);
+                self.push("]", None);
+            }
+            Json::Object(entries) if !entries.is_empty() => {
+                self.push("{", None);
+                for (i, (key, item)) in entries.iter().enumerate() {
+                    self.push(if i == 0 { "" } else if pretty { "," } else { ", " }, None);
+                    self.push(&newline(indent + 1), None);
+                    self.key(key);
+                    self.push(": ", None);
+                    self.nested(item, pretty, indent + 1);
+                }
+                self.push(&newline(indent), None);
+                self.push("}", None);
+            }
+            Json::Array(_) => self.push("[]", None),
+            Json::Object(_) => self.push("{}", None),
+            scalar => self.scalar(scalar),
+        }
+    }
+
+    /// `a.b=1 msg="two words" tags.0=x`: one pair per leaf, keys joined with dots.
+    fn flat(&mut self, value: &Json, path: &mut Vec<String>) {
+        let children: Vec<(String, &Json)> = match value {
+            Json::Array(items) if !items.is_empty() => items.iter().enumerate().map(|(i, item)| (i.to_string(), item)).collect(),
+            Json::Object(entries) if !entries.is_empty() => entries.iter().map(|(key, item)| (key.clone(), item)).collect(),
+            _ => {
+                if !self.plain.is_empty() {
+                    self.push(" ", None);
+                }
+                let key = if path.is_empty() { "value".to_string() } else { path.join(".") };
+                self.push(&key, self.style(|p| p.key));
+                self.push("=", None);
+                match value {
+                    Json::Array(_) => self.push("[]", None),
+                    Json::Object(_) => self.push("{}", None),
+                    Json::String(s) if !s.is_empty() && !s.contains(|c: char| c.is_whitespace() || c == '"' || c == '=') => {
+                        self.push(s, self.style(|p| p.string));
+                    }
+                    scalar => self.scalar(scalar),
+                }
+                return;
+            }
+        };
+        for (key, child) in children {
+            path.push(key);
+            self.flat(child, path);
+            path.pop();
+        }
+    }
+}
+
+fn quote(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_else(|_| format!("\"{}\"", text))
+}
+
+/// Lay out `line` if it's a JSON object or array, returning the plain text and the
+/// same text colored with `palette` (plain again when there is none).
+pub fn render(line: &str, layout: JsonLayout, palette: Option<JsonPalette>) -> Option<(String, String)> {
+    let trimmed = line.trim();
+    if !(trimmed.starts_with('{') || trimmed.starts_with('[')) {
+        return None;
+    }
+    let value: Json = serde_json::from_str(trimmed).ok()?;
+    let mut writer = Writer { palette, plain: String::new(), colored: String::new() };
+    match layout {
+        JsonLayout::Inline => writer.nested(&value, false, 0),
+        JsonLayout::Pretty => writer.nested(&value, true, 0),
+        JsonLayout::Flat => writer.flat(&value, &mut Vec::new()),
+    }
+    Some((writer.plain, writer.colored))
+}
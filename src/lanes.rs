@@ -0,0 +1,63 @@
+use anyhow::{anyhow, Context, Result};
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::colorizer::DEFAULT_HASH_PALETTE;
+use crate::theme::HashFieldRule;
+
+/// Lanes beyond this count wrap around so deep indentation never eats the line.
+const MAX_LANES: usize = 8;
+
+/// Assigns each thread/PID its own indentation lane and color band so that
+/// interleaved concurrent flows can be followed by eye.
+pub struct LaneAssigner {
+    pattern: Regex,
+    lanes: HashMap<String, usize>,
+}
+
+impl LaneAssigner {
+    /// Parse a `--lanes` spec: `field=NAME` (e.g. `field=thread`) or `pid`.
+    pub fn from_spec(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        let pattern = if spec.eq_ignore_ascii_case("pid") {
+            // syslog style `sshd[1234]:` or `pid=1234`
+            Regex::new(r"(?:\[([0-9]+)\]|\bpid[=:]\s*([0-9]+))").unwrap()
+        } else if let Some(field) = spec.strip_prefix("field=") {
+            HashFieldRule::new(field)
+                .with_context(|| format!("Invalid lanes spec: {}", spec))?
+                .pattern
+        } else {
+            return Err(anyhow!("Invalid lanes spec: {} (expected field=NAME or pid)", spec));
+        };
+
+        Ok(Self {
+            pattern,
+            lanes: HashMap::new(),
+        })
+    }
+
+    /// Lane index for a line, assigned in order of first appearance.
+    fn lane_for(&mut self, line: &str) -> Option<usize> {
+        let caps = self.pattern.captures(line)?;
+        let key = caps.iter().skip(1).flatten().next()?.as_str();
+        let next = self.lanes.len();
+        Some(*self.lanes.entry(key.to_string()).or_insert(next) % MAX_LANES)
+    }
+
+    /// Prefix the rendered line with its lane's indentation and color band.
+    /// Lines without a lane key get a plain gutter so columns stay aligned.
+    pub fn decorate(&mut self, raw: &str, rendered: &str, color: bool) -> String {
+        match self.lane_for(raw) {
+            Some(lane) => {
+                let indent = "  ".repeat(lane);
+                if color {
+                    let band = DEFAULT_HASH_PALETTE[lane % DEFAULT_HASH_PALETTE.len()];
+                    format!("{}\x1b[38;5;{}m▌\x1b[0m {}", indent, band, rendered)
+                } else {
+                    format!("{}| {}", indent, rendered)
+                }
+            }
+            None => format!("  {}", rendered),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use anyhow::{anyhow, Result};
+use std::process::{Command, Stdio};
+
+/// Parse a `--max-memory` size such as `256M`, `1G`, `512k` or a bare byte count.
+pub fn parse_size(input: &str) -> Result<usize> {
+    let text = input.trim().to_lowercase();
+    let text = text.trim_end_matches("ib").trim_end_matches('b');
+    let (number, multiplier) = match text.char_indices().last() {
+        Some((i, 'k')) => (&text[..i], 1 << 10),
+        Some((i, 'm')) => (&text[..i], 1 << 20),
+        Some((i, 'g')) => (&text[..i], 1 << 30),
+        _ => (text, 1),
+    };
+    match number.trim().parse::<f64>() {
+        Ok(n) if n > 0.0 => Ok((n * multiplier as f64) as usize),
+        _ => Err(anyhow!("Invalid size '{}': expected e.g. 256M or 1G", input)),
+    }
+}
+
+/// A `--scrollback` limit: a line count, or a size in bytes.
+#[derive(Clone, Copy)]
+pub enum Scrollback {
+    Lines(usize),
+    Bytes(usize),
+}
+
+/// Parse `--scrollback`: a bare number is lines, one with a unit (`64M`, `512k`) bytes.
+pub fn parse_scrollback(input: &str) -> Result<Scrollback> {
+    match input.trim().parse::<usize>() {
+        Ok(0) => Err(anyhow!("Invalid scrollback '{}': expected e.g. 5000 or 64M", input)),
+        Ok(lines) => Ok(Scrollback::Lines(lines)),
+        Err(_) => parse_size(input).map(Scrollback::Bytes),
+    }
+}
+
+/// Parse an `--ionice` class: `idle`, or `best-effort` with an optional level 0-7 (`best-effort:7`).
+pub fn parse_io_class(input: &str) -> Result<String> {
+    let text = input.trim().to_lowercase();
+    let (class, level) = text.split_once(':').unwrap_or((text.as_str(), ""));
+    match (class, level) {
+        ("idle" | "3", "") => Ok("idle".to_string()),
+        ("best-effort" | "2", "") => Ok("best-effort:7".to_string()),
+        ("best-effort" | "2", level) if level.parse::<u8>().is_ok_and(|l| l <= 7) => Ok(format!("best-effort:{}", level)),
+        _ => Err(anyhow!("Invalid I/O class '{}': expected idle or best-effort[:0-7]", input)),
+    }
+}
+
+/// Lower ft's own CPU and I/O scheduling priority with `renice` and `ionice`.
+/// Failures are reported as warnings; ft keeps running at normal priority.
+pub fn lower_priority(nice: Option<i32>, io_class: Option<&str>) {
+    let pid = std::process::id().to_string();
+    if let Some(nice) = nice {
+        run_quietly("renice", &["-n", &nice.to_string(), "-p", &pid]);
+    }
+    if let Some(io_class) = io_class {
+        let args = match io_class.split_once(':') {
+            Some((_, level)) => vec!["-c", "2", "-n", level, "-p", &pid],
+            None => vec!["-c", "3", "-p", &pid],
+        };
+        run_quietly("ionice", &args);
+    }
+}
+
+fn run_quietly(program: &str, args: &[&str]) {
+    let status = Command::new(program)
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => eprintln!("ft: warning: {} exited with {}", program, status),
+        Err(e) => eprintln!("ft: warning: could not run {}: {}", program, e),
+    }
+}
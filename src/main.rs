@@ -1,14 +1,66 @@
-use clap::Parser;
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use is_terminal::IsTerminal;
 use std::path::PathBuf;
 
+mod alert;
+mod ansi;
+mod bell;
 mod config;
+mod delta;
 mod theme;
 mod tail;
 mod colorizer;
+mod control;
 mod filter;
+mod focus;
+mod forward;
+mod fuzzy;
+mod hooks;
+mod html;
+mod hyperlink;
+mod import;
 mod interactive;
+mod jsonview;
+mod clipboard;
+mod duration;
+mod editor;
+mod envelope;
+mod escalation;
+mod examples;
+mod expr;
+mod instance;
+mod lanes;
+mod limits;
+mod merge;
+mod metrics;
+mod multiline;
+mod notification;
+mod order;
+mod rate;
 mod output;
+mod pager;
+mod parser;
+mod reload;
+mod replay;
 mod popup;
+mod preview;
+mod project;
+mod redact;
+mod siem;
+mod sink;
+mod sqlite;
+mod squash;
+mod stats;
+mod statusbar;
+mod svg;
+mod table;
+mod throttle;
+mod timezone;
+mod top;
+mod tomltheme;
+mod update;
+mod width;
 
 use config::Config;
 
@@ -16,13 +68,16 @@ use config::Config;
 #[command(name = "ft", version)]
 #[command(about = "A modern, colorful tail replacement with split-pane log monitoring")]
 struct Cli {
-    /// Files to tail
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Files to tail ('-' reads stdin alongside them)
     #[arg(value_name = "FILE")]
     files: Vec<PathBuf>,
 
-    /// Number of lines to show initially
-    #[arg(short = 'n', long = "lines", default_value = "10")]
-    lines: usize,
+    /// Number of lines to show initially [default: 10, or all lines with --since/--until]
+    #[arg(short = 'n', long = "lines")]
+    lines: Option<usize>,
 
     /// Output the last NUM bytes instead of lines
     #[arg(short = 'c', long = "bytes")]
@@ -48,30 +103,220 @@ struct Cli {
     #[arg(long = "config")]
     config: Option<PathBuf>,
 
-    /// Disable colors
-    #[arg(long = "no-color")]
+    /// Color theme for this run, overriding the config's (user themes, then built-in ones)
+    #[arg(long = "theme", value_name = "NAME")]
+    theme: Option<String>,
+
+    /// When to color output: auto (only to a terminal; honors NO_COLOR and CLICOLOR_FORCE), always or never
+    #[arg(long = "color", value_name = "WHEN", num_args = 0..=1, require_equals = true,
+        default_missing_value = "always", value_parser = colorizer::ColorChoice::parse)]
+    color: Option<colorizer::ColorChoice>,
+
+    /// Same as --color=never
+    #[arg(long = "no-color", hide = true)]
     no_color: bool,
 
-    /// Include only lines matching this regex
+    /// Include only lines matching this regex (repeatable, see --match-mode)
     #[arg(long = "include")]
-    include: Option<String>,
+    include: Vec<String>,
 
-    /// Exclude lines matching this regex
+    /// Exclude lines matching this regex (repeatable; any match hides the line)
     #[arg(long = "exclude")]
-    exclude: Option<String>,
+    exclude: Vec<String>,
+
+    /// How multiple --include patterns combine: any (default) or all
+    #[arg(long = "match-mode", value_name = "MODE")]
+    match_mode: Option<String>,
+
+    /// Match --include/--exclude patterns case-insensitively
+    #[arg(long = "ignore-case")]
+    ignore_case: bool,
+
+    /// Treat --include/--exclude patterns as literal strings, not regexes
+    #[arg(short = 'F', long = "fixed-strings")]
+    fixed_strings: bool,
+
+    /// Show lines that do NOT match --include, like grep -v (-v stays tail's --verbose)
+    #[arg(long = "invert-match")]
+    invert_match: bool,
+
+    /// Keep every line and emphasize --include matches in the theme's match_style instead
+    #[arg(long = "highlight-matches")]
+    highlight_matches: bool,
+
+    /// Show JSON lines with keys and values colored by type, laid out inline (default),
+    /// pretty (indented over several lines) or flat (key.sub=value pairs)
+    #[arg(long = "json-view", value_name = "LAYOUT", num_args = 0..=1, require_equals = true,
+        default_missing_value = "inline", value_parser = jsonview::JsonLayout::parse)]
+    json_view: Option<jsonview::JsonLayout>,
+
+    /// Color key=value (logfmt) lines: each key in a color of its own, values by type
+    #[arg(long = "logfmt-colors")]
+    logfmt_colors: bool,
+
+    /// Color matches of REGEX for this session, on top of the theme, e.g. 'req-42=#ff5555'
+    /// or 'db[0-9]+=208' (repeatable)
+    #[arg(long = "highlight", value_name = "REGEX=COLOR")]
+    highlights: Vec<String>,
+
+    /// Open the output in `less -R +G` instead of printing it (not with -f)
+    #[arg(long = "handoff-less")]
+    handoff_less: bool,
+
+    /// When output is flushed: line, frame (each pass of the follow loop) or
+    /// batch[:SIZE][/TIME] (default: line on a terminal or with -f, else batch)
+    #[arg(long = "flush", value_name = "POLICY", value_parser = sink::FlushPolicy::parse)]
+    flush: Option<sink::FlushPolicy>,
+
+    /// Also append what ft shows to this file, after filtering and coloring
+    #[arg(long = "output-file", value_name = "PATH")]
+    output_file: Option<PathBuf>,
+
+    /// Strip colors and links from what goes to --output-file
+    #[arg(long = "output-plain", requires = "output_file")]
+    output_plain: bool,
+
+    /// Also send the lines shown to a syslog collector as RFC 5424 messages:
+    /// syslog://HOST[:PORT] (UDP), syslog+tcp://... or syslog+tls://...
+    #[arg(long = "forward", value_name = "URL")]
+    forward: Option<String>,
+
+    /// Also insert every line shown into a `records` table in the SQLite database at PATH
+    /// (timestamp, level, service, message, file, raw), to query with SQL afterwards
+    #[arg(long = "sqlite", value_name = "PATH")]
+    sqlite: Option<PathBuf>,
+
+    /// Hide bearer tokens, AWS keys, card numbers, email addresses and the config's
+    /// [redact] patterns as lines are read, before they're shown or written anywhere
+    #[arg(long = "redact")]
+    redact: bool,
+
+    /// Zero the last OCTETS (1-3, default 1) octets of IPv4 addresses, and IPv6 addresses
+    /// past /48 (/32, /16), in everything ft shows and writes
+    #[arg(long = "anonymize-ips", value_name = "OCTETS", num_args = 0..=1, require_equals = true,
+          default_missing_value = "1", value_parser = clap::value_parser!(u8).range(1..=3))]
+    anonymize_ips: Option<u8>,
+
+    /// Remove the color and other escape sequences lines already carry before ft reads them
+    #[arg(long = "strip-ansi")]
+    strip_ansi: bool,
+
+    /// While following, POST to a webhook or run a command when a line matches:
+    /// 'PATTERN=POST URL' or 'PATTERN=exec COMMAND' (repeatable, at most once per 10s each)
+    #[arg(long = "alert", value_name = "RULE")]
+    alerts: Vec<String>,
+
+    /// While following, raise a desktop notification for lines at LEVEL or more severe
+    #[arg(long = "notify-level", value_name = "LEVEL")]
+    notify_level: Option<String>,
+
+    /// While following, ring the terminal bell when a line matches REGEX (repeatable)
+    #[arg(long = "bell", value_name = "REGEX")]
+    bell: Vec<String>,
+
+    /// Print line counts by level, service and file on stderr on exit, and every
+    /// INTERVAL (e.g. --stats=30s) while following
+    #[arg(long = "stats", value_name = "INTERVAL", num_args = 0..=1, require_equals = true,
+          default_missing_value = "0s", value_parser = duration::parse_duration)]
+    stats: Option<std::time::Duration>,
+
+    /// While following, print sparklines of lines and errors per second over the last
+    /// minute on stderr every INTERVAL (default 5s)
+    #[arg(long = "rate", value_name = "INTERVAL", num_args = 0..=1, require_equals = true,
+          default_missing_value = "5s", value_parser = duration::parse_duration)]
+    rate: Option<std::time::Duration>,
+
+    /// Serve counters of lines read, matched, per level and per alert in the Prometheus
+    /// text format on ADDR (e.g. 0.0.0.0:9090), at /metrics
+    #[arg(long = "metrics-listen", value_name = "ADDR")]
+    metrics_listen: Option<String>,
+
+    /// Instead of the lines, show how often each value of FIELD (ip, status, service, a
+    /// JSON path...) occurs, as a table kept up to date while following
+    #[arg(long = "top", value_name = "FIELD")]
+    top: Option<String>,
+
+    /// Print how many lines pass the filters, per file, instead of the lines; exits 1
+    /// when none do, like grep -c
+    #[arg(short = 'C', long = "count", conflicts_with_all = ["follow", "top"])]
+    count: bool,
+
+    /// Print the whole file again with the gaps between its timestamps, to demo dashboards
+    /// or feed collectors
+    #[arg(long = "replay", conflicts_with_all = ["follow", "count", "top"])]
+    replay: bool,
+
+    /// How much faster than real time --replay runs: 10x, 0.5x
+    #[arg(long = "speed", value_name = "FACTOR", requires = "replay", default_value = "1x",
+          value_parser = replay::parse_speed)]
+    speed: f64,
 
-    /// Show only lines with specified log level (ERROR, WARN, INFO, DEBUG)
+    /// Show only lines timestamped at or after this time ("10 minutes ago", 2024-05-01T00:00)
+    #[arg(long = "since", value_name = "TIME")]
+    since: Option<String>,
+
+    /// Show only lines timestamped at or before this time
+    #[arg(long = "until", value_name = "TIME")]
+    until: Option<String>,
+
+    /// Show only lines with specified log level and above (ERROR, WARN, INFO, DEBUG),
+    /// a range like WARN..ERROR, or a list like DEBUG,NOTICE
     #[arg(long = "level")]
     level: Option<String>,
 
+    /// Match --level exactly instead of "this level and more severe"
+    #[arg(long = "level-exact")]
+    level_exact: bool,
+
+    /// Hide the levels selected by --level instead of showing them
+    #[arg(long = "level-invert")]
+    level_invert: bool,
+
+    /// Show only lines matching a filter expression, e.g.
+    /// 'level >= WARN and (service == "nginx" or msg =~ "timeout")'
+    #[arg(long = "filter", value_name = "EXPR")]
+    filter: Option<String>,
+
+    /// Show only JSON lines whose field matches, e.g. status=500 or latency_ms>=250;
+    /// nested keys use dots (user.id). Repeatable; all must match
+    #[arg(long = "field", value_name = "KEY=VALUE")]
+    fields: Vec<String>,
+
+    /// Compare a field found in the line, e.g. 'status_code >= 500' or 'duration_ms > 1000'.
+    /// Looks in named --include capture groups, JSON, key=value pairs and parsed fields. Repeatable
+    #[arg(long = "where", value_name = "CONDITION")]
+    wheres: Vec<String>,
+
+    /// Apply a named filter preset from a [preset.NAME] table in the config (repeatable)
+    #[arg(long = "preset", value_name = "NAME")]
+    presets: Vec<String>,
+
     /// Interactive mode with keyboard controls
     #[arg(short = 'i', long = "interactive")]
     interactive: bool,
 
-    /// Output format: text (default), json, csv
+    /// Output format: text (default), json, json-pretty, ndjson, csv, html, cef, leef, table
     #[arg(long = "format", default_value = "text")]
     format: String,
 
+    /// Add `raw` and `colored` (the line with ANSI colors) fields to JSON output;
+    /// colors are kept through pipes unless --color=never
+    #[arg(long = "json-colored")]
+    json_colored: bool,
+
+    /// Lay out json/csv output with the [schema.NAME] columns from the config
+    #[arg(long = "schema", value_name = "NAME")]
+    schema: Option<String>,
+
+    /// CSV columns, in order: parsed field names (dotted paths for nested JSON), source or message
+    #[arg(long = "csv-columns", value_name = "LIST", value_delimiter = ',', conflicts_with = "schema")]
+    csv_columns: Vec<String>,
+
+    /// Log format for parsed fields: auto (default), cri, json, logfmt, syslog, accesslog
+    /// (or access-log), generic or a [parser.NAME] from the config
+    #[arg(long = "input-format", value_name = "FORMAT")]
+    input_format: Option<String>,
+
     /// Buffer size for file operations (in bytes)
     #[arg(long = "buffer-size", default_value = "65536")]
     buffer_size: usize,
@@ -79,16 +324,390 @@ struct Cli {
     /// Maximum lines to keep in scrollback buffer per window
     #[arg(long = "buffer-lines", default_value = "1000")]
     buffer_lines: usize,
+
+    /// Scrollback kept per window and by -i: a line count (5000) or a size (64M); the
+    /// oldest lines are dropped past it
+    #[arg(long = "scrollback", value_name = "LINES|SIZE", value_parser = limits::parse_scrollback, conflicts_with = "buffer_lines")]
+    scrollback: Option<limits::Scrollback>,
+
+    /// Color the value of this field by hash so equal values share a color (repeatable)
+    #[arg(long = "hash-field", value_name = "FIELD")]
+    hash_fields: Vec<String>,
+
+    /// Give each thread/PID its own indented, colored lane: field=NAME or pid
+    #[arg(long = "lanes", value_name = "SPEC")]
+    lanes: Option<String>,
+
+    /// Interleave all sources (files and '-' for stdin) into one [name]-prefixed stream instead of split panes
+    #[arg(long = "merge")]
+    merge: bool,
+
+    /// Merge multi-file output by timestamp, holding lines up to this long to absorb clock skew (e.g. 2s).
+    /// Implies --merge
+    #[arg(long = "max-skew", value_name = "DURATION", value_parser = duration::parse_duration)]
+    max_skew: Option<std::time::Duration>,
+
+    /// Mark lines whose timestamp jumps backwards within the same source
+    #[arg(long = "order-guard")]
+    order_guard: bool,
+
+    /// Prefix each line with the time since the previous one, from their timestamps or
+    /// when they were read; gaps of a second or more stand out
+    #[arg(long = "delta")]
+    delta: bool,
+
+    /// Badge bursts of WARN-or-worse lines with escalating colors (thresholds from the theme)
+    #[arg(long = "escalate")]
+    escalate: bool,
+
+    /// Mark whole lines at LEVEL or worse (default ERROR) with the theme's emphasis_style (bold)
+    #[arg(long = "emphasize", value_name = "LEVEL", num_args = 0..=1, require_equals = true,
+        default_missing_value = "ERROR", value_parser = filter::LogLevel::parse)]
+    emphasize: Option<filter::LogLevel>,
+
+    /// Show only these parts of JSON lines, comma separated: jq-style paths like '.msg' or '.ts, .user.id, .tags[0]'
+    #[arg(long = "jq", value_name = "PATHS", value_parser = project::Projection::parse_jq,
+        conflicts_with_all = ["project_fields", "json_view"])]
+    jq: Option<project::Projection>,
+
+    /// Show only these parsed fields of each line, comma separated (e.g. timestamp,level,msg)
+    #[arg(long = "fields", value_name = "LIST", value_parser = project::Projection::parse_fields,
+        conflicts_with = "json_view")]
+    project_fields: Option<project::Projection>,
+
+    /// Color each line's timestamp by its age: green under 1m, yellow under 10m, gray after (theme `age:` rules)
+    #[arg(long = "age-colors")]
+    age_colors: bool,
+
+    /// Show timestamps in UTC, whatever zone they were written in
+    #[arg(long = "utc", conflicts_with_all = ["local", "tz"])]
+    utc: bool,
+
+    /// Show timestamps in the local time zone
+    #[arg(long = "local", conflicts_with = "tz")]
+    local: bool,
+
+    /// Show timestamps in ZONE, an IANA name such as Europe/Berlin
+    #[arg(long = "tz", value_name = "ZONE", value_parser = timezone::DisplayZone::parse)]
+    tz: Option<timezone::DisplayZone>,
+
+    /// Show timestamps as how long ago they were (e.g. 3m12s ago), as of when each line is shown
+    #[arg(long = "relative")]
+    relative: bool,
+
+    /// Prefix lines with their line number in the source (a field or column in JSON, CSV and tables)
+    #[arg(long = "line-numbers")]
+    line_numbers: bool,
+
+    /// Prefix lines with the byte offset they start at in the source (a field or column in JSON, CSV and tables)
+    #[arg(long = "byte-offsets")]
+    byte_offsets: bool,
+
+    /// Make URLs and existing file paths clickable with OSC 8 links (only on a terminal, with colors on)
+    #[arg(long = "hyperlinks")]
+    hyperlinks: bool,
+
+    /// Group stack traces and other continuation lines with the line that starts them
+    #[arg(long = "multiline")]
+    multiline: bool,
+
+    /// Start a new multiline record at each line matching this regex (implies --multiline)
+    #[arg(long = "multiline-start", value_name = "REGEX")]
+    multiline_start: Option<String>,
+
+    /// Once PATTERN first matches, show only lines mentioning the matched text
+    /// (or its first capture group, e.g. a request id); press r to release
+    #[arg(long = "focus", value_name = "PATTERN")]
+    focus: Option<String>,
+
+    /// Name stdin in headers, prefixes and structured output (default: stdin)
+    #[arg(long = "stdin-label", value_name = "NAME")]
+    stdin_label: Option<String>,
+
+    /// Collapse consecutive identical lines (ignoring timestamps) into one line
+    /// annotated with "(repeated N×)"
+    #[arg(long = "squash-repeats")]
+    squash_repeats: bool,
+
+    /// Show only every Nth matching line, e.g. 1/100
+    #[arg(long = "sample", value_name = "1/N", value_parser = throttle::parse_sample)]
+    sample: Option<usize>,
+
+    /// Show at most this many lines while following, e.g. 200/s (or /m, /h);
+    /// the excess is dropped and counted in a summary line
+    #[arg(long = "max-rate", value_name = "RATE", value_parser = throttle::parse_rate)]
+    max_rate: Option<throttle::Rate>,
+
+    /// Exit after printing NUM lines that pass the filters (use -n 0 to count only new lines)
+    #[arg(short = 'm', long = "max-count", value_name = "NUM")]
+    max_count: Option<usize>,
+
+    /// Write ft's pid to PATH, refusing to start if a running ft already owns it
+    #[arg(long = "pidfile", value_name = "PATH")]
+    pidfile: Option<PathBuf>,
+
+    /// Accept `ft ctl` commands on a Unix socket [default: ft.sock in the runtime dir]
+    #[arg(long = "control", value_name = "PATH", num_args = 0..=1, require_equals = true, default_missing_value = "")]
+    control: Option<PathBuf>,
+
+    /// Refuse to start while another ft with the same --single-instance=KEY runs
+    /// (default key: the config file plus any --preset names)
+    #[arg(long = "single-instance", value_name = "KEY", num_args = 0..=1, require_equals = true, default_missing_value = "")]
+    single_instance: Option<String>,
+
+    /// Cap ft's buffers (file reads and scrollback) at about SIZE, e.g. 256M
+    #[arg(long = "max-memory", value_name = "SIZE", value_parser = limits::parse_size)]
+    max_memory: Option<usize>,
+
+    /// Lower ft's CPU priority (niceness, default 10)
+    #[arg(long = "nice", value_name = "N", num_args = 0..=1, require_equals = true, default_missing_value = "10")]
+    nice: Option<i32>,
+
+    /// Lower ft's I/O priority: idle (default) or best-effort[:0-7]
+    #[arg(long = "ionice", value_name = "CLASS", num_args = 0..=1, require_equals = true,
+        default_missing_value = "idle", value_parser = limits::parse_io_class)]
+    ionice: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show categorized example invocations
+    Examples {
+        /// Only show categories whose name contains TOPIC (e.g. filtering)
+        topic: Option<String>,
+
+        /// Run example N against a generated sample log
+        #[arg(long = "run", value_name = "N")]
+        run: Option<usize>,
+    },
+    /// Work with color themes
+    Themes {
+        #[command(subcommand)]
+        action: ThemesCommand,
+    },
+    /// Control an ft started with --control
+    Ctl {
+        /// Socket the running ft listens on [default: ft.sock in the runtime dir]
+        #[arg(long = "socket", value_name = "PATH")]
+        socket: Option<PathBuf>,
+
+        #[command(subcommand)]
+        action: CtlCommand,
+    },
+    /// Replace this ft with the newest release from GitHub
+    SelfUpdate {
+        /// Release channel: stable or nightly
+        #[arg(long = "channel", default_value = "stable", value_parser = update::Channel::parse)]
+        channel: update::Channel,
+
+        /// Only report whether an update is available
+        #[arg(long = "check")]
+        check: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CtlCommand {
+    /// Change the running filter; settings not given are kept
+    SetFilter {
+        /// Include only lines matching this regex (repeatable; replaces the running patterns)
+        #[arg(long = "include")]
+        include: Vec<String>,
+
+        /// Exclude lines matching this regex (repeatable; replaces the running patterns)
+        #[arg(long = "exclude")]
+        exclude: Vec<String>,
+
+        /// How multiple --include patterns combine: any or all
+        #[arg(long = "match-mode", value_name = "MODE")]
+        match_mode: Option<String>,
+
+        /// Minimum log level, as for ft --level
+        #[arg(long = "level")]
+        level: Option<String>,
+
+        /// Filter expression, as for ft --filter
+        #[arg(long = "filter", value_name = "EXPR")]
+        filter: Option<String>,
+
+        /// Drop the running patterns, level and expression first
+        #[arg(long = "clear")]
+        clear: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ThemesCommand {
+    /// Render colorized output of FILE (or a sample log) as an image
+    Render {
+        /// Write an SVG snapshot to PATH
+        #[arg(long = "svg", value_name = "PATH")]
+        svg: PathBuf,
+
+        /// Theme to render with [default: the configured theme]
+        #[arg(long = "theme")]
+        theme: Option<String>,
+
+        /// Number of lines to render
+        #[arg(short = 'n', long = "lines", default_value = "20")]
+        lines: usize,
+
+        /// Log file to render the last lines of
+        file: Option<PathBuf>,
+    },
+    /// List the themes ft can find and where each comes from
+    List,
+    /// Show FILE (or a sample log) colored with a theme, followed by its rules
+    Preview {
+        /// Theme to preview [default: the configured theme]
+        theme: Option<String>,
+
+        /// Number of lines to show
+        #[arg(short = 'n', long = "lines", default_value = "20")]
+        lines: usize,
+
+        /// Log file to show the last lines of
+        #[arg(long = "file", value_name = "FILE")]
+        file: Option<PathBuf>,
+    },
+    /// Report every problem in a theme file, with line numbers
+    Check {
+        /// Theme file, or the name of an installed or built-in theme
+        theme: String,
+    },
+    /// Print a built-in theme, or write built-ins into DIR to start your own from
+    Export {
+        /// Built-in theme to export [default: all of them, with --dir]
+        name: Option<String>,
+
+        /// Write ft.conf.NAME files into DIR instead of printing
+        #[arg(long = "dir", value_name = "DIR")]
+        dir: Option<PathBuf>,
+
+        /// Overwrite theme files already in DIR
+        #[arg(long = "force")]
+        force: bool,
+    },
+    /// Convert a grc or ccze colorizer config into an ft theme, printed to stdout
+    Import {
+        /// Format of FILE: grc or ccze
+        #[arg(long = "from", value_name = "FORMAT", value_parser = import::ImportFormat::parse)]
+        from: import::ImportFormat,
+
+        /// The grc conf.* or ccze.conf file to convert
+        file: PathBuf,
+    },
 }
 
 fn main() -> anyhow::Result<()> {
     let args = Cli::parse();
 
+    if let Some(Command::Examples { topic, run }) = &args.command {
+        return match run {
+            Some(number) => examples::run(*number),
+            None => examples::print(topic.as_deref()),
+        };
+    }
+
+    if let Some(Command::SelfUpdate { channel, check }) = &args.command {
+        return update::self_update(*channel, *check);
+    }
+
+    if let Some(Command::Ctl { socket, action }) = args.command {
+        let CtlCommand::SetFilter { include, exclude, match_mode, level, filter, clear } = action;
+        let update = control::FilterUpdate {
+            include: (!include.is_empty()).then_some(include),
+            exclude: (!exclude.is_empty()).then_some(exclude),
+            match_mode,
+            level,
+            filter,
+            clear,
+        };
+        return control::send(&socket.unwrap_or_else(control::default_socket_path), &update);
+    }
+
     // Load configuration
-    let config = Config::load(args.config.as_deref())?;
+    let mut config = Config::load(args.config.as_deref())?;
+    if let Some(theme) = &args.theme {
+        config.general.theme = theme.clone();
+    }
+
+    if let Some(Command::Themes { action }) = &args.command {
+        match action {
+            ThemesCommand::Render { svg, theme, lines, file } => {
+                svg::snapshot(&config, theme.as_deref(), file.as_deref(), *lines, svg)?;
+                println!("Wrote {}", svg.display());
+            }
+            ThemesCommand::List => {
+                for (name, path) in theme::Theme::available(&config) {
+                    let active = if name == config.general.theme { "*" } else { " " };
+                    let origin = path.map(|path| path.display().to_string()).unwrap_or_else(|| "built-in".to_string());
+                    println!("{} {:<16} {}", active, name, origin);
+                }
+            }
+            ThemesCommand::Preview { theme, lines, file } => {
+                preview::preview(&config, theme.as_deref(), file.as_deref(), *lines)?;
+            }
+            ThemesCommand::Check { theme } => {
+                let path = Some(PathBuf::from(theme))
+                    .filter(|path| path.exists())
+                    .or_else(|| config.get_theme_path(theme));
+                let (label, contents) = match (path, theme::Theme::builtin_source(theme)) {
+                    (Some(path), _) => (path.display().to_string(), theme::Theme::read_file(&path)
+                        .with_context(|| format!("{} won't load", path.display()))?),
+                    (None, Some(source)) => (format!("{} (built-in)", theme), source.to_string()),
+                    (None, None) => return Err(anyhow::anyhow!("No theme file or theme named '{}'", theme)),
+                };
+                let diagnostics = theme::Theme::check(&config, &contents);
+                let toml = label.ends_with(".toml");
+                for diagnostic in &diagnostics {
+                    let kind = if diagnostic.error { "error" } else { "warning" };
+                    let message = diagnostic.message.replace('\n', "\n    ");
+                    if toml {
+                        // Line numbers are of the translated text, so name the setting instead
+                        let setting = contents.lines().nth(diagnostic.line - 1).unwrap_or_default();
+                        println!("{}: {}: {}\n    in: {}", label, kind, message, setting);
+                    } else {
+                        println!("{}:{}: {}: {}", label, diagnostic.line, kind, message);
+                    }
+                }
+                let errors = diagnostics.iter().filter(|d| d.error).count();
+                if errors > 0 {
+                    return Err(anyhow::anyhow!("{} has {} error(s) and won't load", label, errors));
+                }
+                if diagnostics.is_empty() {
+                    println!("{}: OK", label);
+                }
+            }
+            ThemesCommand::Export { name, dir, force } => {
+                theme::Theme::export_builtins(name.as_deref(), dir.as_deref(), *force)?;
+            }
+            ThemesCommand::Import { from, file } => {
+                print!("{}", import::import(*from, file)?);
+            }
+        }
+        return Ok(());
+    }
+
+    let lock_key = match args.single_instance.as_deref() {
+        Some("") => {
+            let config_file = match &args.config {
+                Some(path) => path.clone(),
+                None => Config::default_config_path()?,
+            };
+            let config_file = config_file.canonicalize().unwrap_or(config_file);
+            Some(std::iter::once(config_file.display().to_string())
+                .chain(args.presets.iter().cloned())
+                .collect::<Vec<_>>()
+                .join("+"))
+        }
+        key => key.map(str::to_string),
+    };
+    let _instance = instance::InstanceGuard::acquire(args.pidfile.as_deref(), lock_key.as_deref())?;
+    limits::lower_priority(args.nice, args.ionice.as_deref());
 
     // Auto-follow for multiple files (like multitail), unless --no-follow
-    let follow = if args.no_follow {
+    let follow = if args.no_follow || args.count || args.replay {
         false
     } else if args.files.len() > 1 {
         true
@@ -96,32 +715,135 @@ fn main() -> anyhow::Result<()> {
         args.follow
     };
 
+    // A time window, counting or a replay covers everything unless -n says otherwise
+    let lines = args.lines.unwrap_or(if args.since.is_some() || args.until.is_some() || args.count || args.replay {
+        usize::MAX
+    } else {
+        10
+    });
+
+    let color = match args.color {
+        _ if args.no_color => colorizer::ColorChoice::Never,
+        Some(choice) => choice,
+        // These carry colors in the output itself rather than writing escapes to a terminal
+        None if args.json_colored || args.format.eq_ignore_ascii_case("html") => colorizer::ColorChoice::Always,
+        None => colorizer::ColorChoice::Auto,
+    };
+    let color = color.enabled(std::io::stdout().is_terminal());
+    // The interactive UI's status bars and popups draw through crossterm
+    crossterm::style::force_color_output(color);
+
+    // OSC 8 links would end up inside json/csv/html output
+    let text_output = args.format.eq_ignore_ascii_case("text");
+
     // Initialize tail processor
-    let mut tail_processor = tail::TailProcessor::new(
-        config,
-        args.no_color,
-        args.include,
-        args.exclude,
-        args.level,
-        args.interactive,
-        args.format,
-        args.buffer_size,
-        args.bytes,
-        args.quiet,
-        args.verbose,
-        args.buffer_lines,
-    )?;
+    let options = tail::TailOptions {
+        no_color: !color,
+        filter: filter::FilterOptions {
+            includes: args.include,
+            excludes: args.exclude,
+            match_mode: args.match_mode,
+            level: args.level,
+            ignore_case: args.ignore_case,
+            fixed_strings: args.fixed_strings,
+            invert_match: args.invert_match,
+            highlight_matches: args.highlight_matches,
+            since: args.since,
+            until: args.until,
+            level_exact: args.level_exact,
+            level_invert: args.level_invert,
+            expression: args.filter,
+            fields: args.fields,
+            wheres: args.wheres,
+        },
+        interactive: args.interactive,
+        format: args.format,
+        buffer_size: args.buffer_size,
+        quiet: args.quiet,
+        verbose: args.verbose,
+        max_buffer_lines: match args.scrollback {
+            Some(limits::Scrollback::Lines(lines)) => lines,
+            _ => args.buffer_lines,
+        },
+        scrollback_bytes: match args.scrollback {
+            Some(limits::Scrollback::Bytes(bytes)) => Some(bytes),
+            _ => None,
+        },
+        hash_fields: args.hash_fields,
+        json_view: args.json_view,
+        logfmt_colors: args.logfmt_colors,
+        lanes: args.lanes,
+        max_skew: args.max_skew,
+        order_guard: args.order_guard,
+        delta: args.delta,
+        escalate: args.escalate,
+        emphasize: args.emphasize,
+        age_colors: args.age_colors,
+        zone: match (args.utc, args.local) {
+            (true, _) => Some(timezone::DisplayZone::Utc),
+            (_, true) => Some(timezone::DisplayZone::Local),
+            _ => args.tz,
+        },
+        relative: args.relative,
+        line_numbers: args.line_numbers,
+        byte_offsets: args.byte_offsets,
+        projection: args.jq.or(args.project_fields),
+        hyperlinks: (args.hyperlinks || config.general.hyperlinks.unwrap_or(false)) && color && std::io::stdout().is_terminal() && text_output,
+        multiline: args.multiline,
+        multiline_start: args.multiline_start,
+        focus: args.focus,
+        stdin_label: args.stdin_label,
+        squash_repeats: args.squash_repeats,
+        merge: args.merge,
+        sample: args.sample,
+        max_rate: args.max_rate,
+        max_count: args.max_count,
+        presets: args.presets,
+        max_memory: args.max_memory,
+        highlights: args.highlights,
+        handoff_less: args.handoff_less && !follow && std::io::stdout().is_terminal(),
+        schema: args.schema,
+        csv_columns: args.csv_columns,
+        json_colored: args.json_colored,
+        input_format: args.input_format,
+        flush: args.flush.unwrap_or_else(|| sink::FlushPolicy::default_for_stdout(std::io::stdout().is_terminal(), follow)),
+        output_file: args.output_file,
+        output_plain: args.output_plain,
+        forward: args.forward,
+        sqlite: args.sqlite,
+        redact: args.redact,
+        anonymize_ips: args.anonymize_ips.map(usize::from),
+        strip_ansi: args.strip_ansi,
+        alerts: args.alerts,
+        notify_level: args.notify_level,
+        bell: args.bell,
+        stats: args.stats,
+        rate: args.rate,
+        metrics_listen: args.metrics_listen,
+        top: args.top,
+        count: args.count,
+        replay: args.replay.then_some(args.speed),
+        control: args.control.map(|path| {
+            if path.as_os_str().is_empty() { control::default_socket_path() } else { path }
+        }),
+    };
+    let mut tail_processor = tail::TailProcessor::new(config, options)?;
+    tail_processor.start(&args.files);
 
     if args.files.is_empty() {
-        use is_terminal::IsTerminal;
         if std::io::stdin().is_terminal() {
-            tail_processor.show_default_logs(args.lines)?;
+            tail_processor.show_default_logs(lines)?;
         } else {
-            tail_processor.process_stdin(args.lines, follow)?;
+            tail_processor.process_stdin(lines, follow)?;
         }
     } else {
-        tail_processor.process_files(&args.files, args.lines, follow)?;
+        tail_processor.process_files(&args.files, lines, follow)?;
     }
 
+    tail_processor.finish()?;
+
+    if tail_processor.counted() == Some(0) {
+        std::process::exit(1);
+    }
     Ok(())
 }
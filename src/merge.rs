@@ -0,0 +1,95 @@
+use chrono::{DateTime, Local};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::time::{Duration, Instant};
+
+/// How fast a source's skew estimate drifts upward when samples exceed it.
+const SKEW_DRIFT: f64 = 0.05;
+
+/// Reorders lines from several sources by timestamp, tolerating clock skew between them.
+///
+/// Each source's skew is estimated from the gap between when its lines arrive and the
+/// time they claim to have been written. The smallest gap seen is the best estimate
+/// (clock offset plus minimum latency); larger gaps only nudge it upward slowly so a
+/// burst of delayed lines doesn't distort it. Lines are held for up to `max_skew` and
+/// released in order of their skew-corrected timestamps.
+pub struct SkewMerger {
+    max_skew: Duration,
+    skews_ms: Vec<Option<f64>>,
+    pending: BinaryHeap<Reverse<PendingLine>>,
+    seq: u64,
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct PendingLine {
+    key_ms: i64,
+    seq: u64,
+    arrived: Instant,
+    source: usize,
+    text: String,
+}
+
+impl SkewMerger {
+    pub fn new(max_skew: Duration) -> Self {
+        Self {
+            max_skew,
+            skews_ms: Vec::new(),
+            pending: BinaryHeap::new(),
+            seq: 0,
+        }
+    }
+
+    /// Queue a line from `source`. `parsed` is the timestamp found in the line, if any.
+    pub fn push(&mut self, source: usize, parsed: Option<DateTime<Local>>, text: String) {
+        let now = Local::now();
+        if self.skews_ms.len() <= source {
+            self.skews_ms.resize(source + 1, None);
+        }
+
+        let key_ms = match parsed {
+            Some(ts) => {
+                let sample = (now - ts).num_milliseconds() as f64;
+                let skew = match self.skews_ms[source] {
+                    Some(prev) if sample < prev => sample,
+                    Some(prev) => prev + SKEW_DRIFT * (sample - prev),
+                    None => sample,
+                };
+                self.skews_ms[source] = Some(skew);
+                ts.timestamp_millis() + skew as i64
+            }
+            // No timestamp: order by arrival
+            None => now.timestamp_millis(),
+        };
+
+        self.seq += 1;
+        self.pending.push(Reverse(PendingLine {
+            key_ms,
+            seq: self.seq,
+            arrived: Instant::now(),
+            source,
+            text,
+        }));
+    }
+
+    /// Release lines that have waited at least `max_skew`, oldest corrected time first.
+    pub fn drain_ready(&mut self) -> Vec<(usize, String)> {
+        let mut ready = Vec::new();
+        while let Some(Reverse(next)) = self.pending.peek() {
+            if next.arrived.elapsed() < self.max_skew {
+                break;
+            }
+            let Reverse(line) = self.pending.pop().unwrap();
+            ready.push((line.source, line.text));
+        }
+        ready
+    }
+
+    /// Release everything still buffered, in corrected order.
+    pub fn drain_all(&mut self) -> Vec<(usize, String)> {
+        let mut all = Vec::with_capacity(self.pending.len());
+        while let Some(Reverse(line)) = self.pending.pop() {
+            all.push((line.source, line.text));
+        }
+        all
+    }
+}
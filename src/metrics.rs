@@ -0,0 +1,123 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::alert::AlertCounters;
+use crate::filter::LogLevel;
+
+/// How long a scraper gets to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `--metrics-listen`: counters of the lines ft reads, served over HTTP in the
+/// Prometheus text format from a background thread.
+pub struct Metrics {
+    counts: Arc<Mutex<Counts>>,
+}
+
+#[derive(Default)]
+struct Counts {
+    /// Lines read and matched per source
+    files: BTreeMap<String, (u64, u64)>,
+    levels: BTreeMap<String, u64>,
+}
+
+impl Metrics {
+    /// Listen on `address` (e.g. `0.0.0.0:9090`) and answer scrapes until ft exits.
+    pub fn listen(address: &str, alerts: Vec<(String, Arc<AlertCounters>)>) -> Result<Self> {
+        let listener = TcpListener::bind(address)
+            .with_context(|| format!("Failed to listen for metrics on {}", address))?;
+        let counts = Arc::new(Mutex::new(Counts::default()));
+        let started = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+        let shared = counts.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let body = render(&shared.lock().unwrap(), &alerts, started);
+                // One slow scraper shouldn't hold up the next
+                thread::spawn(move || respond(stream, &body));
+            }
+        });
+        Ok(Self { counts })
+    }
+
+    /// Count a line read from `path`, its level, and whether it passed the filters.
+    pub fn observe(&self, path: &Path, level: Option<LogLevel>, matched: bool) {
+        let mut counts = self.counts.lock().unwrap();
+        let file = counts.files.entry(path.display().to_string()).or_default();
+        file.0 += 1;
+        file.1 += u64::from(matched);
+        if let Some(level) = level {
+            *counts.levels.entry(format!("{:?}", level).to_uppercase()).or_default() += 1;
+        }
+    }
+}
+
+/// Answer `GET /metrics` (or `/`) with the exposition, anything else with 404.
+fn respond(stream: TcpStream, body: &str) {
+    let _ = stream.set_read_timeout(Some(REQUEST_TIMEOUT));
+    let mut reader = BufReader::new(&stream);
+    let mut request = String::new();
+    if reader.read_line(&mut request).is_err() {
+        return;
+    }
+    // Skip the headers; nothing in them matters
+    let mut header = String::new();
+    while reader.read_line(&mut header).is_ok_and(|n| n > 0) && header.trim_end() != "" {
+        header.clear();
+    }
+    let path = request.split_whitespace().nth(1).unwrap_or("/");
+    let path = path.split('?').next().unwrap_or(path);
+    let (status, body) = match path {
+        "/" | "/metrics" => ("200 OK", body),
+        _ => ("404 Not Found", "not found\n"),
+    };
+    let mut stream = &stream;
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, body.len(), body
+    );
+}
+
+fn render(counts: &Counts, alerts: &[(String, Arc<AlertCounters>)], started: f64) -> String {
+    let mut out = String::new();
+    let mut family = |name: &str, kind: &str, help: &str, samples: Vec<(String, String)>| {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} {}", name, kind);
+        for (labels, value) in samples {
+            let _ = writeln!(out, "{}{} {}", name, labels, value);
+        }
+    };
+    family("ft_start_time_seconds", "gauge", "When ft started, in seconds since the epoch.",
+        vec![(String::new(), started.to_string())]);
+    family("ft_lines_read_total", "counter", "Lines read, per file.",
+        counts.files.iter().map(|(file, (read, _))| (labels(&[("file", file)]), read.to_string())).collect());
+    family("ft_lines_matched_total", "counter", "Lines that passed the filters, per file.",
+        counts.files.iter().map(|(file, (_, matched))| (labels(&[("file", file)]), matched.to_string())).collect());
+    family("ft_lines_by_level_total", "counter", "Lines read, per detected level.",
+        counts.levels.iter().map(|(level, count)| (labels(&[("level", level)]), count.to_string())).collect());
+    if !alerts.is_empty() {
+        family("ft_alert_matches_total", "counter", "Lines matching each alert rule's pattern.",
+            alerts.iter().map(|(rule, c)| (labels(&[("rule", rule)]), c.matched.load(Ordering::Relaxed).to_string())).collect());
+        family("ft_alerts_fired_total", "counter", "Times each alert rule's action ran.",
+            alerts.iter().map(|(rule, c)| (labels(&[("rule", rule)]), c.fired.load(Ordering::Relaxed).to_string())).collect());
+    }
+    out
+}
+
+/// `{name="value",...}` with values escaped as the text format wants.
+fn labels(pairs: &[(&str, &str)]) -> String {
+    let pairs: Vec<String> = pairs.iter()
+        .map(|(name, value)| {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+            format!("{}=\"{}\"", name, value)
+        })
+        .collect();
+    format!("{{{}}}", pairs.join(","))
+}
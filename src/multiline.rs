@@ -0,0 +1,79 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::time::{Duration, Instant};
+
+/// How long a partial record may sit idle in follow mode before it is emitted anyway.
+pub const IDLE_FLUSH: Duration = Duration::from_millis(300);
+
+/// Groups physical lines into logical records (e.g. a log line plus its stack trace)
+/// so filtering, level detection, structured output and colorization see the whole record.
+#[derive(Clone)]
+pub struct MultilineGrouper {
+    start: Option<Regex>,
+    continuation: Regex,
+    pending: Option<String>,
+    last_push: Instant,
+}
+
+impl MultilineGrouper {
+    /// With `start`, a line matching it begins a new record and every other line
+    /// continues the current one. Without it, indented lines and common traceback
+    /// lines (`Caused by:`, `Traceback ...`, `FooError: ...`) are treated as continuations.
+    pub fn new(start: Option<&str>) -> Result<Self> {
+        let start = start
+            .map(|pattern| Regex::new(pattern).with_context(|| format!("Invalid multiline start regex: {}", pattern)))
+            .transpose()?;
+
+        Ok(Self {
+            start,
+            continuation: Regex::new(
+                r"^(?:\s|Caused by:|Traceback \(most recent call last\):|\.\.\. \d+ (?:more|common frames omitted)|[\w.$]*(?:Error|Exception)\b)",
+            )
+            .unwrap(),
+            pending: None,
+            last_push: Instant::now(),
+        })
+    }
+
+    fn starts_record(&self, line: &str) -> bool {
+        match &self.start {
+            Some(start) => start.is_match(line),
+            None => !self.continuation.is_match(line),
+        }
+    }
+
+    /// Add a line. Returns the previous record once this line starts a new one.
+    pub fn push(&mut self, line: String) -> Option<String> {
+        self.last_push = Instant::now();
+        let starts = self.starts_record(&line);
+        match self.pending.as_mut() {
+            Some(pending) if !starts => {
+                pending.push('\n');
+                pending.push_str(&line);
+                None
+            }
+            _ => self.pending.replace(line),
+        }
+    }
+
+    /// Emit the buffered record, if any.
+    pub fn flush(&mut self) -> Option<String> {
+        self.pending.take()
+    }
+
+    /// Emit the buffered record if nothing has arrived for `IDLE_FLUSH`.
+    pub fn flush_if_idle(&mut self) -> Option<String> {
+        if self.last_push.elapsed() >= IDLE_FLUSH {
+            self.flush()
+        } else {
+            None
+        }
+    }
+
+    /// Group a complete batch of lines into records.
+    pub fn group_all(&mut self, lines: Vec<String>) -> Vec<String> {
+        let mut records: Vec<String> = lines.into_iter().filter_map(|line| self.push(line)).collect();
+        records.extend(self.flush());
+        records
+    }
+}
@@ -0,0 +1,80 @@
+use anyhow::{anyhow, Result};
+use std::cell::Cell;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::filter::{detect_log_level, LogLevel};
+
+/// Notifications closer together than this are held back and counted in the next one
+const COOLDOWN: Duration = Duration::from_secs(5);
+
+/// `--notify-level`: a desktop notification for lines at a level or more severe that
+/// arrive while following, so ft can sit in a background terminal.
+pub struct Notifier {
+    level: LogLevel,
+    last_sent: Cell<Option<Instant>>,
+    /// Lines that qualified during the cooldown
+    held: Cell<usize>,
+}
+
+impl Notifier {
+    pub fn new(level: &str) -> Result<Self> {
+        if !cfg!(feature = "notifications") {
+            return Err(anyhow!("This ft was built without desktop notifications (the `notifications` feature)"));
+        }
+        let level = LogLevel::from_name(level)
+            .ok_or_else(|| anyhow!("Invalid --notify-level '{}': expected a level such as ERROR or WARN", level))?;
+        Ok(Self { level, last_sent: Cell::new(None), held: Cell::new(0) })
+    }
+
+    /// Notify about the most severe of `lines`, newly read from `path`, if one qualifies.
+    pub fn check(&self, path: &Path, lines: &[String]) {
+        let Some((line, level)) = lines.iter()
+            .filter_map(|line| detect_log_level(line).map(|level| (line, level)))
+            .filter(|(_, level)| level.priority() <= self.level.priority())
+            .min_by_key(|(_, level)| level.priority())
+        else {
+            return;
+        };
+        if self.last_sent.get().is_some_and(|at| at.elapsed() < COOLDOWN) {
+            self.held.set(self.held.get() + 1);
+            return;
+        }
+        self.last_sent.set(Some(Instant::now()));
+        let held = self.held.replace(0);
+        let name = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned());
+        let mut summary = format!("{} in {}", format!("{:?}", level).to_uppercase(), name);
+        if held > 0 {
+            summary.push_str(&format!(" (+{} more)", held));
+        }
+        show(summary, line.clone(), level);
+    }
+}
+
+#[cfg(feature = "notifications")]
+fn show(summary: String, body: String, level: LogLevel) {
+    let urgency = if level.priority() <= LogLevel::Critical.priority() {
+        notify_rust::Urgency::Critical
+    } else {
+        notify_rust::Urgency::Normal
+    };
+    // Talking to the notification daemon can block; never hold up the tail for it
+    std::thread::spawn(move || {
+        let mut notification = notify_rust::Notification::new();
+        notification.appname("ft").summary(&summary).body(&body);
+        #[cfg(all(unix, not(target_os = "macos")))]
+        notification.urgency(urgency);
+        #[cfg(not(all(unix, not(target_os = "macos"))))]
+        let _ = urgency;
+        // Once: without a notification daemon every line would say so again
+        static REPORTED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+        if let Err(e) = notification.show() {
+            if !REPORTED.swap(true, std::sync::atomic::Ordering::Relaxed) {
+                eprintln!("ft: desktop notification failed: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(not(feature = "notifications"))]
+fn show(_summary: String, _body: String, _level: LogLevel) {}
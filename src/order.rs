@@ -0,0 +1,34 @@
+use chrono::{DateTime, Local};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::duration::format_duration;
+
+/// Detects timestamps that jump backwards within a single source (clock resets,
+/// mixed writers) so the discontinuity can be annotated instead of silently shown.
+pub struct OrderGuard {
+    last_seen: HashMap<PathBuf, DateTime<Local>>,
+}
+
+impl OrderGuard {
+    pub fn new() -> Self {
+        Self {
+            last_seen: HashMap::new(),
+        }
+    }
+
+    /// Record a line's timestamp. Returns a marker to prefix the line with when its
+    /// timestamp is earlier than the previous one from the same source.
+    pub fn check(&mut self, source: &Path, timestamp: Option<DateTime<Local>>, color: bool) -> Option<String> {
+        let ts = timestamp?;
+        let previous = self.last_seen.insert(source.to_path_buf(), ts)?;
+        let jump = (previous - ts).to_std().ok().filter(|d| !d.is_zero())?;
+
+        let label = format!("⟲ -{}", format_duration(jump));
+        Some(if color {
+            format!("\x1b[1;7m{}\x1b[0m ", label)
+        } else {
+            format!("[{}] ", label)
+        })
+    }
+}
@@ -1,140 +1,423 @@
+use anyhow::{anyhow, Context, Result};
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::path::Path;
+use std::sync::Arc;
 use regex::Regex;
 
+use crate::ansi;
+use crate::config::SchemaConfig;
+use crate::envelope::Position;
+use crate::filter::json_path;
+use crate::html;
+use crate::parser::{compile_pattern, GenericParser, Parser, ParserRegistry};
+use crate::siem;
+use crate::table::TableLayout;
+use crate::timezone::DisplayZone;
+
 pub enum OutputFormat {
     Text,
     Json,
+    /// JSON indented over several lines, colored like `--json-view`
+    JsonPretty,
+    /// One JSON object per line, wrapped with where it came from
+    Ndjson,
     Csv,
+    /// Colorized lines as HTML with inline styles
+    Html,
+    /// ArcSight Common Event Format, for SIEMs
+    Cef,
+    /// IBM QRadar Log Event Extended Format
+    Leef,
+    /// Aligned timestamp, level, service and message columns
+    Table,
 }
 
 impl OutputFormat {
     pub fn from_string(format: &str) -> OutputFormat {
         match format.to_lowercase().as_str() {
             "json" => OutputFormat::Json,
+            "json-pretty" => OutputFormat::JsonPretty,
+            "ndjson" => OutputFormat::Ndjson,
             "csv" => OutputFormat::Csv,
+            "html" => OutputFormat::Html,
+            "cef" => OutputFormat::Cef,
+            "leef" => OutputFormat::Leef,
+            "table" => OutputFormat::Table,
             _ => OutputFormat::Text,
         }
     }
 }
 
+/// CSV columns without `--csv-columns` or a schema.
+const DEFAULT_CSV_COLUMNS: &[&str] = &["timestamp", "level", "service", "message", "ip", "status_code"];
+/// CSV columns without `--csv-columns` or a schema under `--input-format accesslog`.
+const ACCESS_LOG_CSV_COLUMNS: &[&str] = &[
+    "timestamp", "ip", "user", "method", "path", "protocol", "status_code", "bytes", "referer", "user_agent", "latency_ms",
+];
+
 pub struct OutputFormatter {
     format: OutputFormat,
-    csv_headers_printed: bool,
-    log_parser: LogParser,
+    header_printed: bool,
+    csv_columns: Option<Vec<String>>,
+    /// `--json-colored`: JSON output carries `raw` and the line as ft colors it
+    json_colored: bool,
+    parsers: ParserRegistry,
+    /// Reads `timestamp` columns
+    timestamps: GenericParser,
+    schema: Option<OutputSchema>,
+    table: TableLayout,
+    /// JSON gets a `timestamp_iso` and tables show times in this zone
+    zone: Option<DisplayZone>,
+    /// `line_number` and `byte_offset`, for `--line-numbers` and `--byte-offsets`
+    positions: Vec<&'static str>,
+}
+
+/// A `[schema.NAME]` ready for use: columns in a fixed order, each converted to its
+/// type, so exports from different runs line up.
+pub struct OutputSchema {
+    pattern: Option<Regex>,
+    columns: Vec<Column>,
+}
+
+struct Column {
+    name: String,
+    kind: ColumnType,
+    default: Option<String>,
+}
+
+#[derive(Clone, Copy)]
+enum ColumnType {
+    String,
+    Int,
+    Float,
+    Bool,
+    Timestamp,
 }
 
 impl OutputFormatter {
-    pub fn new(format: OutputFormat) -> Self {
+    pub fn new(format: OutputFormat, parsers: ParserRegistry) -> Self {
         Self {
             format,
-            csv_headers_printed: false,
-            log_parser: LogParser::new(),
+            header_printed: false,
+            csv_columns: None,
+            json_colored: false,
+            parsers,
+            timestamps: GenericParser::new(),
+            schema: None,
+            table: TableLayout::new(&[]),
+            zone: None,
+            positions: Vec::new(),
+        }
+    }
+
+    /// Lay out CSV and JSON output by `schema` instead of the built-in columns.
+    pub fn with_schema(mut self, schema: Option<OutputSchema>) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    /// `--csv-columns`: the fields CSV output has, in order, instead of the built-in
+    /// columns or a schema's.
+    pub fn with_csv_columns(mut self, columns: Vec<String>) -> Self {
+        if !columns.is_empty() {
+            self.csv_columns = Some(columns);
         }
+        self
+    }
+
+    /// `--utc`, `--local` or `--tz`: the zone structured output normalizes timestamps to.
+    pub fn with_zone(mut self, zone: Option<DisplayZone>) -> Self {
+        self.zone = zone;
+        self
+    }
+
+    /// `--line-numbers` and `--byte-offsets`: give where each line starts in its source as
+    /// fields (JSON), columns (CSV, tables) or, in text, a `line:offset:` prefix.
+    pub fn with_positions(mut self, line_numbers: bool, byte_offsets: bool) -> Self {
+        self.positions = [("line_number", line_numbers), ("byte_offset", byte_offsets)].into_iter()
+            .filter_map(|(name, on)| on.then_some(name))
+            .collect();
+        self.table = TableLayout::new(&self.positions);
+        self
+    }
+
+    /// `--json-colored`: add `raw` and `colored` (the line with its ANSI colors) to JSON output.
+    pub fn with_json_colored(mut self, on: bool) -> Self {
+        self.json_colored = on;
+        self
+    }
+
+    /// What closes output once it's done: the closing HTML tag, if the opening one was printed.
+    pub fn footer(&self) -> Option<&'static str> {
+        (matches!(self.format, OutputFormat::Html) && self.header_printed).then(html::footer)
+    }
+
+    /// Whether output is `--format ndjson`, which wraps each record with its position.
+    pub fn is_ndjson(&self) -> bool {
+        matches!(self.format, OutputFormat::Ndjson)
+    }
+
+    /// Whether output is `--format json-pretty`, which is laid out and colored for reading.
+    pub fn is_pretty(&self) -> bool {
+        matches!(self.format, OutputFormat::JsonPretty)
+    }
+
+    /// Whether output is the lines as shown (text, HTML) rather than records made from them.
+    pub fn is_text(&self) -> bool {
+        matches!(self.format, OutputFormat::Text | OutputFormat::Html)
     }
 
-    pub fn format_line(&mut self, line: &str, colored_line: &str) -> String {
+    /// Whether output is `--format table`, whose rows are colored like text lines.
+    pub fn is_table(&self) -> bool {
+        matches!(self.format, OutputFormat::Table)
+    }
+
+    /// The parser lines from `path` are read with.
+    pub fn parser_for(&self, path: &Path) -> Arc<dyn Parser> {
+        self.parsers.for_source(path)
+    }
+
+    /// The CSV or table header row or opening HTML tag, the first time it's asked for, to
+    /// print before the first line.
+    pub fn take_header(&mut self) -> Option<String> {
+        if !matches!(self.format, OutputFormat::Csv | OutputFormat::Html | OutputFormat::Table) || self.header_printed {
+            return None;
+        }
+        self.header_printed = true;
         match self.format {
-            OutputFormat::Text => colored_line.to_string(),
-            OutputFormat::Json => {
-                let parsed = self.log_parser.parse_line(line);
+            OutputFormat::Html => return Some(html::header()),
+            OutputFormat::Table => return Some(self.table.header()),
+            _ => {}
+        }
+        let header = match (&self.csv_columns, &self.schema) {
+            (Some(columns), _) => csv_record(columns),
+            (None, Some(schema)) => schema.csv_header(),
+            (None, None) => csv_record(self.default_csv_columns()),
+        };
+        Some(self.positions.iter().fold(header, |header, name| format!("{},{}", header, name)))
+    }
+
+    /// A line's `--line-numbers` and `--byte-offsets` values, null where it has no position.
+    fn position_values(&self, position: Option<Position>) -> Vec<(&'static str, Value)> {
+        self.positions.iter()
+            .map(|&name| {
+                let value = position.map(|p| if name == "line_number" { p.line } else { p.offset });
+                (name, json!(value))
+            })
+            .collect()
+    }
+
+    fn default_csv_columns(&self) -> &'static [&'static str] {
+        match self.parsers.default_parser().name() {
+            "accesslog" => ACCESS_LOG_CSV_COLUMNS,
+            _ => DEFAULT_CSV_COLUMNS,
+        }
+    }
+
+    /// Format a line read from `path` for output, parsed with that source's format.
+    /// `source` names where the line came from and is included in structured output when given,
+    /// as is `position` under `--line-numbers` or `--byte-offsets`.
+    pub fn format_line(&mut self, path: &Path, source: Option<&str>, line: &str, colored_line: &str, position: Option<Position>) -> String {
+        let parser = self.parsers.for_source(path);
+        // Fields are parsed from the text the line shows, without the colors it came with
+        let stripped = ansi::strip(line);
+        let line: &str = &stripped;
+        let positions = self.position_values(position);
+        let mut extra = positions.clone();
+        if self.json_colored {
+            extra.extend([("raw", json!(line)), ("colored", json!(colored_line))]);
+        }
+        let csv_row = |row: String| positions.iter().fold(row, |row, (_, value)| match value {
+            Value::Null => format!("{},", row),
+            value => format!("{},{}", row, value),
+        });
+        if let (OutputFormat::Csv, Some(columns)) = (&self.format, &self.csv_columns) {
+            return csv_row(Self::format_csv_line(parser.as_ref(), columns, source, line));
+        }
+        // CEF, LEEF and tables have their own fields, so a schema doesn't apply
+        match self.format {
+            OutputFormat::Cef => return siem::cef(&parse_fields(parser.as_ref(), line), &self.timestamps, path, line),
+            OutputFormat::Leef => return siem::leef(&parse_fields(parser.as_ref(), line), &self.timestamps, path, line),
+            OutputFormat::Table => {
+                let line = match self.zone {
+                    Some(zone) => self.timestamps.rewrite_timestamps(line, zone),
+                    None => Cow::Borrowed(line),
+                };
+                return self.table.row(&parse_fields(parser.as_ref(), &line), &line, position);
+            }
+            _ => {}
+        }
+        if let Some(schema) = &self.schema {
+            let values = schema.values(parser.as_ref(), &self.timestamps, source, line);
+            return match self.format {
+                OutputFormat::Text | OutputFormat::Cef | OutputFormat::Leef | OutputFormat::Table => colored_line.to_string(),
+                OutputFormat::Html => html::line(colored_line),
+                OutputFormat::Json | OutputFormat::JsonPretty | OutputFormat::Ndjson => schema.to_json(&values, &extra),
+                OutputFormat::Csv => csv_row(schema.to_csv(&values)),
+            };
+        }
+        match self.format {
+            OutputFormat::Text | OutputFormat::Cef | OutputFormat::Leef | OutputFormat::Table => colored_line.to_string(),
+            OutputFormat::Html => html::line(colored_line),
+            OutputFormat::Json | OutputFormat::JsonPretty | OutputFormat::Ndjson => {
+                let mut parsed = parse_fields(parser.as_ref(), line);
+                if let Some(source) = source {
+                    parsed["source"] = json!(source);
+                }
+                if let Some(zone) = self.zone {
+                    let time = parsed["timestamp"].as_str()
+                        .and_then(|text| self.timestamps.parse_timestamp(text))
+                        .or_else(|| self.timestamps.parse_timestamp(line));
+                    if let Some(time) = time {
+                        parsed["timestamp_iso"] = json!(zone.iso8601(time));
+                    }
+                }
+                if let Some(fields) = parsed.as_object_mut() {
+                    for (name, value) in extra {
+                        fields.entry(name).or_insert(value);
+                    }
+                }
                 serde_json::to_string(&parsed).unwrap_or_else(|_| {
                     json!({"raw": line, "error": "failed to parse"}).to_string()
                 })
             }
-            OutputFormat::Csv => {
-                if !self.csv_headers_printed {
-                    self.csv_headers_printed = true;
-                    let headers = "timestamp,level,service,message,ip,status_code";
-                    format!("{}\n{}", headers, self.format_csv_line(line))
-                } else {
-                    self.format_csv_line(line)
-                }
-            }
+            OutputFormat::Csv => csv_row(Self::format_csv_line(parser.as_ref(), self.default_csv_columns(), source, line)),
         }
     }
 
-    fn format_csv_line(&self, line: &str) -> String {
-        let parsed = self.log_parser.parse_line(line);
-        
-        let timestamp = parsed["timestamp"].as_str().unwrap_or("");
-        let level = parsed["level"].as_str().unwrap_or("");
-        let service = parsed["service"].as_str().unwrap_or("");
-        let message = parsed["message"].as_str().unwrap_or(line);
-        let ip = parsed["ip"].as_str().unwrap_or("");
-        let status_code = parsed["status_code"].as_str().unwrap_or("");
-
-        format!(
-            "{},{},{},{},{},{}",
-            Self::csv_escape(timestamp),
-            Self::csv_escape(level),
-            Self::csv_escape(service),
-            Self::csv_escape(message),
-            Self::csv_escape(ip),
-            Self::csv_escape(status_code)
-        )
-    }
-
-    fn csv_escape(field: &str) -> String {
-        if field.contains(',') || field.contains('"') || field.contains('\n') {
-            format!("\"{}\"", field.replace("\"", "\"\""))
-        } else {
-            field.to_string()
-        }
+    /// One CSV row of `columns`: parsed fields (dotted paths reach into nested JSON),
+    /// `source`, or empty. `message` falls back to the whole line.
+    fn format_csv_line<S: AsRef<str>>(parser: &dyn Parser, columns: &[S], source: Option<&str>, line: &str) -> String {
+        let parsed = parse_fields(parser, line);
+        let values = columns.iter().map(|column| {
+            let name = column.as_ref();
+            match parsed.get(name).or_else(|| json_path(&parsed, name)) {
+                Some(Value::String(text)) => text.clone(),
+                Some(Value::Null) => String::new(),
+                Some(other) => other.to_string(),
+                None if name == "source" => source.unwrap_or_default().to_string(),
+                None if name == "message" => line.to_string(),
+                None => String::new(),
+            }
+        });
+        csv_record(values)
     }
 }
 
-pub struct LogParser {
-    timestamp_regex: Regex,
-    ip_regex: Regex,
-    status_code_regex: Regex,
-    level_regex: Regex,
-    service_regex: Regex,
-}
-
-impl LogParser {
-    pub fn new() -> Self {
-        Self {
-            timestamp_regex: Regex::new(r"\d{4}-\d{2}-\d{2}[T\s]\d{2}:\d{2}:\d{2}").unwrap(),
-            ip_regex: Regex::new(r"\b(?:[0-9]{1,3}\.){3}[0-9]{1,3}\b").unwrap(),
-            status_code_regex: Regex::new(r"\b[2-5][0-9]{2}\b").unwrap(),
-            level_regex: Regex::new(r"\b(EMERG|ALERT|CRIT|ERROR|WARN|NOTICE|INFO|DEBUG|TRACE)\b").unwrap(),
-            service_regex: Regex::new(r"\b(nginx|apache|mysql|postgres|sshd|systemd|docker|php-fpm)\b").unwrap(),
+impl OutputSchema {
+    pub fn from_config(config: &SchemaConfig) -> Result<Self> {
+        let pattern = config.pattern.as_deref()
+            .map(|p| compile_pattern(p).with_context(|| format!("Invalid schema pattern: {}", p)))
+            .transpose()?;
+        if config.columns.is_empty() {
+            return Err(anyhow!("Schema has no columns"));
         }
+        let columns = config.columns.iter()
+            .map(|column| {
+                let kind = match column.kind.as_deref().unwrap_or("string").to_lowercase().as_str() {
+                    "string" | "str" | "text" => ColumnType::String,
+                    "int" | "integer" => ColumnType::Int,
+                    "float" | "number" => ColumnType::Float,
+                    "bool" | "boolean" => ColumnType::Bool,
+                    "timestamp" | "time" => ColumnType::Timestamp,
+                    other => return Err(anyhow!("Unknown type '{}' for column '{}' (expected string, int, float, bool or timestamp)", other, column.name)),
+                };
+                let default = column.default.as_ref().map(|value| match value {
+                    toml::Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                });
+                Ok(Column { name: column.name.clone(), kind, default })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { pattern, columns })
     }
 
-    pub fn parse_line(&self, line: &str) -> Value {
-        let mut parsed = HashMap::new();
+    /// Each column's typed value for a line: from the schema pattern's named groups,
+    /// the line's own JSON fields, or the built-in parser, in that order.
+    fn values(&self, parser: &dyn Parser, timestamps: &GenericParser, source: Option<&str>, line: &str) -> Vec<Value> {
+        let captures = self.pattern.as_ref().and_then(|re| re.captures(line));
+        let json = serde_json::from_str::<Value>(line).ok().filter(Value::is_object);
+        let parsed = parse_fields(parser, line);
 
-        // Extract timestamp
-        if let Some(ts_match) = self.timestamp_regex.find(line) {
-            parsed.insert("timestamp".to_string(), json!(ts_match.as_str()));
-        }
+        self.columns.iter()
+            .map(|column| {
+                let name = column.name.as_str();
+                let found = captures.as_ref().and_then(|caps| caps.name(name)).map(|m| m.as_str().to_string())
+                    .or_else(|| (name == "source").then(|| source.map(str::to_string)).flatten())
+                    .or_else(|| json.as_ref().and_then(|j| j.get(name)).map(|v| match v {
+                        Value::String(text) => text.clone(),
+                        other => other.to_string(),
+                    }))
+                    .or_else(|| parsed[name].as_str().map(str::to_string));
+                found.and_then(|text| column.kind.convert(&text, timestamps))
+                    .or_else(|| column.default.as_deref().and_then(|text| column.kind.convert(text, timestamps)))
+                    .unwrap_or(Value::Null)
+            })
+            .collect()
+    }
 
-        // Extract IP address
-        if let Some(ip_match) = self.ip_regex.find(line) {
-            parsed.insert("ip".to_string(), json!(ip_match.as_str()));
-        }
+    fn csv_header(&self) -> String {
+        csv_record(self.columns.iter().map(|column| &column.name))
+    }
 
-        // Extract status code
-        if let Some(status_match) = self.status_code_regex.find(line) {
-            parsed.insert("status_code".to_string(), json!(status_match.as_str()));
-        }
+    fn to_csv(&self, values: &[Value]) -> String {
+        csv_record(values.iter().map(|value| match value {
+            Value::Null => String::new(),
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        }))
+    }
 
-        // Extract log level
-        if let Some(level_match) = self.level_regex.find(line) {
-            parsed.insert("level".to_string(), json!(level_match.as_str()));
-        }
+    /// A JSON object with keys in column order.
+    /// The columns as a JSON object, in order, followed by `extra` fields.
+    fn to_json(&self, values: &[Value], extra: &[(&str, Value)]) -> String {
+        let fields: Vec<String> = self.columns.iter().zip(values)
+            .map(|(column, value)| format!("{}:{}", json!(column.name), value))
+            .chain(extra.iter()
+                .filter(|(name, _)| !self.columns.iter().any(|column| column.name == *name))
+                .map(|(name, value)| format!("{}:{}", json!(name), value)))
+            .collect();
+        format!("{{{}}}", fields.join(","))
+    }
+}
 
-        // Extract service
-        if let Some(service_match) = self.service_regex.find(line) {
-            parsed.insert("service".to_string(), json!(service_match.as_str()));
+impl ColumnType {
+    fn convert(self, text: &str, timestamps: &GenericParser) -> Option<Value> {
+        let text = text.trim();
+        match self {
+            ColumnType::String => Some(json!(text)),
+            ColumnType::Int => text.parse::<i64>().ok()
+                .or_else(|| text.parse::<f64>().ok().filter(|f| f.fract() == 0.0).map(|f| f as i64))
+                .map(|n| json!(n)),
+            ColumnType::Float => text.parse::<f64>().ok().filter(|f| f.is_finite()).map(|f| json!(f)),
+            ColumnType::Bool => match text.to_lowercase().as_str() {
+                "true" | "yes" | "1" | "on" => Some(json!(true)),
+                "false" | "no" | "0" | "off" => Some(json!(false)),
+                _ => None,
+            },
+            ColumnType::Timestamp => timestamps.parse_timestamp(text).map(|dt| json!(dt.to_rfc3339())),
         }
+    }
+}
 
-        // Always include the raw message
-        parsed.insert("message".to_string(), json!(line));
-        parsed.insert("raw".to_string(), json!(line));
+/// One CSV row, quoted where needed, without the line ending.
+fn csv_record<I>(fields: I) -> String
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    let mut writer = csv::WriterBuilder::new()
+        .terminator(csv::Terminator::Any(b'\n'))
+        .from_writer(Vec::new());
+    // Writing to memory can't fail
+    let _ = writer.write_record(fields);
+    let bytes = writer.into_inner().unwrap_or_default();
+    String::from_utf8_lossy(&bytes).trim_end_matches('\n').to_string()
+}
 
-        json!(parsed)
-    }
-}
\ No newline at end of file
+/// A line's fields from `parser`; a line the format doesn't recognize keeps just
+/// its text, as `message` and `raw`.
+pub fn parse_fields(parser: &dyn Parser, line: &str) -> Value {
+    parser.parse(line).unwrap_or_else(|| json!({"message": line, "raw": line}))
+}
@@ -0,0 +1,29 @@
+use anyhow::{anyhow, Context, Result};
+use std::process::Command;
+
+/// Hand `lines` (ANSI colors kept) to `less -R +G`, starting at the end. With
+/// `suspend` the full-screen UI is put aside while less runs and restored after.
+pub fn page<'a>(lines: impl IntoIterator<Item = &'a String>, suspend: bool) -> Result<()> {
+    let path = std::env::temp_dir().join(format!("ft-less-{}.log", std::process::id()));
+    let mut text = String::new();
+    for line in lines {
+        text.push_str(line);
+        text.push('\n');
+    }
+    std::fs::write(&path, text).with_context(|| format!("Failed to write {:?}", path))?;
+
+    let mut command = Command::new("less");
+    command.args(["-R", "+G"]).arg(&path);
+    let status = if suspend {
+        crate::editor::run_suspended(&mut command)
+    } else {
+        command.status()
+    };
+    let _ = std::fs::remove_file(&path);
+
+    let status = status.context("Failed to start less")?;
+    if !status.success() {
+        return Err(anyhow!("less exited with {}", status));
+    }
+    Ok(())
+}
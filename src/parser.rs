@@ -0,0 +1,675 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDateTime, TimeZone};
+use regex::{Captures, Regex};
+use serde_json::{json, Map, Value};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use crate::config::Config;
+use crate::duration::format_duration;
+use crate::timezone::DisplayZone;
+
+/// A log format: turns a line into named fields.
+///
+/// Recognized lines give a JSON object carrying whichever of the common fields the
+/// format knows (`timestamp`, `level`, `service`, `ip`, `status_code`, `message`) as
+/// strings, always `raw`, and any format-specific fields alongside.
+pub trait Parser: Send + Sync {
+    /// Name used by `--input-format` and `[parser.NAME]`.
+    fn name(&self) -> &str;
+
+    /// The line's fields, or `None` when the line isn't in this format.
+    fn parse(&self, line: &str) -> Option<Value>;
+}
+
+/// The built-in formats, in the order `auto` tries them.
+const BUILTIN: &[&str] = &["cri", "json", "accesslog", "syslog", "logfmt", "generic"];
+
+/// Patterns `%{NAME}` and `%{NAME:field}` stand for in `[parser.NAME]` and
+/// `[schema.NAME]` patterns, named as in Logstash's grok.
+const GROK_PATTERNS: &[(&str, &str)] = &[
+    ("WORD", r"\b\w+\b"),
+    ("NOTSPACE", r"\S+"),
+    ("SPACE", r"\s*"),
+    ("DATA", r".*?"),
+    ("GREEDYDATA", r".*"),
+    ("INT", r"[+-]?\d+"),
+    ("POSINT", r"\b[1-9]\d*\b"),
+    ("NUMBER", r"[+-]?(?:\d+(?:\.\d+)?|\.\d+)"),
+    ("BASE16NUM", r"(?:0[xX])?[0-9A-Fa-f]+"),
+    ("UUID", r"[0-9A-Fa-f]{8}-(?:[0-9A-Fa-f]{4}-){3}[0-9A-Fa-f]{12}"),
+    ("IPV4", r"(?:\d{1,3}\.){3}\d{1,3}"),
+    ("IP", r"(?:\d{1,3}\.){3}\d{1,3}|[0-9A-Fa-f]*:[0-9A-Fa-f:]+"),
+    ("HOSTNAME", r"\b[0-9A-Za-z][0-9A-Za-z-]{0,62}(?:\.[0-9A-Za-z][0-9A-Za-z-]{0,62})*\.?\b"),
+    ("USER", r"[A-Za-z0-9._-]+"),
+    ("PATH", r"(?:/[^\s/]*)+"),
+    ("URI", r"[A-Za-z][A-Za-z0-9+.-]*://\S+"),
+    ("QUOTEDSTRING", r#""(?:[^"\\]|\\.)*""#),
+    ("LOGLEVEL", r"(?i:trace|debug|info|notice|warn(?:ing)?|err(?:or)?|crit(?:ical)?|fatal|severe|emerg(?:ency)?|alert)"),
+    ("TIMESTAMP_ISO8601", r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?"),
+    ("HTTPDATE", r"\d{2}/[A-Za-z]{3}/\d{4}:\d{2}:\d{2}:\d{2} [+-]\d{4}"),
+    ("SYSLOGTIMESTAMP", r"[A-Z][a-z]{2} +\d{1,2} \d{2}:\d{2}:\d{2}"),
+];
+
+/// Compile a user pattern, first expanding grok-style `%{NAME:field}` into a group
+/// named `field` matching pattern NAME (`%{NAME}` alone captures nothing).
+pub fn compile_pattern(pattern: &str) -> Result<Regex> {
+    let grok = Regex::new(r"%\{(\w+)(?::(\w+))?\}").unwrap();
+    let mut unknown = None;
+    let expanded = grok.replace_all(pattern, |caps: &regex::Captures| {
+        let name = &caps[1];
+        let Some((_, regex)) = GROK_PATTERNS.iter().find(|(known, _)| *known == name) else {
+            unknown.get_or_insert_with(|| name.to_string());
+            return String::new();
+        };
+        match caps.get(2) {
+            Some(field) => format!("(?P<{}>{})", field.as_str(), regex),
+            None => format!("(?:{})", regex),
+        }
+    });
+    if let Some(name) = unknown {
+        let known: Vec<&str> = GROK_PATTERNS.iter().map(|(known, _)| *known).collect();
+        return Err(anyhow!("Unknown pattern %{{{}}} (known: {})", name, known.join(", ")));
+    }
+    Ok(Regex::new(&expanded)?)
+}
+
+/// Every parser ft knows (built-in and `[parser.NAME]` from the config), and which
+/// one each source uses.
+pub struct ParserRegistry {
+    parsers: Vec<Arc<dyn Parser>>,
+    /// `[parser.NAME] files` globs, checked in config order
+    bindings: Vec<(String, Arc<dyn Parser>)>,
+    /// `--input-format`, or `auto`
+    default: Arc<dyn Parser>,
+    forced: bool,
+    /// Which parser each source resolved to, so globs are matched once per source
+    resolved: RefCell<HashMap<PathBuf, Arc<dyn Parser>>>,
+}
+
+impl ParserRegistry {
+    pub fn new(config: &Config, input_format: Option<&str>) -> Result<Self> {
+        let generic = Arc::new(GenericParser::new());
+        let mut parsers: Vec<Arc<dyn Parser>> = vec![
+            Arc::new(JsonParser),
+            Arc::new(AccessLogParser::new()),
+            Arc::new(SyslogParser::new(generic.clone())),
+            Arc::new(LogfmtParser),
+            generic,
+        ];
+        // Container lines wrap any of the others, so it goes first
+        parsers.insert(0, Arc::new(CriParser { payload: parsers.clone() }));
+        let auto: Arc<dyn Parser> = Arc::new(AutoParser { candidates: parsers.clone() });
+        parsers.push(auto.clone());
+
+        for (name, parser) in &config.parsers {
+            if BUILTIN.contains(&name.as_str()) || name == "auto" {
+                if parser.pattern.is_some() {
+                    return Err(anyhow!("[parser.{}] is built in and can't take a pattern", name));
+                }
+                continue;
+            }
+            let pattern = parser.pattern.as_deref()
+                .ok_or_else(|| anyhow!("[parser.{}] needs a pattern", name))?;
+            let pattern = compile_pattern(pattern)
+                .with_context(|| format!("Invalid pattern in [parser.{}]", name))?;
+            parsers.push(Arc::new(PatternParser { name: name.clone(), pattern }));
+        }
+
+        let mut registry = Self {
+            parsers,
+            bindings: Vec::new(),
+            default: auto,
+            forced: false,
+            resolved: RefCell::new(HashMap::new()),
+        };
+        for (name, parser) in &config.parsers {
+            let target = registry.get(name)?;
+            for glob in &parser.files {
+                registry.bindings.push((glob.clone(), target.clone()));
+            }
+        }
+        if let Some(name) = input_format {
+            registry.default = registry.get(name)?;
+            registry.forced = true;
+        }
+        Ok(registry)
+    }
+
+    fn get(&self, name: &str) -> Result<Arc<dyn Parser>> {
+        let name = if name == "access-log" { "accesslog" } else { name };
+        self.parsers.iter()
+            .find(|parser| parser.name() == name)
+            .cloned()
+            .ok_or_else(|| {
+                let names: Vec<&str> = self.parsers.iter().map(|p| p.name()).collect();
+                anyhow!("Unknown input format '{}' (expected {})", name, names.join(", "))
+            })
+    }
+
+    /// The parser for lines from `path`: `--input-format` if given, else the first
+    /// `[parser.NAME]` whose `files` match (the path as given or made absolute), else `auto`.
+    pub fn for_source(&self, path: &Path) -> Arc<dyn Parser> {
+        if self.forced || self.bindings.is_empty() {
+            return self.default.clone();
+        }
+        self.resolved.borrow_mut()
+            .entry(path.to_path_buf())
+            .or_insert_with(|| {
+                let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+                let names = [path.to_string_lossy(), absolute.to_string_lossy()];
+                self.bindings.iter()
+                    .find(|(glob, _)| names.iter().any(|name| glob_match(glob, name)))
+                    .map_or_else(|| self.default.clone(), |(_, parser)| parser.clone())
+            })
+            .clone()
+    }
+
+    /// The parser used when the source isn't known, as for filters.
+    pub fn default_parser(&self) -> Arc<dyn Parser> {
+        self.default.clone()
+    }
+}
+
+/// Tries each built-in format in turn; `generic` accepts any line.
+struct AutoParser {
+    candidates: Vec<Arc<dyn Parser>>,
+}
+
+impl Parser for AutoParser {
+    fn name(&self) -> &str {
+        "auto"
+    }
+
+    fn parse(&self, line: &str) -> Option<Value> {
+        self.candidates.iter().find_map(|parser| parser.parse(line))
+    }
+}
+
+/// Scrapes the common fields out of free text with a few regexes.
+#[derive(Clone)]
+pub struct GenericParser {
+    timestamp_regex: Regex,
+    ip_regex: Regex,
+    status_code_regex: Regex,
+    level_regex: Regex,
+    service_regex: Regex,
+}
+
+impl GenericParser {
+    pub fn new() -> Self {
+        Self {
+            timestamp_regex: Regex::new(concat!(
+                // ISO 8601: 2024-05-01T12:00:00(.123)?(Z|+02:00)?
+                r"(?P<iso>\d{4}-\d{2}-\d{2}[T\s]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?)",
+                // Common/combined log format: 01/May/2024:12:00:00 +0000
+                r"|(?P<clf>\d{2}/[A-Z][a-z]{2}/\d{4}:\d{2}:\d{2}:\d{2}(?: [+-]\d{4})?)",
+                // Syslog: May  1 12:00:00
+                r"|(?P<syslog>\b(?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec) +\d{1,2} \d{2}:\d{2}:\d{2})",
+            )).unwrap(),
+            ip_regex: Regex::new(r"\b(?:[0-9]{1,3}\.){3}[0-9]{1,3}\b").unwrap(),
+            status_code_regex: Regex::new(r"\b[2-5][0-9]{2}\b").unwrap(),
+            level_regex: Regex::new(r"\b(EMERG|ALERT|CRIT|ERROR|WARN|NOTICE|INFO|DEBUG|TRACE)\b").unwrap(),
+            service_regex: Regex::new(r"\b(nginx|apache|mysql|postgres|sshd|systemd|docker|php-fpm)\b").unwrap(),
+        }
+    }
+
+    /// Parse the first recognizable timestamp in a line (ISO 8601, common log format
+    /// or syslog). Timestamps without a zone are interpreted as local time.
+    pub fn parse_timestamp(&self, line: &str) -> Option<DateTime<Local>> {
+        let caps = self.timestamp_regex.captures(line)?;
+
+        if let Some(iso) = caps.name("iso") {
+            let ts = iso.as_str().replacen(' ', "T", 1).replace(',', ".");
+            let ts = match ts.strip_suffix('Z') {
+                Some(rest) => format!("{}+00:00", rest),
+                None => ts,
+            };
+            if let Ok(dt) = DateTime::parse_from_str(&ts, "%Y-%m-%dT%H:%M:%S%.f%:z")
+                .or_else(|_| DateTime::parse_from_str(&ts, "%Y-%m-%dT%H:%M:%S%.f%z"))
+            {
+                return Some(dt.with_timezone(&Local));
+            }
+            let naive = NaiveDateTime::parse_from_str(&ts, "%Y-%m-%dT%H:%M:%S%.f").ok()?;
+            return Local.from_local_datetime(&naive).earliest();
+        }
+
+        if let Some(clf) = caps.name("clf") {
+            let ts = clf.as_str();
+            if let Ok(dt) = DateTime::<FixedOffset>::parse_from_str(ts, "%d/%b/%Y:%H:%M:%S %z") {
+                return Some(dt.with_timezone(&Local));
+            }
+            let naive = NaiveDateTime::parse_from_str(ts, "%d/%b/%Y:%H:%M:%S").ok()?;
+            return Local.from_local_datetime(&naive).earliest();
+        }
+
+        if let Some(syslog) = caps.name("syslog") {
+            // Syslog omits the year: assume the current one unless that lands in the future
+            let now = Local::now();
+            let parse_with_year = |year: i32| {
+                NaiveDateTime::parse_from_str(&format!("{} {}", year, syslog.as_str()), "%Y %b %e %H:%M:%S")
+                    .ok()
+                    .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+            };
+            let dt = parse_with_year(now.year())?;
+            if dt > now + chrono::Duration::days(1) {
+                return parse_with_year(now.year() - 1);
+            }
+            return Some(dt);
+        }
+
+        None
+    }
+
+    /// Where the first timestamp in a line is (the one `parse_timestamp` reads).
+    pub fn timestamp_span(&self, line: &str) -> Option<Range<usize>> {
+        self.timestamp_regex.find(line).map(|m| m.range())
+    }
+
+    /// The line with each timestamp it has shown in `zone`, in the style it was written in:
+    /// ISO 8601 keeps its separator and fraction digits and gains an offset, the common log
+    /// format gets the zone's offset, and syslog times (which have no zone) just move.
+    pub fn rewrite_timestamps<'a>(&self, line: &'a str, zone: DisplayZone) -> Cow<'a, str> {
+        self.replace_timestamps(line, |caps, time| {
+            let time = zone.convert(time);
+            if let Some(iso) = caps.name("iso") {
+                let iso = iso.as_str();
+                let separator = &iso[10..11];
+                let digits = iso[19..].strip_prefix(['.', ',']).map_or(0, |rest| {
+                    rest.bytes().take_while(u8::is_ascii_digit).count().min(9)
+                });
+                let fraction = match digits {
+                    0 => String::new(),
+                    n => format!("{}{:0n$}", &iso[19..20], time.timestamp_subsec_nanos() / 10u32.pow(9 - n as u32), n = n),
+                };
+                return format!("{}{}{}{}{}", time.format("%Y-%m-%d"), separator, time.format("%H:%M:%S"), fraction, zone.offset_suffix(&time));
+            }
+            if caps.name("clf").is_some() {
+                return time.format("%d/%b/%Y:%H:%M:%S %z").to_string();
+            }
+            time.format("%b %e %H:%M:%S").to_string()
+        })
+    }
+
+    /// `--relative`: the line with each timestamp it has replaced by how long before `now`
+    /// it was, as in `3m12s ago`.
+    pub fn relative_timestamps<'a>(&self, line: &'a str, now: DateTime<Local>) -> Cow<'a, str> {
+        self.replace_timestamps(line, |_, time| match (now - time).to_std() {
+            Ok(age) => format!("{} ago", format_duration(age)),
+            // Clock skew between the writer and this machine
+            Err(_) => format!("in {}", format_duration((time - now).to_std().unwrap_or_default())),
+        })
+    }
+
+    /// Replace each timestamp ft can read in `line` with what `replace` makes of it.
+    fn replace_timestamps<'a>(&self, line: &'a str, replace: impl Fn(&Captures, DateTime<Local>) -> String) -> Cow<'a, str> {
+        self.timestamp_regex.replace_all(line, |caps: &Captures| match self.parse_timestamp(&caps[0]) {
+            Some(time) => replace(caps, time),
+            None => caps[0].to_string(),
+        })
+    }
+
+    /// The line with its first timestamp removed, for comparing lines that differ only in time.
+    pub fn strip_timestamp(&self, line: &str) -> String {
+        self.timestamp_regex.replace(line, "").into_owned()
+    }
+
+    /// The first match of each common-field regex in `text`.
+    fn scrape(&self, text: &str) -> Map<String, Value> {
+        let mut fields = Map::new();
+        let found = [
+            ("timestamp", &self.timestamp_regex),
+            ("ip", &self.ip_regex),
+            ("status_code", &self.status_code_regex),
+            ("level", &self.level_regex),
+            ("service", &self.service_regex),
+        ];
+        for (name, regex) in found {
+            if let Some(m) = regex.find(text) {
+                fields.insert(name.to_string(), json!(m.as_str()));
+            }
+        }
+        fields
+    }
+}
+
+impl Parser for GenericParser {
+    fn name(&self) -> &str {
+        "generic"
+    }
+
+    fn parse(&self, line: &str) -> Option<Value> {
+        let mut fields = self.scrape(line);
+        fields.insert("message".to_string(), json!(line));
+        fields.insert("raw".to_string(), json!(line));
+        Some(Value::Object(fields))
+    }
+}
+
+/// One JSON object per line. Every top-level key is a field; common names such as
+/// `msg`, `time` or `severity` also fill the standard fields.
+struct JsonParser;
+
+impl Parser for JsonParser {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn parse(&self, line: &str) -> Option<Value> {
+        if !line.trim_start().starts_with('{') {
+            return None;
+        }
+        let Ok(Value::Object(mut fields)) = serde_json::from_str::<Value>(line) else {
+            return None;
+        };
+        add_standard_fields(&mut fields, line);
+        Some(Value::Object(fields))
+    }
+}
+
+/// `key=value key2="quoted value"` pairs, as written by Go, Heroku and many others.
+struct LogfmtParser;
+
+impl LogfmtParser {
+    /// The pairs of a logfmt line, or `None` if a token isn't `key=value` or a bare key.
+    fn pairs(line: &str) -> Option<Vec<(String, String)>> {
+        let mut pairs = Vec::new();
+        let mut chars = line.trim().chars().peekable();
+        while chars.peek().is_some() {
+            let mut key = String::new();
+            while let Some(&c) = chars.peek() {
+                if c == '=' || c.is_whitespace() {
+                    break;
+                }
+                if c == '"' {
+                    return None;
+                }
+                key.push(c);
+                chars.next();
+            }
+            let mut value = String::new();
+            if chars.peek() == Some(&'=') {
+                chars.next();
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    loop {
+                        match chars.next()? {
+                            '"' => break,
+                            '\\' => value.push(chars.next()?),
+                            c => value.push(c),
+                        }
+                    }
+                } else {
+                    while let Some(&c) = chars.peek() {
+                        if c.is_whitespace() {
+                            break;
+                        }
+                        value.push(c);
+                        chars.next();
+                    }
+                }
+            }
+            if key.is_empty() {
+                return None;
+            }
+            pairs.push((key, value));
+            while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                chars.next();
+            }
+        }
+        Some(pairs)
+    }
+}
+
+impl Parser for LogfmtParser {
+    fn name(&self) -> &str {
+        "logfmt"
+    }
+
+    fn parse(&self, line: &str) -> Option<Value> {
+        // Plain text with the odd key=value in it is not logfmt: the line must open with a pair
+        let first = line.split_whitespace().next()?;
+        if !first.contains('=') || first.starts_with('=') {
+            return None;
+        }
+        let pairs = Self::pairs(line)?;
+        if pairs.len() < 2 {
+            return None;
+        }
+        let mut fields: Map<String, Value> = pairs.into_iter()
+            .map(|(key, value)| (key, json!(value)))
+            .collect();
+        add_standard_fields(&mut fields, line);
+        Some(Value::Object(fields))
+    }
+}
+
+/// BSD syslog (`May  1 12:00:00 host prog[pid]: message`), optionally with a `<PRI>`
+/// prefix or an ISO timestamp as rsyslog writes by default.
+struct SyslogParser {
+    regex: Regex,
+    generic: Arc<GenericParser>,
+}
+
+impl SyslogParser {
+    fn new(generic: Arc<GenericParser>) -> Self {
+        Self {
+            regex: Regex::new(concat!(
+                r"^(?:<(?P<pri>\d{1,3})>)?",
+                r"(?P<timestamp>(?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec) +\d{1,2} \d{2}:\d{2}:\d{2}",
+                r"|\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:\d{2})?) ",
+                r"(?P<host>\S+) (?P<service>[^\s\[:]+)(?:\[(?P<pid>\d+)\])?: (?P<message>.*)$",
+            )).unwrap(),
+            generic,
+        }
+    }
+}
+
+impl Parser for SyslogParser {
+    fn name(&self) -> &str {
+        "syslog"
+    }
+
+    fn parse(&self, line: &str) -> Option<Value> {
+        let caps = self.regex.captures(line)?;
+        let message = caps.name("message").map_or("", |m| m.as_str());
+        // The message is free text: scrape it for what the header doesn't say
+        let mut fields = self.generic.scrape(message);
+        fields.remove("timestamp");
+        for name in ["timestamp", "host", "service", "pid", "message"] {
+            if let Some(m) = caps.name(name) {
+                fields.insert(name.to_string(), json!(m.as_str()));
+            }
+        }
+        if let Some(pri) = caps.name("pri").and_then(|m| m.as_str().parse::<u8>().ok()) {
+            const SEVERITIES: [&str; 8] = ["EMERG", "ALERT", "CRIT", "ERROR", "WARN", "NOTICE", "INFO", "DEBUG"];
+            fields.insert("level".to_string(), json!(SEVERITIES[(pri % 8) as usize]));
+            fields.insert("facility".to_string(), json!((pri / 8).to_string()));
+        }
+        fields.insert("raw".to_string(), json!(line));
+        Some(Value::Object(fields))
+    }
+}
+
+/// Kubernetes CRI container logs (containerd, CRI-O), as under /var/log/containers:
+/// `TIME STREAM P|F PAYLOAD`. The payload is parsed as whatever it is, plus `stream`.
+struct CriParser {
+    payload: Vec<Arc<dyn Parser>>,
+}
+
+impl Parser for CriParser {
+    fn name(&self) -> &str {
+        "cri"
+    }
+
+    fn parse(&self, line: &str) -> Option<Value> {
+        let caps = cri_regex().captures(line)?;
+        let payload = caps.name("payload").map_or("", |m| m.as_str());
+        let mut fields = match self.payload.iter().find_map(|parser| parser.parse(payload)) {
+            Some(Value::Object(fields)) => fields,
+            _ => Map::new(),
+        };
+        fields.entry("timestamp").or_insert_with(|| json!(&caps["time"]));
+        fields.insert("stream".to_string(), json!(&caps["stream"]));
+        if &caps["tag"] == "P" {
+            fields.insert("partial".to_string(), json!(true));
+        }
+        Some(Value::Object(fields))
+    }
+}
+
+fn cri_regex() -> &'static Regex {
+    static CRI: OnceLock<Regex> = OnceLock::new();
+    CRI.get_or_init(|| {
+        Regex::new(r"^(?P<time>\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:\d{2})) (?P<stream>stdout|stderr) (?P<tag>[PF])(?: (?P<payload>.*))?$").unwrap()
+    })
+}
+
+/// What a CRI container log line carries, without the wrapper; None for other lines.
+pub fn cri_payload(line: &str) -> Option<&str> {
+    let caps = cri_regex().captures(line)?;
+    Some(caps.name("payload").map_or("", |m| m.as_str()))
+}
+
+/// Apache/nginx common and combined access log format, with the response time nginx
+/// (`$request_time`, `rt=`) and Apache (`%D`) configs often add at the end.
+struct AccessLogParser {
+    regex: Regex,
+    latency_regex: Regex,
+}
+
+impl AccessLogParser {
+    fn new() -> Self {
+        Self {
+            regex: Regex::new(concat!(
+                r#"^(?P<ip>\S+) (?P<ident>\S+) (?P<user>\S+) \[(?P<timestamp>[^\]]+)\] "#,
+                r#""(?P<request>(?P<method>[A-Z]+) (?P<path>\S+)(?: (?P<protocol>[^"]*))?|[^"]*)" "#,
+                r#"(?P<status_code>\d{3}) (?P<bytes>\d+|-)"#,
+                r#"(?: "(?P<referer>[^"]*)" "(?P<user_agent>[^"]*)")?"#,
+                r#"(?P<rest>.*)$"#,
+            )).unwrap(),
+            latency_regex: Regex::new(
+                r#"(?:^|\s)(?:(?:request_time|rt|duration|latency)[=:]"?)?(?P<time>\d+(?:\.\d+)?)"?(?:\s|$)"#,
+            ).unwrap(),
+        }
+    }
+
+    /// Milliseconds from a response time after the standard fields: seconds with a
+    /// fraction (nginx's `$request_time`) or whole microseconds (Apache's `%D`).
+    fn latency_ms(&self, rest: &str) -> Option<String> {
+        let time = self.latency_regex.captures(rest)?.name("time")?.as_str();
+        let ms = if time.contains('.') {
+            time.parse::<f64>().ok()? * 1000.0
+        } else {
+            time.parse::<f64>().ok()? / 1000.0
+        };
+        Some(((ms * 1000.0).round() / 1000.0).to_string())
+    }
+}
+
+impl Parser for AccessLogParser {
+    fn name(&self) -> &str {
+        "accesslog"
+    }
+
+    fn parse(&self, line: &str) -> Option<Value> {
+        let caps = self.regex.captures(line)?;
+        let mut fields = Map::new();
+        for name in ["ip", "ident", "user", "timestamp", "method", "path", "protocol", "status_code", "bytes", "referer", "user_agent"] {
+            if let Some(m) = caps.name(name).filter(|m| m.as_str() != "-") {
+                fields.insert(name.to_string(), json!(m.as_str()));
+            }
+        }
+        // Server errors are errors, client errors are warnings
+        let level = match &caps["status_code"][..1] {
+            "5" => "ERROR",
+            "4" => "WARN",
+            _ => "INFO",
+        };
+        fields.insert("level".to_string(), json!(level));
+        if let Some(latency) = self.latency_ms(&caps["rest"]) {
+            fields.insert("latency_ms".to_string(), json!(latency));
+        }
+        fields.insert("message".to_string(), json!(&caps["request"]));
+        fields.insert("raw".to_string(), json!(line));
+        Some(Value::Object(fields))
+    }
+}
+
+/// A `[parser.NAME]` from the config: the pattern's named groups are the fields.
+struct PatternParser {
+    name: String,
+    pattern: Regex,
+}
+
+impl Parser for PatternParser {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn parse(&self, line: &str) -> Option<Value> {
+        let caps = self.pattern.captures(line)?;
+        let mut fields: Map<String, Value> = self.pattern.capture_names()
+            .flatten()
+            .filter_map(|name| caps.name(name).map(|m| (name.to_string(), json!(m.as_str()))))
+            .collect();
+        add_standard_fields(&mut fields, line);
+        Some(Value::Object(fields))
+    }
+}
+
+/// Fill the standard fields from the names structured formats commonly use for them,
+/// e.g. `msg` for `message` or `severity` for `level`.
+fn add_standard_fields(fields: &mut Map<String, Value>, line: &str) {
+    const ALIASES: &[(&str, &[&str])] = &[
+        ("timestamp", &["timestamp", "time", "ts", "@timestamp", "datetime", "date"]),
+        ("level", &["level", "lvl", "severity", "loglevel", "log_level"]),
+        ("message", &["message", "msg", "@message", "log"]),
+        ("service", &["service", "app", "application", "logger", "component"]),
+        ("status_code", &["status_code", "status", "code"]),
+        ("ip", &["ip", "client_ip", "remote_addr", "remote_ip"]),
+    ];
+    for (field, names) in ALIASES {
+        let value = names.iter().find_map(|name| match fields.get(*name) {
+            Some(Value::String(text)) => Some(text.clone()),
+            Some(Value::Number(n)) => Some(n.to_string()),
+            _ => None,
+        });
+        if let Some(value) = value {
+            fields.insert(field.to_string(), json!(value));
+        }
+    }
+    if !fields.contains_key("message") {
+        fields.insert("message".to_string(), json!(line));
+    }
+    fields.insert("raw".to_string(), json!(line));
+}
+
+/// Shell-style glob match supporting `*` and `?`, where `*` also crosses `/`.
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // Let the last `*` swallow one more character and retry
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
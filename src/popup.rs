@@ -208,11 +208,9 @@ pub fn popup_menu(title: &str, items: &[String], colors: &PopupColors) -> Result
                 if key.kind == KeyEventKind::Release { continue; }
                 match key.code {
                     KeyCode::Up => {
-                        if selected > 0 { selected -= 1; }
-                    }
-                    KeyCode::Down => {
-                        if selected < items.len() - 1 { selected += 1; }
+                        selected = selected.saturating_sub(1);
                     }
+                    KeyCode::Down if selected < items.len() - 1 => { selected += 1; }
                     KeyCode::Home => { selected = 0; }
                     KeyCode::End => { selected = items.len() - 1; }
                     KeyCode::Enter | KeyCode::Char('\n') | KeyCode::Char('\r') => {
@@ -300,31 +298,25 @@ pub fn popup_input(title: &str, prompt: &str, default: &str, colors: &PopupColor
                     KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                         return Ok(PopupResult::Dismissed);
                     }
-                    KeyCode::Backspace => {
-                        if cursor_pos > 0 {
-                            let byte_idx = input.char_indices()
-                                .nth(cursor_pos - 1)
-                                .map(|(idx, _)| idx)
-                                .unwrap_or(0);
-                            input.remove(byte_idx);
-                            cursor_pos -= 1;
-                        }
+                    KeyCode::Backspace if cursor_pos > 0 => {
+                        let byte_idx = input.char_indices()
+                            .nth(cursor_pos - 1)
+                            .map(|(idx, _)| idx)
+                            .unwrap_or(0);
+                        input.remove(byte_idx);
+                        cursor_pos -= 1;
                     }
-                    KeyCode::Delete => {
-                        if cursor_pos < input.chars().count() {
-                            let byte_idx = input.char_indices()
-                                .nth(cursor_pos)
-                                .map(|(idx, _)| idx)
-                                .unwrap_or(input.len());
-                            input.remove(byte_idx);
-                        }
+                    KeyCode::Delete if cursor_pos < input.chars().count() => {
+                        let byte_idx = input.char_indices()
+                            .nth(cursor_pos)
+                            .map(|(idx, _)| idx)
+                            .unwrap_or(input.len());
+                        input.remove(byte_idx);
                     }
                     KeyCode::Left => {
-                        if cursor_pos > 0 { cursor_pos -= 1; }
-                    }
-                    KeyCode::Right => {
-                        if cursor_pos < input.chars().count() { cursor_pos += 1; }
+                        cursor_pos = cursor_pos.saturating_sub(1);
                     }
+                    KeyCode::Right if cursor_pos < input.chars().count() => { cursor_pos += 1; }
                     KeyCode::Home => { cursor_pos = 0; }
                     KeyCode::End => { cursor_pos = input.chars().count(); }
                     KeyCode::Char(c) => {
@@ -342,7 +334,255 @@ pub fn popup_input(title: &str, prompt: &str, default: &str, colors: &PopupColor
     }
 }
 
+/// Display a fuzzy finder over `items`: typing narrows them to the ones matching
+/// fzf-style, best first, with the matched characters marked. Up/Down pick one and Enter
+/// returns Selected(its index in `items`); Esc dismisses it.
+pub fn popup_fuzzy(title: &str, items: &[String], colors: &PopupColors) -> Result<PopupResult> {
+    let (tw, th) = size()?;
+    if tw < 10 || th < 5 || items.is_empty() { return Ok(PopupResult::Dismissed); }
+
+    let popup_w = tw.saturating_sub(4);
+    let popup_h = th.saturating_sub(2);
+    let (px, py) = center_popup(tw, th, popup_w, popup_h);
+    let inner_w = (popup_w - 2) as usize;
+    let visible_count = popup_h.saturating_sub(3) as usize;
+
+    // Matched and shown without colors, one character per column
+    let plain: Vec<Vec<char>> = items.iter()
+        .map(|item| crate::ansi::strip(item).chars().map(|c| if c.is_control() { ' ' } else { c }).collect())
+        .collect();
+    let rank = |input: &str| {
+        let mut matches: Vec<(i64, usize, Vec<usize>)> = plain.iter().enumerate()
+            .filter_map(|(idx, chars)| {
+                let text: String = chars.iter().collect();
+                crate::fuzzy::fuzzy_match(input, &text).map(|(score, positions)| (score, idx, positions))
+            })
+            .collect();
+        // Best first, and the newest of equally good ones
+        matches.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
+        matches
+    };
+
+    let mut input = String::new();
+    let mut matches = rank(&input);
+    let mut selected: usize = 0;
+    let mut scroll_offset: usize = 0;
+
+    loop {
+        let mut buf: Vec<u8> = Vec::with_capacity(16 * 1024);
+        queue!(buf, BeginSynchronizedUpdate)?;
+        let counted = format!("{}({}/{}) ", title, matches.len(), items.len());
+        draw_popup_frame(&mut buf, px, py, popup_w, popup_h, &counted, colors)?;
+
+        // Input line
+        queue!(buf, MoveTo(px + 1, py + 1),
+            SetForegroundColor(colors.highlight_fg), SetBackgroundColor(colors.highlight_bg))?;
+        let shown_input: String = input.chars().rev().take(inner_w.saturating_sub(2)).collect::<Vec<_>>().into_iter().rev().collect();
+        queue!(buf, Print(format!("> {:<width$}", shown_input, width = inner_w.saturating_sub(2))))?;
+
+        if selected < scroll_offset {
+            scroll_offset = selected;
+        } else if selected >= scroll_offset + visible_count {
+            scroll_offset = selected + 1 - visible_count;
+        }
+
+        for i in 0..visible_count {
+            queue!(buf, MoveTo(px + 1, py + 2 + i as u16))?;
+            let Some((_, idx, positions)) = matches.get(scroll_offset + i) else {
+                queue!(buf, SetForegroundColor(colors.content_fg), SetBackgroundColor(colors.content_bg))?;
+                queue!(buf, Print(" ".repeat(inner_w)))?;
+                continue;
+            };
+            let (fg, bg) = match scroll_offset + i == selected {
+                true => (colors.highlight_fg, colors.highlight_bg),
+                false => (colors.content_fg, colors.content_bg),
+            };
+            // Matched characters in the other colors' background color
+            let marked = match scroll_offset + i == selected {
+                true => colors.content_bg,
+                false => colors.highlight_bg,
+            };
+            queue!(buf, SetForegroundColor(fg), SetBackgroundColor(bg), Print(" "))?;
+            let chars = &plain[*idx];
+            for col in 0..inner_w.saturating_sub(1) {
+                let c = chars.get(col).copied().unwrap_or(' ');
+                if positions.binary_search(&col).is_ok() {
+                    queue!(buf, SetForegroundColor(marked), Print(c), SetForegroundColor(fg))?;
+                } else {
+                    queue!(buf, Print(c))?;
+                }
+            }
+        }
+
+        let cursor_x = input.chars().count().min(inner_w.saturating_sub(2));
+        queue!(buf, MoveTo(px + 3 + cursor_x as u16, py + 1))?;
+        queue!(buf, ResetColor, EndSynchronizedUpdate)?;
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(&buf)?;
+        stdout.flush()?;
+
+        if poll(Duration::from_millis(100))? {
+            if let Event::Key(key) = read()? {
+                if key.kind == KeyEventKind::Release { continue; }
+                let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+                match key.code {
+                    KeyCode::Enter | KeyCode::Char('\n') | KeyCode::Char('\r') => {
+                        return Ok(match matches.get(selected) {
+                            Some((_, idx, _)) => PopupResult::Selected(*idx),
+                            None => PopupResult::Dismissed,
+                        });
+                    }
+                    KeyCode::Esc => {
+                        return Ok(PopupResult::Dismissed);
+                    }
+                    KeyCode::Char('c') if ctrl => {
+                        return Ok(PopupResult::Dismissed);
+                    }
+                    KeyCode::Up => { selected = selected.saturating_sub(1); }
+                    KeyCode::Char('p') if ctrl => { selected = selected.saturating_sub(1); }
+                    KeyCode::Down if selected + 1 < matches.len() => { selected += 1; }
+                    KeyCode::Char('n') if ctrl && selected + 1 < matches.len() => { selected += 1; }
+                    KeyCode::PageUp => { selected = selected.saturating_sub(visible_count); }
+                    KeyCode::PageDown => { selected = (selected + visible_count).min(matches.len().saturating_sub(1)); }
+                    KeyCode::Backspace if !input.is_empty() => {
+                        input.pop();
+                        matches = rank(&input);
+                        selected = 0;
+                    }
+                    KeyCode::Char(c) if !ctrl => {
+                        input.push(c);
+                        matches = rank(&input);
+                        selected = 0;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
 /// Wrapper around popup_menu for window/file selection.
 pub fn popup_select_window(names: &[String], colors: &PopupColors) -> Result<PopupResult> {
     popup_menu(" Select Window ", names, colors)
 }
+
+/// A table cell: the text shown and, for numeric columns, the value it sorts by.
+pub struct TableCell {
+    pub text: String,
+    pub sort: Option<f64>,
+}
+
+/// Display a table popup sortable by column: Left/Right or 1-9 pick the sort
+/// column, r reverses the order. Esc, q or Enter dismisses it.
+pub fn popup_table(title: &str, headers: &[&str], rows: &[Vec<TableCell>], colors: &PopupColors) -> Result<()> {
+    let (tw, th) = size()?;
+    if tw < 10 || th < 5 || headers.is_empty() { return Ok(()); }
+
+    let widths: Vec<usize> = headers.iter().enumerate()
+        .map(|(col, header)| {
+            rows.iter()
+                .map(|row| row.get(col).map(|cell| cell.text.chars().count()).unwrap_or(0))
+                .max()
+                .unwrap_or(0)
+                .max(header.chars().count() + 2)
+        })
+        .collect();
+    let table_w: usize = widths.iter().sum::<usize>() + (widths.len() - 1) * 2;
+    let popup_w = (table_w + 4).min(tw.saturating_sub(4) as usize) as u16;
+    let popup_h = (rows.len() as u16 + 5).min(th.saturating_sub(2));
+    let (px, py) = center_popup(tw, th, popup_w, popup_h);
+    let inner_w = (popup_w - 2) as usize;
+    let visible_rows = popup_h.saturating_sub(5) as usize;
+
+    let mut sort_col = 0usize;
+    let mut descending = false;
+
+    let format_row = |cells: Vec<&str>| {
+        let mut line = String::from(" ");
+        for (col, text) in cells.iter().enumerate() {
+            if col > 0 {
+                line.push_str("  ");
+            }
+            // Numeric columns are right-aligned, text columns left-aligned
+            if rows.first().and_then(|row| row.get(col)).is_some_and(|cell| cell.sort.is_some()) {
+                line.push_str(&format!("{:>width$}", text, width = widths[col]));
+            } else {
+                line.push_str(&format!("{:<width$}", text, width = widths[col]));
+            }
+        }
+        line
+    };
+
+    loop {
+        let mut order: Vec<usize> = (0..rows.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (ca, cb) = (rows[a].get(sort_col), rows[b].get(sort_col));
+            let ordering = match (ca.and_then(|c| c.sort), cb.and_then(|c| c.sort)) {
+                (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(std::cmp::Ordering::Equal),
+                _ => ca.map(|c| c.text.as_str()).cmp(&cb.map(|c| c.text.as_str())),
+            };
+            if descending { ordering.reverse() } else { ordering }
+        });
+
+        let mut buf: Vec<u8> = Vec::with_capacity(8 * 1024);
+        queue!(buf, BeginSynchronizedUpdate)?;
+        draw_popup_frame(&mut buf, px, py, popup_w, popup_h, title, colors)?;
+
+        let header_cells: Vec<String> = headers.iter().enumerate()
+            .map(|(col, header)| {
+                if col == sort_col {
+                    format!("{} {}", header, if descending { "▼" } else { "▲" })
+                } else {
+                    header.to_string()
+                }
+            })
+            .collect();
+        let header_line = format_row(header_cells.iter().map(String::as_str).collect());
+        queue!(buf, MoveTo(px + 1, py + 1),
+            SetForegroundColor(colors.highlight_fg), SetBackgroundColor(colors.highlight_bg),
+            Print(format!("{:<width$}", truncate_chars(&header_line, inner_w), width = inner_w)))?;
+
+        for (i, &row_idx) in order.iter().take(visible_rows).enumerate() {
+            let line = format_row(rows[row_idx].iter().map(|cell| cell.text.as_str()).collect());
+            queue!(buf, MoveTo(px + 1, py + 2 + i as u16),
+                SetForegroundColor(colors.content_fg), SetBackgroundColor(colors.content_bg),
+                Print(format!("{:<width$}", truncate_chars(&line, inner_w), width = inner_w)))?;
+        }
+
+        let footer = " ←/→ or 1-9 sort column, r reverse, q close";
+        queue!(buf, MoveTo(px + 1, py + popup_h - 2),
+            SetForegroundColor(colors.content_fg), SetBackgroundColor(colors.content_bg),
+            Print(format!("{:<width$}", truncate_chars(footer, inner_w), width = inner_w)))?;
+
+        queue!(buf, ResetColor, EndSynchronizedUpdate)?;
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(&buf)?;
+        stdout.flush()?;
+
+        if poll(Duration::from_millis(100))? {
+            if let Event::Key(key) = read()? {
+                if key.kind == KeyEventKind::Release { continue; }
+                let mut new_col = None;
+                match key.code {
+                    KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => return Ok(()),
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+                    KeyCode::Left => new_col = Some(sort_col.saturating_sub(1)),
+                    KeyCode::Right | KeyCode::Tab => new_col = Some((sort_col + 1) % headers.len()),
+                    KeyCode::Char('r') => descending = !descending,
+                    KeyCode::Char(c) if c.is_ascii_digit() => {
+                        let n = c.to_digit(10).unwrap() as usize;
+                        if n >= 1 && n <= headers.len() {
+                            new_col = Some(n - 1);
+                        }
+                    }
+                    _ => {}
+                }
+                if let Some(col) = new_col {
+                    sort_col = col;
+                    // Numbers read best largest-first, names alphabetically
+                    descending = rows.first().and_then(|row| row.get(col)).is_some_and(|cell| cell.sort.is_some());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::colorizer::Colorizer;
+use crate::config::Config;
+use crate::theme::{Color, ColorDepth, ColorRule, Theme};
+
+/// `ft themes preview`: print the last `lines` lines of `file` (or a built-in sample
+/// log) colored with a theme, then each of its rules drawn in its own style.
+pub fn preview(config: &Config, theme_name: Option<&str>, file: Option<&Path>, lines: usize) -> Result<()> {
+    let theme_name = theme_name.unwrap_or(&config.general.theme);
+    let depth = ColorDepth::detect();
+    let mut theme = Theme::resolve(config, theme_name)?;
+    theme.downgrade(depth);
+
+    let origin = config.get_theme_path(theme_name)
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| "built-in".to_string());
+    println!("Theme: {} ({})", theme_name, origin);
+    println!();
+
+    let text = match file {
+        Some(path) => std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read file: {:?}", path))?,
+        None => crate::examples::sample_log(),
+    };
+    let all: Vec<&str> = text.lines().collect();
+    let line_rules = theme.line_rules.clone();
+    let word_rules = theme.word_rules.clone();
+    let level_rules: Vec<ColorRule> = theme.level_rules.iter().map(|level| level.rule.clone()).collect();
+    let colorizer = Colorizer::new(theme, false).with_color_depth(depth);
+    for line in &all[all.len().saturating_sub(lines)..] {
+        println!("{}", colorizer.colorize_line(line));
+    }
+
+    print_rules("Line rules", &line_rules);
+    print_rules("Word rules", &word_rules);
+    print_rules("Level rules", &level_rules);
+    Ok(())
+}
+
+fn print_rules(heading: &str, rules: &[ColorRule]) {
+    if rules.is_empty() {
+        return;
+    }
+    println!();
+    println!("{} ({}):", heading, rules.len());
+    for rule in rules {
+        let group = rule.group.map(|n| format!("  (group {})", n)).unwrap_or_default();
+        println!("  {}{}{}{}", rule.style.to_ansi(), rule.original_pattern, Color::to_ansi_reset(), group);
+    }
+}
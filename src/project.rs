@@ -0,0 +1,80 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::filter::json_path;
+use crate::output::parse_fields;
+use crate::parser::Parser;
+
+/// `--jq` and `--fields`: show only some fields of each structured line, given comma
+/// separated and printed space separated.
+#[derive(Debug, Clone)]
+pub enum Projection {
+    /// `.msg, .user.id, .tags[0]`: paths into the line's own JSON
+    Jq(Vec<String>),
+    /// `timestamp,level,msg`: fields as the input format parses them
+    Fields(Vec<String>),
+}
+
+impl Projection {
+    pub fn parse_jq(expr: &str) -> Result<Self> {
+        let paths = expr.split(',')
+            .map(|path| {
+                let path = path.trim();
+                if path.contains(char::is_whitespace) {
+                    return Err(anyhow!("Invalid --jq path '{}': separate paths with commas", path));
+                }
+                let rest = path.strip_prefix('.')
+                    .ok_or_else(|| anyhow!("Invalid --jq path '{}': paths start with '.', e.g. .msg or .user.id", path))?;
+                // `.tags[0]` is `.tags.0`
+                let rest = rest.replace('[', ".").replace(']', "");
+                let keys = rest.trim_start_matches('.');
+                if keys.contains("..") || keys.ends_with('.') || keys.contains(|c: char| "\"'|()".contains(c)) {
+                    return Err(anyhow!("Unsupported --jq path '{}': only ., .key, .key.sub and .key[N] paths are", path));
+                }
+                Ok(keys.to_string())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Projection::Jq(paths))
+    }
+
+    pub fn parse_fields(spec: &str) -> Result<Self> {
+        let fields: Vec<String> = spec.split(',').map(str::trim).filter(|f| !f.is_empty()).map(str::to_string).collect();
+        if fields.is_empty() {
+            return Err(anyhow!("--fields needs at least one field name"));
+        }
+        if let Some(field) = fields.iter().find(|f| f.contains(char::is_whitespace)) {
+            return Err(anyhow!("Invalid --fields name '{}': separate field names with commas", field));
+        }
+        Ok(Projection::Fields(fields))
+    }
+
+    /// The selected values of `line`, or None when it has none of them (such as a
+    /// line that isn't JSON under `--jq`), so it can be shown as it is.
+    pub fn apply(&self, parser: &dyn Parser, line: &str) -> Option<String> {
+        let values: Vec<String> = match self {
+            Projection::Jq(paths) => {
+                let json: Value = serde_json::from_str(line.trim()).ok()?;
+                paths.iter()
+                    .filter_map(|path| if path.is_empty() { Some(&json) } else { json_path(&json, path) })
+                    .map(text)
+                    .collect()
+            }
+            Projection::Fields(names) => {
+                let fields = parse_fields(parser, line);
+                names.iter()
+                    .filter_map(|name| fields.get(name).or_else(|| json_path(&fields, name)))
+                    .map(text)
+                    .collect()
+            }
+        };
+        (!values.is_empty()).then(|| values.join(" "))
+    }
+}
+
+/// Strings as they are (like `jq -r`), anything else as compact JSON.
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::filter::{detect_log_level, LogLevel};
+
+/// Seconds the sparklines cover, one character each
+const WIDTH: usize = 60;
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// `--rate`: lines and errors read per second while following, printed every so often
+/// as sparklines of the last minute.
+pub struct RateMeter {
+    started: Instant,
+    interval: Duration,
+    last_report: Instant,
+    /// (second since `started`, lines, errors), oldest first
+    seconds: VecDeque<(u64, usize, usize)>,
+}
+
+impl RateMeter {
+    pub fn new(interval: Duration) -> Self {
+        let now = Instant::now();
+        Self { started: now, interval, last_report: now, seconds: VecDeque::new() }
+    }
+
+    /// Count lines just read.
+    pub fn observe(&mut self, lines: &[String]) {
+        let errors = lines.iter()
+            .filter(|line| detect_log_level(line).is_some_and(|level| level.priority() <= LogLevel::Error.priority()))
+            .count();
+        self.tick();
+        if let Some(last) = self.seconds.back_mut() {
+            last.1 += lines.len();
+            last.2 += errors;
+        }
+    }
+
+    /// The sparklines, once `interval` has passed since they were last printed.
+    pub fn due_report(&mut self) -> Option<String> {
+        if self.last_report.elapsed() < self.interval {
+            return None;
+        }
+        self.last_report = Instant::now();
+        self.tick();
+        // The current second is still filling up
+        let done: Vec<(usize, usize)> = self.seconds.iter()
+            .take(self.seconds.len().saturating_sub(1))
+            .map(|(_, lines, errors)| (*lines, *errors))
+            .collect();
+        if done.is_empty() {
+            return None;
+        }
+        let lines: Vec<usize> = done.iter().map(|(lines, _)| *lines).collect();
+        let errors: Vec<usize> = done.iter().map(|(_, errors)| *errors).collect();
+        Some(format!("ft rate  lines {}  errors {}", summary(&lines), summary(&errors)))
+    }
+
+    /// Make sure there's a bucket for the current second, with empty ones for quiet
+    /// seconds before it.
+    fn tick(&mut self) {
+        let now = self.started.elapsed().as_secs();
+        let next = self.seconds.back().map_or(now, |last| last.0 + 1);
+        for second in next.max(now.saturating_sub(WIDTH as u64))..=now {
+            self.seconds.push_back((second, 0, 0));
+        }
+        // One more than WIDTH: the newest second isn't shown until it's over
+        while self.seconds.len() > WIDTH + 1 {
+            self.seconds.pop_front();
+        }
+    }
+}
+
+/// `▁▂▅█▃ 12.0/s (peak 40)`: a sparkline scaled to its peak, the last second's rate and the peak.
+fn summary(counts: &[usize]) -> String {
+    let peak = counts.iter().copied().max().unwrap_or(0);
+    let line: String = counts.iter()
+        .map(|&count| match peak {
+            0 => BARS[0],
+            _ => BARS[(count * (BARS.len() - 1)).div_ceil(peak)],
+        })
+        .collect();
+    let last = counts.last().copied().unwrap_or(0);
+    format!("{} {}/s (peak {})", line, last, peak)
+}
@@ -0,0 +1,124 @@
+use anyhow::{Context, Result};
+use regex::{Captures, Regex};
+use std::net::Ipv6Addr;
+
+use crate::theme::{IPV4_PATTERN, IPV6_PATTERN};
+
+/// `--redact` and `--anonymize-ips`: hides secrets, personal data and addresses in lines
+/// as they're read, so nothing after it (the screen, output files, sinks, alerts) ever
+/// sees them.
+pub struct Redactor {
+    rules: Vec<Rule>,
+}
+
+struct Rule {
+    /// With capture groups, only the first is replaced, so `password=(\S+)` keeps `password=`
+    pattern: Regex,
+    replace: Replace,
+}
+
+enum Replace {
+    Text(&'static str),
+    /// Only digit runs that pass the Luhn check, to tell card numbers from other numbers
+    Card(&'static str),
+    /// Zero the last `n` octets of an IPv4 address
+    Ipv4(usize),
+    /// Zero all but the first `4 - n` groups of an IPv6 address (/48 for one octet)
+    Ipv6(usize),
+}
+
+/// (pattern, replacement)
+const SECRETS: &[(&str, &str)] = &[
+    (r"(?i)\bbearer\s+([A-Za-z0-9._~+/-]+=*)", "[REDACTED:token]"),
+    (r"\b((?:AKIA|ASIA|AIDA|AROA)[0-9A-Z]{16})\b", "[REDACTED:aws-key]"),
+    (r#"(?i)aws_?secret_?access_?key["']?\s*[:=]\s*["']?([A-Za-z0-9/+=]{40})"#, "[REDACTED:aws-secret]"),
+    (r"\b([A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,})\b", "[REDACTED:email]"),
+];
+const CARD: &str = r"\b((?:\d[ -]?){12,18}\d)\b";
+
+impl Redactor {
+    pub fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Hide the built-in secrets (bearer tokens, AWS keys, card numbers, email addresses)
+    /// and matches of `patterns` from the config.
+    pub fn with_secrets(mut self, patterns: &[String]) -> Result<Self> {
+        for (pattern, replacement) in SECRETS {
+            self.rules.push(Rule { pattern: Regex::new(pattern).unwrap(), replace: Replace::Text(replacement) });
+        }
+        self.rules.push(Rule { pattern: Regex::new(CARD).unwrap(), replace: Replace::Card("[REDACTED:card]") });
+        for pattern in patterns {
+            let pattern = Regex::new(pattern).with_context(|| format!("Invalid [redact] pattern: {}", pattern))?;
+            self.rules.push(Rule { pattern, replace: Replace::Text("[REDACTED]") });
+        }
+        Ok(self)
+    }
+
+    /// Zero the last `octets` (1 to 3) octets of IP addresses, keeping them recognizable as
+    /// addresses, and in the same network, for hash colors, `--top ip` and the like.
+    pub fn with_ip_masking(mut self, octets: usize) -> Self {
+        self.rules.push(Rule { pattern: Regex::new(IPV6_PATTERN).unwrap(), replace: Replace::Ipv6(octets) });
+        self.rules.push(Rule { pattern: Regex::new(IPV4_PATTERN).unwrap(), replace: Replace::Ipv4(octets) });
+        self
+    }
+
+    pub fn redact(&self, line: String) -> String {
+        let mut line = line;
+        for rule in &self.rules {
+            if !rule.pattern.is_match(&line) {
+                continue;
+            }
+            line = rule.pattern.replace_all(&line, |caps: &Captures| {
+                let whole = caps.get(0).unwrap();
+                let secret = caps.get(1).unwrap_or(whole);
+                let Some(replacement) = rule.replace.apply(secret.as_str()) else {
+                    return whole.as_str().to_string();
+                };
+                let start = secret.start() - whole.start();
+                let end = secret.end() - whole.start();
+                format!("{}{}{}", &whole.as_str()[..start], replacement, &whole.as_str()[end..])
+            }).into_owned();
+        }
+        line
+    }
+}
+
+impl Replace {
+    /// What `secret` becomes, or None to leave it alone.
+    fn apply(&self, secret: &str) -> Option<String> {
+        match self {
+            Replace::Text(text) => Some(text.to_string()),
+            Replace::Card(text) => luhn(secret).then(|| text.to_string()),
+            Replace::Ipv4(octets) => {
+                let (address, port) = match secret.split_once(':') {
+                    Some((address, port)) => (address, Some(port)),
+                    None => (secret, None),
+                };
+                let parts: Vec<&str> = address.split('.').collect();
+                let kept = parts.len().saturating_sub(*octets);
+                let masked: Vec<&str> = parts.iter().enumerate().map(|(i, part)| if i < kept { *part } else { "0" }).collect();
+                Some(match port {
+                    Some(port) => format!("{}:{}", masked.join("."), port),
+                    None => masked.join("."),
+                })
+            }
+            Replace::Ipv6(octets) => {
+                let address: Ipv6Addr = secret.parse().ok()?;
+                let kept = 4usize.saturating_sub(*octets);
+                let mut segments = address.segments();
+                segments[kept..].fill(0);
+                Some(Ipv6Addr::from(segments).to_string())
+            }
+        }
+    }
+}
+
+/// Whether the digits in `number` pass the Luhn checksum card numbers carry.
+fn luhn(number: &str) -> bool {
+    let digits: Vec<u32> = number.chars().filter_map(|c| c.to_digit(10)).collect();
+    let sum: u32 = digits.iter().rev().enumerate()
+        .map(|(i, &d)| if i % 2 == 1 { if d * 2 > 9 { d * 2 - 9 } else { d * 2 } } else { d })
+        .sum();
+    sum.is_multiple_of(10)
+}
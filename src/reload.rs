@@ -0,0 +1,49 @@
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Notices when the active themes should be reloaded: one of their files was saved,
+/// or ft got SIGHUP. Built-in themes have no file, so only SIGHUP applies to them.
+pub struct ThemeWatcher {
+    changed: Arc<AtomicBool>,
+    // Kept alive for as long as the theme is watched
+    _watchers: Vec<RecommendedWatcher>,
+}
+
+impl ThemeWatcher {
+    pub fn new(theme_files: &[PathBuf]) -> Self {
+        let changed = Arc::new(AtomicBool::new(false));
+        #[cfg(unix)]
+        {
+            let _ = signal_hook::flag::register(signal_hook::consts::SIGHUP, changed.clone());
+        }
+        let watchers = theme_files.iter()
+            .filter_map(|file| Self::watch(file, changed.clone()))
+            .collect();
+        Self { changed, _watchers: watchers }
+    }
+
+    /// Watch the file's directory rather than the file: editors often save by
+    /// writing a new file and renaming it over the old one.
+    fn watch(file: &Path, changed: Arc<AtomicBool>) -> Option<RecommendedWatcher> {
+        let dir = file.parent()?;
+        let name = file.file_name()?.to_os_string();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            let ours = event.paths.iter().any(|path| path.file_name() == Some(name.as_os_str()));
+            if ours && matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                changed.store(true, Ordering::SeqCst);
+            }
+        }).ok()?;
+        watcher.watch(dir, RecursiveMode::NonRecursive).ok()?;
+        Some(watcher)
+    }
+
+    /// Whether a reload was asked for since the last call.
+    pub fn take(&self) -> bool {
+        self.changed.swap(false, Ordering::SeqCst)
+    }
+}
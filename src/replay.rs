@@ -0,0 +1,47 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
+use std::thread;
+use std::time::Instant;
+
+/// `--replay`: prints lines with the gaps their timestamps had, sped up by `--speed`.
+pub struct Replay {
+    speed: f64,
+    /// The first timestamp, and when its line was printed
+    start: Option<(DateTime<Local>, Instant)>,
+}
+
+impl Replay {
+    pub fn new(speed: f64) -> Self {
+        Self { speed, start: None }
+    }
+
+    /// Wait until a line timestamped `time` is due. Pacing follows the first line's
+    /// timestamp, so time spent printing doesn't add up over a long replay; lines without a
+    /// timestamp, or out of order, go straight out.
+    pub fn wait(&mut self, time: Option<DateTime<Local>>) {
+        let Some(time) = time else {
+            return;
+        };
+        let Some((first, started)) = self.start else {
+            self.start = Some((time, Instant::now()));
+            return;
+        };
+        let Ok(offset) = (time - first).to_std() else {
+            return;
+        };
+        let due = started + offset.div_f64(self.speed);
+        let now = Instant::now();
+        if due > now {
+            thread::sleep(due - now);
+        }
+    }
+}
+
+/// `--speed`: `10x`, `0.5x` or just `10`.
+pub fn parse_speed(text: &str) -> Result<f64> {
+    let number = text.trim().trim_end_matches(['x', 'X']);
+    match number.parse::<f64>() {
+        Ok(speed) if speed.is_finite() && speed > 0.0 => Ok(speed),
+        _ => Err(anyhow!("Invalid speed: {} (expected e.g. 10x or 0.5x)", text)),
+    }
+}
@@ -0,0 +1,166 @@
+use serde_json::Value;
+use std::path::Path;
+
+use crate::envelope::hostname;
+use crate::filter::{detect_log_level, LogLevel};
+use crate::parser::GenericParser;
+
+const VENDOR: &str = "fuzzytail";
+const PRODUCT: &str = "ft";
+/// CEF's Name header is meant to be short; the whole message is in `msg`
+const MAX_NAME: usize = 128;
+
+/// Parsed fields and the CEF extension keys they map to. `cs1` carries the service, with
+/// its label, as CEF has no key of its own for it.
+const CEF_KEYS: &[(&str, &str)] = &[
+    ("ip", "src"),
+    ("user", "suser"),
+    ("method", "requestMethod"),
+    ("path", "request"),
+    ("user_agent", "requestClientApplication"),
+    ("referer", "requestContext"),
+    ("bytes", "out"),
+    ("protocol", "app"),
+    ("host", "dvchost"),
+    ("pid", "dvcpid"),
+];
+
+/// Parsed fields and their LEEF attribute names, predefined ones where QRadar has them.
+const LEEF_KEYS: &[(&str, &str)] = &[
+    ("ip", "src"),
+    ("user", "usrName"),
+    ("method", "method"),
+    ("path", "url"),
+    ("user_agent", "userAgent"),
+    ("referer", "referer"),
+    ("bytes", "dstBytes"),
+    ("protocol", "proto"),
+    ("status_code", "statusCode"),
+    ("service", "service"),
+    ("host", "identHostName"),
+    ("pid", "pid"),
+];
+
+/// `--format cef`: an ArcSight Common Event Format record,
+/// `CEF:0|fuzzytail|ft|VERSION|CLASS|NAME|SEVERITY|EXTENSIONS`. The event class is the
+/// line's level (or `line`), the name its message, and the severity 0-10 from the level.
+pub fn cef(fields: &Value, timestamps: &GenericParser, path: &Path, line: &str) -> String {
+    let level = level(fields, line);
+    let message = text(fields, "message").unwrap_or_else(|| line.to_string());
+    let name: String = message.chars().take(MAX_NAME).collect();
+
+    let mut extensions = Vec::new();
+    if let Some(time) = timestamp(fields, timestamps, line) {
+        extensions.push(("rt".to_string(), time.to_string()));
+    }
+    for (field, key) in CEF_KEYS {
+        if let Some(value) = text(fields, field) {
+            extensions.push((key.to_string(), value));
+        }
+    }
+    if let Some(status) = text(fields, "status_code") {
+        extensions.push(("cn1".to_string(), status));
+        extensions.push(("cn1Label".to_string(), "statusCode".to_string()));
+    }
+    if let Some(service) = text(fields, "service") {
+        extensions.push(("cs1".to_string(), service));
+        extensions.push(("cs1Label".to_string(), "service".to_string()));
+    }
+    if !extensions.iter().any(|(key, _)| key == "dvchost") {
+        extensions.push(("dvchost".to_string(), hostname()));
+    }
+    extensions.push(("fname".to_string(), path.display().to_string()));
+    extensions.push(("msg".to_string(), message));
+
+    let extensions: Vec<String> = extensions.iter()
+        .map(|(key, value)| format!("{}={}", key, cef_extension(value)))
+        .collect();
+    format!(
+        "CEF:0|{}|{}|{}|{}|{}|{}|{}",
+        VENDOR, PRODUCT, env!("CARGO_PKG_VERSION"),
+        cef_header(&level.map_or_else(|| "line".to_string(), |l| format!("{:?}", l).to_uppercase())),
+        cef_header(&name),
+        level.map_or(0, cef_severity),
+        extensions.join(" "),
+    )
+}
+
+/// `--format leef`: an IBM QRadar LEEF 2.0 record, `LEEF:2.0|fuzzytail|ft|VERSION|EVENT|^|`
+/// and `^`-separated attributes (tabs and pipes are common in logs, carets aren't).
+pub fn leef(fields: &Value, timestamps: &GenericParser, path: &Path, line: &str) -> String {
+    let level = level(fields, line);
+    let mut attributes = Vec::new();
+    if let Some(time) = timestamp(fields, timestamps, line) {
+        attributes.push(("devTime".to_string(), time.to_string()));
+        attributes.push(("devTimeFormat".to_string(), "milliseconds".to_string()));
+    }
+    if let Some(level) = level {
+        attributes.push(("sev".to_string(), cef_severity(level).max(1).to_string()));
+    }
+    for (field, key) in LEEF_KEYS {
+        if let Some(value) = text(fields, field) {
+            attributes.push((key.to_string(), value));
+        }
+    }
+    attributes.push(("fname".to_string(), path.display().to_string()));
+    attributes.push(("msg".to_string(), text(fields, "message").unwrap_or_else(|| line.to_string())));
+
+    let attributes: Vec<String> = attributes.iter()
+        .map(|(key, value)| format!("{}={}", key, leef_value(value)))
+        .collect();
+    format!(
+        "LEEF:2.0|{}|{}|{}|{}|^|{}",
+        VENDOR, PRODUCT, env!("CARGO_PKG_VERSION"),
+        cef_header(&level.map_or_else(|| "line".to_string(), |l| format!("{:?}", l).to_uppercase())),
+        attributes.join("^"),
+    )
+}
+
+fn text(fields: &Value, name: &str) -> Option<String> {
+    match fields.get(name)? {
+        Value::String(text) if !text.is_empty() => Some(text.clone()),
+        Value::Null | Value::String(_) | Value::Array(_) | Value::Object(_) => None,
+        other => Some(other.to_string()),
+    }
+}
+
+fn level(fields: &Value, line: &str) -> Option<LogLevel> {
+    text(fields, "level").and_then(|level| LogLevel::from_name(&level)).or_else(|| detect_log_level(line))
+}
+
+/// Milliseconds since the epoch, from the parsed timestamp or the first one in the line.
+fn timestamp(fields: &Value, timestamps: &GenericParser, line: &str) -> Option<i64> {
+    text(fields, "timestamp")
+        .and_then(|time| timestamps.parse_timestamp(&time))
+        .or_else(|| timestamps.parse_timestamp(line))
+        .map(|time| time.timestamp_millis())
+}
+
+/// CEF severity: 10 is the most severe.
+fn cef_severity(level: LogLevel) -> u8 {
+    match level {
+        LogLevel::Emergency => 10,
+        LogLevel::Alert => 9,
+        LogLevel::Critical => 8,
+        LogLevel::Error => 7,
+        LogLevel::Warning => 5,
+        LogLevel::Notice => 3,
+        LogLevel::Info => 2,
+        LogLevel::Debug => 1,
+    }
+}
+
+/// Header fields escape backslashes and pipes, and can't span lines.
+fn cef_header(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+/// Extension values escape backslashes and equals signs, and newlines as `\n`.
+fn cef_extension(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('=', "\\=").replace("\r\n", "\\n").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+/// LEEF values can't contain the delimiter or line breaks.
+fn leef_value(text: &str) -> String {
+    text.replace('^', " ").replace(['\r', '\n'], " ")
+}
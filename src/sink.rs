@@ -0,0 +1,168 @@
+use anyhow::{anyhow, Context, Result};
+use regex::Regex;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::duration::parse_duration;
+use crate::limits::parse_size;
+
+const DEFAULT_BATCH_BYTES: usize = 64 * 1024;
+const DEFAULT_BATCH_INTERVAL: Duration = Duration::from_millis(200);
+/// A frame is one pass of the follow loop; a stream that never pauses still gets one this often
+const MAX_FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+/// When a sink pushes buffered output to its destination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlushPolicy {
+    /// After every line
+    Line,
+    /// After each batch of lines read together while following
+    Frame,
+    /// Once `bytes` are buffered or `interval` has passed since the last flush
+    Batch { bytes: usize, interval: Duration },
+}
+
+impl FlushPolicy {
+    /// Parse `line`, `frame` or `batch[:SIZE][/TIME]`, e.g. `batch:1M/2s`.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let spec = spec.trim().to_lowercase();
+        let (mode, limits) = spec.split_once(':').unwrap_or((spec.as_str(), ""));
+        match (mode, limits) {
+            ("line", "") => Ok(FlushPolicy::Line),
+            ("frame", "") => Ok(FlushPolicy::Frame),
+            ("batch", limits) => {
+                let (size, time) = limits.split_once('/').unwrap_or((limits, ""));
+                let bytes = match size {
+                    "" => DEFAULT_BATCH_BYTES,
+                    size => parse_size(size)?,
+                };
+                let interval = match time {
+                    "" => DEFAULT_BATCH_INTERVAL,
+                    time => parse_duration(time).with_context(|| format!("Invalid flush interval: {}", time))?,
+                };
+                Ok(FlushPolicy::Batch { bytes, interval })
+            }
+            _ => Err(anyhow!("Invalid flush policy '{}': expected line, frame or batch[:SIZE][/TIME]", spec)),
+        }
+    }
+
+    /// What stdout uses without `--flush`: every line on a terminal or while following,
+    /// so `ft -f app.log | grep` sees lines as they come, and batches for one-off dumps.
+    pub fn default_for_stdout(terminal: bool, follow: bool) -> Self {
+        if terminal || follow {
+            FlushPolicy::Line
+        } else {
+            FlushPolicy::Batch { bytes: DEFAULT_BATCH_BYTES, interval: DEFAULT_BATCH_INTERVAL }
+        }
+    }
+}
+
+/// Buffered line output flushed by a `FlushPolicy`. Once the reader goes away
+/// (`ft app.log | head`) the sink closes and drops further output quietly.
+pub struct Sink<W: Write> {
+    out: BufWriter<W>,
+    policy: FlushPolicy,
+    last_flush: Instant,
+    closed: bool,
+}
+
+impl<W: Write> Sink<W> {
+    pub fn new(out: W, policy: FlushPolicy) -> Self {
+        let capacity = match policy {
+            FlushPolicy::Batch { bytes, .. } => bytes.max(1),
+            _ => 8 * 1024,
+        };
+        Self {
+            out: BufWriter::with_capacity(capacity, out),
+            policy,
+            last_flush: Instant::now(),
+            closed: false,
+        }
+    }
+
+    /// Whether output failed and nothing more will be written.
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    pub fn write_line(&mut self, text: &str) {
+        if self.closed {
+            return;
+        }
+        let written = self.out.write_all(text.as_bytes()).and_then(|_| self.out.write_all(b"\n"));
+        self.check(written);
+        match self.policy {
+            FlushPolicy::Line => self.flush(),
+            FlushPolicy::Frame => self.flush_after(MAX_FRAME_INTERVAL),
+            // BufWriter writes through by itself once `bytes` are buffered
+            FlushPolicy::Batch { interval, .. } => self.flush_after(interval),
+        }
+    }
+
+    /// The follow loop finished a pass: flush a frame, or a batch that's waited long enough.
+    pub fn end_frame(&mut self) {
+        match self.policy {
+            FlushPolicy::Line => {}
+            FlushPolicy::Frame => self.flush(),
+            FlushPolicy::Batch { interval, .. } => self.flush_after(interval),
+        }
+    }
+
+    pub fn flush(&mut self) {
+        if self.closed {
+            return;
+        }
+        let flushed = self.out.flush();
+        self.check(flushed);
+        self.last_flush = Instant::now();
+    }
+
+    fn flush_after(&mut self, interval: Duration) {
+        if self.last_flush.elapsed() >= interval {
+            self.flush();
+        }
+    }
+
+    fn check(&mut self, result: io::Result<()>) {
+        if let Err(e) = result {
+            if e.kind() != ErrorKind::BrokenPipe {
+                eprintln!("ft: error writing output: {}", e);
+            }
+            self.closed = true;
+        }
+    }
+}
+
+/// `--output-file`: a copy of what ft shows, appended to a file, with colors and
+/// links taken out when `plain` is set.
+pub struct Tee {
+    sink: Sink<File>,
+    escapes: Option<Regex>,
+}
+
+impl Tee {
+    pub fn open(path: &Path, plain: bool, policy: FlushPolicy) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)
+            .with_context(|| format!("Failed to open output file {:?}", path))?;
+        // SGR colors and OSC 8 hyperlinks
+        let escapes = plain.then(|| Regex::new(r"\x1b\[[0-9;]*[A-Za-z]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)").unwrap());
+        Ok(Self { sink: Sink::new(file, policy), escapes })
+    }
+
+    pub fn write_line(&mut self, text: &str) {
+        match &self.escapes {
+            Some(escapes) => self.sink.write_line(&escapes.replace_all(text, "")),
+            None => self.sink.write_line(text),
+        }
+    }
+
+    pub fn end_frame(&mut self) {
+        self.sink.end_frame();
+    }
+
+    pub fn flush(&mut self) {
+        self.sink.flush();
+    }
+}
@@ -0,0 +1,127 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::filter::detect_log_level;
+use crate::output::parse_fields;
+use crate::parser::Parser;
+
+/// Inserts are committed in batches: once per frame while following, or after this many
+#[cfg(feature = "sqlite")]
+const BATCH: usize = 1000;
+
+/// `--sqlite`: every record ft shows, parsed into a `records` table so the session can be
+/// queried with SQL afterwards. The table is created if needed and appended to.
+pub struct SqliteSink {
+    #[cfg(feature = "sqlite")]
+    connection: rusqlite::Connection,
+    /// Inserts since the last commit
+    pending: usize,
+    /// Set once a failed insert has been reported
+    failed: bool,
+}
+
+#[cfg(feature = "sqlite")]
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS records (
+    id INTEGER PRIMARY KEY,
+    captured_at TEXT NOT NULL,
+    timestamp TEXT,
+    level TEXT,
+    service TEXT,
+    message TEXT,
+    file TEXT NOT NULL,
+    raw TEXT NOT NULL
+)";
+
+impl SqliteSink {
+    #[cfg(feature = "sqlite")]
+    pub fn open(path: &Path) -> Result<Self> {
+        use anyhow::Context;
+        let connection = rusqlite::Connection::open(path)
+            .with_context(|| format!("Failed to open SQLite database {:?}", path))?;
+        connection.execute_batch(SCHEMA)
+            .with_context(|| format!("Failed to create the records table in {:?}", path))?;
+        Ok(Self { connection, pending: 0, failed: false })
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    pub fn open(_path: &Path) -> Result<Self> {
+        Err(anyhow::anyhow!("This ft was built without SQLite support (the `sqlite` feature)"))
+    }
+
+    /// Insert `line`, read from `path`, with the fields `parser` finds in it. A failure is
+    /// reported once; the tail carries on either way.
+    pub fn insert(&mut self, path: &Path, parser: &dyn Parser, line: &str) {
+        let fields = parse_fields(parser, line);
+        let field = |name: &str| fields.get(name).and_then(|v| v.as_str()).filter(|s| !s.is_empty()).map(str::to_string);
+        let level = field("level").or_else(|| detect_log_level(line).map(|l| format!("{:?}", l).to_uppercase()));
+        let record = Record {
+            captured_at: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+            timestamp: field("timestamp"),
+            level,
+            service: field("service"),
+            message: field("message"),
+            file: path.display().to_string(),
+            raw: line.to_string(),
+        };
+        if let Err(e) = self.write(&record) {
+            if !self.failed {
+                eprintln!("ft: --sqlite: {:#}", e);
+                self.failed = true;
+            }
+        }
+    }
+
+    /// Commit what's been inserted so far.
+    pub fn flush(&mut self) {
+        if self.pending == 0 {
+            return;
+        }
+        self.pending = 0;
+        if let Err(e) = self.commit() {
+            eprintln!("ft: --sqlite: {:#}", e);
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn write(&mut self, record: &Record) -> Result<()> {
+        if self.pending == 0 {
+            self.connection.execute_batch("BEGIN")?;
+        }
+        self.pending += 1;
+        self.connection
+            .prepare_cached("INSERT INTO records (captured_at, timestamp, level, service, message, file, raw) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)")?
+            .execute(rusqlite::params![
+                record.captured_at, record.timestamp, record.level, record.service, record.message, record.file, record.raw
+            ])?;
+        if self.pending >= BATCH {
+            self.flush();
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    fn write(&mut self, _record: &Record) -> Result<()> {
+        unreachable!("SqliteSink::open fails without the sqlite feature")
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn commit(&mut self) -> Result<()> {
+        Ok(self.connection.execute_batch("COMMIT")?)
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    fn commit(&mut self) -> Result<()> {
+        unreachable!("SqliteSink::open fails without the sqlite feature")
+    }
+}
+
+#[cfg_attr(not(feature = "sqlite"), allow(dead_code))]
+struct Record {
+    captured_at: String,
+    timestamp: Option<String>,
+    level: Option<String>,
+    service: Option<String>,
+    message: Option<String>,
+    file: String,
+    raw: String,
+}
@@ -0,0 +1,78 @@
+use std::time::{Duration, Instant};
+
+use crate::parser::GenericParser;
+
+/// How long a run of repeats may sit idle in follow mode before it is emitted anyway.
+pub const REPEAT_FLUSH: Duration = Duration::from_secs(1);
+
+/// Collapses runs of identical lines (ignoring their timestamps) into the first
+/// line of the run annotated with `(repeated N×)`, the way syslog does.
+#[derive(Clone)]
+pub struct RepeatSquasher {
+    parser: GenericParser,
+    pending: Option<Run>,
+    last_push: Instant,
+}
+
+#[derive(Clone)]
+struct Run {
+    line: String,
+    key: String,
+    count: usize,
+}
+
+impl Run {
+    fn into_line(self) -> String {
+        if self.count > 1 {
+            format!("{} (repeated {}×)", self.line, self.count)
+        } else {
+            self.line
+        }
+    }
+}
+
+impl RepeatSquasher {
+    pub fn new() -> Self {
+        Self {
+            parser: GenericParser::new(),
+            pending: None,
+            last_push: Instant::now(),
+        }
+    }
+
+    /// Add a line. Returns the previous run once this line differs from it.
+    pub fn push(&mut self, line: String) -> Option<String> {
+        self.last_push = Instant::now();
+        let key = self.parser.strip_timestamp(&line);
+        match self.pending.as_mut() {
+            Some(run) if run.key == key => {
+                run.count += 1;
+                None
+            }
+            _ => self.pending
+                .replace(Run { line, key, count: 1 })
+                .map(Run::into_line),
+        }
+    }
+
+    /// Emit the buffered run, if any.
+    pub fn flush(&mut self) -> Option<String> {
+        self.pending.take().map(Run::into_line)
+    }
+
+    /// Emit the buffered run if nothing has arrived for `REPEAT_FLUSH`.
+    pub fn flush_if_idle(&mut self) -> Option<String> {
+        if self.last_push.elapsed() >= REPEAT_FLUSH {
+            self.flush()
+        } else {
+            None
+        }
+    }
+
+    /// Squash a complete batch of lines.
+    pub fn squash_all<I: IntoIterator<Item = String>>(&mut self, lines: I) -> Vec<String> {
+        let mut squashed: Vec<String> = lines.into_iter().filter_map(|line| self.push(line)).collect();
+        squashed.extend(self.flush());
+        squashed
+    }
+}
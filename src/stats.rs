@@ -0,0 +1,129 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::duration::format_duration;
+use crate::filter::LogLevel;
+
+/// Services listed in the summary; the rest are added up on one line
+const TOP_SERVICES: usize = 10;
+
+/// `--stats`: counts of the lines ft looked at, by level, service and file, printed as a
+/// summary table on exit and, with an interval, every so often while following.
+pub struct Stats {
+    started: Instant,
+    interval: Option<Duration>,
+    last_report: Instant,
+    lines: usize,
+    matched: usize,
+    levels: BTreeMap<u8, (LogLevel, usize)>,
+    services: HashMap<String, usize>,
+    /// Lines read and matched per source, in the order sources were first seen
+    files: Vec<(PathBuf, usize, usize)>,
+    /// Timestamps that jumped backwards, counted with `--order-guard`
+    out_of_order: Option<usize>,
+}
+
+impl Stats {
+    /// `interval` of zero reports on exit only.
+    pub fn new(interval: Duration) -> Self {
+        let now = Instant::now();
+        Self {
+            started: now,
+            interval: (!interval.is_zero()).then_some(interval),
+            last_report: now,
+            lines: 0,
+            matched: 0,
+            levels: BTreeMap::new(),
+            services: HashMap::new(),
+            files: Vec::new(),
+            out_of_order: None,
+        }
+    }
+
+    /// Also count lines whose timestamp jumped backwards, as `--order-guard` finds them.
+    pub fn with_order_guard(mut self) -> Self {
+        self.out_of_order = Some(0);
+        self
+    }
+
+    pub fn observe_out_of_order(&mut self) {
+        if let Some(count) = self.out_of_order.as_mut() {
+            *count += 1;
+        }
+    }
+
+    /// Count a line read from `path`, whether it passed the filters, and its level and service.
+    pub fn observe(&mut self, path: &Path, level: Option<LogLevel>, service: Option<&str>, matched: bool) {
+        self.lines += 1;
+        self.matched += usize::from(matched);
+        if let Some(level) = level {
+            self.levels.entry(level.priority()).or_insert((level, 0)).1 += 1;
+        }
+        if let Some(service) = service.filter(|s| !s.is_empty()) {
+            *self.services.entry(service.to_string()).or_default() += 1;
+        }
+        let file = match self.files.iter().position(|(p, _, _)| p == path) {
+            Some(i) => &mut self.files[i],
+            None => {
+                self.files.push((path.to_path_buf(), 0, 0));
+                self.files.last_mut().unwrap()
+            }
+        };
+        file.1 += 1;
+        file.2 += usize::from(matched);
+    }
+
+    /// The summary, when the `--stats` interval has passed since the last one.
+    pub fn due_report(&mut self) -> Option<String> {
+        let interval = self.interval?;
+        if self.last_report.elapsed() < interval {
+            return None;
+        }
+        self.last_report = Instant::now();
+        Some(self.report())
+    }
+
+    pub fn report(&self) -> String {
+        let elapsed = self.started.elapsed();
+        let rate = self.lines as f64 / elapsed.as_secs_f64().max(0.001);
+        let percent = |n: usize| if self.lines == 0 { 0.0 } else { n as f64 * 100.0 / self.lines as f64 };
+        let mut out = vec![format!(
+            "ft stats: {} lines in {} ({:.1}/s), {} matched ({:.1}%)",
+            self.lines, format_duration(elapsed), rate, self.matched, percent(self.matched)
+        )];
+        if let Some(count) = self.out_of_order {
+            out[0].push_str(&format!(", {} out of order", count));
+        }
+
+        if !self.levels.is_empty() {
+            out.push(format!("  {:<12} {:>10} {:>7}", "LEVEL", "LINES", "%"));
+            for (level, count) in self.levels.values() {
+                out.push(format!("  {:<12} {:>10} {:>6.1}%", format!("{:?}", level).to_uppercase(), count, percent(*count)));
+            }
+        }
+
+        if !self.services.is_empty() {
+            let mut services: Vec<(&String, &usize)> = self.services.iter().collect();
+            services.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+            out.push(format!("  {:<24} {:>10} {:>7}", "SERVICE", "LINES", "%"));
+            for (service, count) in services.iter().take(TOP_SERVICES) {
+                out.push(format!("  {:<24} {:>10} {:>6.1}%", service, count, percent(**count)));
+            }
+            if services.len() > TOP_SERVICES {
+                let rest: usize = services[TOP_SERVICES..].iter().map(|(_, count)| **count).sum();
+                out.push(format!("  {:<24} {:>10} {:>6.1}%", format!("({} more)", services.len() - TOP_SERVICES), rest, percent(rest)));
+            }
+        }
+
+        if !self.files.is_empty() {
+            out.push(format!("  {:<24} {:>10} {:>10} {:>7}", "FILE", "LINES", "MATCHED", "RATE"));
+            for (path, lines, matched) in &self.files {
+                let name = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned());
+                let rate = if *lines == 0 { 0.0 } else { *matched as f64 * 100.0 / *lines as f64 };
+                out.push(format!("  {:<24} {:>10} {:>10} {:>6.1}%", name, lines, matched, rate));
+            }
+        }
+        out.join("\n")
+    }
+}
@@ -0,0 +1,78 @@
+use crate::width::{truncate, visible_width};
+
+/// One piece of a status bar. When the bar is too narrow, segments with the
+/// highest `priority` are dropped first; priority 0 is never dropped, only shortened.
+struct Segment {
+    text: String,
+    priority: u8,
+    right: bool,
+}
+
+/// A one-row bar with left- and right-aligned segments, laid out to an exact width.
+#[derive(Default)]
+pub struct StatusBar {
+    segments: Vec<Segment>,
+}
+
+impl StatusBar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn left(mut self, text: impl Into<String>, priority: u8) -> Self {
+        self.segments.push(Segment { text: text.into(), priority, right: false });
+        self
+    }
+
+    pub fn right(mut self, text: impl Into<String>, priority: u8) -> Self {
+        self.segments.push(Segment { text: text.into(), priority, right: true });
+        self
+    }
+
+    /// Render to exactly `width` columns, dropping optional segments (ties: the
+    /// last added goes first) and then truncating the left side if still too wide.
+    pub fn render(&self, width: usize) -> String {
+        let mut kept: Vec<&Segment> = self.segments.iter().collect();
+        loop {
+            let (left, right) = join(&kept);
+            if visible_width(&left) + visible_width(&right) <= width {
+                return layout(&left, &right, width);
+            }
+            let droppable = kept.iter()
+                .enumerate()
+                .filter(|(_, s)| s.priority > 0)
+                .max_by_key(|(i, s)| (s.priority, *i))
+                .map(|(i, _)| i);
+            match droppable {
+                Some(i) => {
+                    kept.remove(i);
+                }
+                None => break,
+            }
+        }
+
+        // Only essential segments left: the right side wins, the left is shortened
+        let (left, right) = join(&kept);
+        let right = truncate(&right, width);
+        let left = truncate(&left, width - visible_width(&right));
+        layout(&left, &right, width)
+    }
+}
+
+fn join(segments: &[&Segment]) -> (String, String) {
+    let mut left = String::new();
+    let mut right = String::new();
+    for segment in segments {
+        if segment.right {
+            right.push_str(&segment.text);
+        } else {
+            left.push_str(&segment.text);
+        }
+    }
+    (left, right)
+}
+
+fn layout(left: &str, right: &str, width: usize) -> String {
+    let gap = width.saturating_sub(visible_width(left) + visible_width(right));
+    format!("{}{}{}", left, " ".repeat(gap), right)
+}
@@ -0,0 +1,218 @@
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::colorizer::Colorizer;
+use crate::config::Config;
+use crate::theme::{xterm_to_rgb, Theme};
+use crate::width::char_width;
+
+const FONT_SIZE: f32 = 14.0;
+const CHAR_WIDTH: f32 = FONT_SIZE * 0.6;
+const LINE_HEIGHT: f32 = FONT_SIZE * 1.4;
+const PADDING: f32 = 16.0;
+pub(crate) const BACKGROUND: &str = "#1a1b26";
+pub(crate) const FOREGROUND: &str = "#c0caf5";
+
+type Rgb = (u8, u8, u8);
+
+/// A run of text sharing one style, as decoded from ANSI SGR sequences.
+pub(crate) struct Span {
+    pub(crate) text: String,
+    fg: Option<Rgb>,
+    bg: Option<Rgb>,
+    pub(crate) bold: bool,
+    pub(crate) italic: bool,
+    pub(crate) underline: bool,
+    reverse: bool,
+}
+
+impl Span {
+    /// Colors as drawn: reverse video swaps foreground and background.
+    pub(crate) fn colors(&self) -> (Option<Rgb>, Option<Rgb>) {
+        if self.reverse {
+            (Some(self.bg.unwrap_or_else(|| parse_hex(BACKGROUND))), Some(self.fg.unwrap_or_else(|| parse_hex(FOREGROUND))))
+        } else {
+            (self.fg, self.bg)
+        }
+    }
+}
+
+/// `ft themes render --svg`: colorize the last `lines` lines of `file` (or a built-in
+/// sample log) with a theme and write the result to `out` as SVG.
+pub fn snapshot(config: &Config, theme_name: Option<&str>, file: Option<&Path>, lines: usize, out: &Path) -> Result<()> {
+    let theme_name = theme_name.unwrap_or(&config.general.theme);
+    let colorizer = Colorizer::new(Theme::resolve(config, theme_name)?, false);
+
+    let text = match file {
+        Some(path) => std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read file: {:?}", path))?,
+        None => crate::examples::sample_log(),
+    };
+    let all: Vec<&str> = text.lines().collect();
+    let colored: Vec<String> = all[all.len().saturating_sub(lines)..].iter()
+        .map(|line| colorizer.colorize_line(line))
+        .collect();
+
+    let title = match file {
+        Some(path) => format!("ft {} ({})", path.display(), theme_name),
+        None => format!("ft sample.log ({})", theme_name),
+    };
+    std::fs::write(out, render(&colored, &title))
+        .with_context(|| format!("Failed to write {:?}", out))?;
+    Ok(())
+}
+
+/// Render ANSI-colored lines as a standalone SVG "terminal screenshot".
+pub fn render(lines: &[String], title: &str) -> String {
+    let parsed: Vec<Vec<Span>> = lines.iter().map(|line| parse_ansi(line)).collect();
+    let columns = parsed.iter()
+        .map(|spans| spans.iter().map(|s| s.text.chars().map(char_width).sum::<usize>()).sum::<usize>())
+        .max()
+        .unwrap_or(0)
+        .max(40);
+    let width = PADDING * 2.0 + columns as f32 * CHAR_WIDTH;
+    let height = PADDING * 2.0 + LINE_HEIGHT * (lines.len() as f32 + 1.0);
+
+    let mut svg = String::new();
+    let _ = writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{:.0}" height="{:.0}" viewBox="0 0 {:.0} {:.0}">"#, width, height, width, height);
+    let _ = writeln!(svg, "<title>{}</title>", escape(title));
+    let _ = writeln!(svg, r#"<rect width="100%" height="100%" rx="8" fill="{}"/>"#, BACKGROUND);
+    let _ = writeln!(svg, r#"<g font-family="ui-monospace, 'SFMono-Regular', Menlo, Consolas, monospace" font-size="{}" xml:space="preserve">"#, FONT_SIZE);
+    let _ = writeln!(svg, r#"<text x="{}" y="{:.1}" fill="{}" opacity="0.6">{}</text>"#,
+        PADDING, PADDING + FONT_SIZE, FOREGROUND, escape(title));
+
+    for (row, spans) in parsed.iter().enumerate() {
+        let baseline = PADDING + FONT_SIZE + LINE_HEIGHT * (row as f32 + 1.0);
+        let mut column = 0usize;
+        // Backgrounds first so text is drawn over them
+        for span in spans {
+            let cols: usize = span.text.chars().map(char_width).sum();
+            if let (_, Some(bg)) = span.colors() {
+                let _ = writeln!(svg, r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"/>"#,
+                    PADDING + column as f32 * CHAR_WIDTH, baseline - FONT_SIZE, cols as f32 * CHAR_WIDTH, LINE_HEIGHT, hex(bg));
+            }
+            column += cols;
+        }
+
+        let _ = write!(svg, r#"<text x="{}" y="{:.1}">"#, PADDING, baseline);
+        for span in spans {
+            let fill = span.colors().0.map(hex).unwrap_or_else(|| FOREGROUND.to_string());
+            let weight = if span.bold { r#" font-weight="bold""# } else { "" };
+            let style = if span.italic { r#" font-style="italic""# } else { "" };
+            let decoration = if span.underline { r#" text-decoration="underline""# } else { "" };
+            let _ = write!(svg, r#"<tspan fill="{}"{}{}{}>{}</tspan>"#, fill, weight, style, decoration, escape(&span.text));
+        }
+        svg.push_str("</text>\n");
+    }
+
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+pub(crate) fn parse_ansi(line: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    let mut fg = None;
+    let mut bg = None;
+    let mut bold = false;
+    let mut italic = false;
+    let mut underline = false;
+    let mut reverse = false;
+    let mut text = String::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\x1b' {
+            match ch {
+                '\t' => text.push_str("    "),
+                c if c.is_control() => {}
+                c => text.push(c),
+            }
+            continue;
+        }
+        if chars.peek() != Some(&'[') {
+            continue;
+        }
+        chars.next();
+        let mut params = String::new();
+        let mut command = None;
+        for c in chars.by_ref() {
+            if c.is_ascii_alphabetic() {
+                command = Some(c);
+                break;
+            }
+            params.push(c);
+        }
+        if command != Some('m') {
+            continue;
+        }
+
+        if !text.is_empty() {
+            spans.push(Span { text: std::mem::take(&mut text), fg, bg, bold, italic, underline, reverse });
+        }
+        let codes: Vec<u16> = params.split(';').map(|p| p.parse().unwrap_or(0)).collect();
+        let mut i = 0;
+        while i < codes.len() {
+            match codes[i] {
+                0 => {
+                    fg = None;
+                    bg = None;
+                    bold = false;
+                    italic = false;
+                    underline = false;
+                    reverse = false;
+                }
+                1 => bold = true,
+                3 => italic = true,
+                4 => underline = true,
+                7 => reverse = true,
+                22 => bold = false,
+                23 => italic = false,
+                24 => underline = false,
+                27 => reverse = false,
+                39 => fg = None,
+                49 => bg = None,
+                code @ 30..=37 => fg = Some(xterm_to_rgb((code - 30) as u8)),
+                code @ 90..=97 => fg = Some(xterm_to_rgb((code - 90 + 8) as u8)),
+                code @ 40..=47 => bg = Some(xterm_to_rgb((code - 40) as u8)),
+                code @ 100..=107 => bg = Some(xterm_to_rgb((code - 100 + 8) as u8)),
+                code @ (38 | 48) => {
+                    let color = match codes.get(i + 1) {
+                        Some(5) => {
+                            let c = codes.get(i + 2).copied().unwrap_or(0);
+                            i += 2;
+                            Some(xterm_to_rgb(c as u8))
+                        }
+                        Some(2) => {
+                            let get = |k: usize| codes.get(i + k).copied().unwrap_or(0) as u8;
+                            let rgb = (get(2), get(3), get(4));
+                            i += 4;
+                            Some(rgb)
+                        }
+                        _ => None,
+                    };
+                    if code == 38 { fg = color } else { bg = color }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+    if !text.is_empty() {
+        spans.push(Span { text, fg, bg, bold, italic, underline, reverse });
+    }
+    spans
+}
+
+pub(crate) fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn parse_hex(color: &str) -> Rgb {
+    let channel = |i: usize| u8::from_str_radix(&color[i..i + 2], 16).unwrap_or(0);
+    (channel(1), channel(3), channel(5))
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
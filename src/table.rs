@@ -0,0 +1,102 @@
+use serde_json::Value;
+
+use crate::envelope::Position;
+use crate::filter::detect_log_level;
+use crate::width::{truncate, visible_width};
+
+/// The columns before the message, and the widest each gets however long a value is
+const COLUMNS: [(&str, usize); 3] = [("TIMESTAMP", 32), ("LEVEL", 9), ("SERVICE", 24)];
+const SEPARATOR: &str = "  ";
+/// Narrower than this, the message is left to wrap rather than cut
+const MIN_MESSAGE: usize = 20;
+
+/// `--format table`: lines as rows of timestamp, level, service and message columns.
+/// Columns fit the first row and widen, up to a limit, for wider values after it; on a
+/// terminal the message is cut to what's left of its width.
+pub struct TableLayout {
+    /// `line_number` and `byte_offset` columns, first, under `--line-numbers` and `--byte-offsets`
+    positions: Vec<&'static str>,
+    columns: Vec<(&'static str, usize)>,
+    widths: Vec<usize>,
+}
+
+impl TableLayout {
+    pub fn new(positions: &[&'static str]) -> Self {
+        let columns: Vec<(&str, usize)> = positions.iter()
+            .map(|&name| if name == "line_number" { ("LINE", 10) } else { ("OFFSET", 14) })
+            .chain(COLUMNS)
+            .collect();
+        Self { positions: positions.to_vec(), widths: vec![0; columns.len()], columns }
+    }
+
+    /// The heading row, laid out for the rows so far.
+    pub fn header(&self) -> String {
+        self.render(self.columns.iter().map(|(heading, _)| heading.to_string()).collect(), "MESSAGE")
+    }
+
+    /// A row for `line`, from the fields its parser found in it.
+    pub fn row(&mut self, fields: &Value, line: &str, position: Option<Position>) -> String {
+        let text = |name: &str| fields.get(name).and_then(Value::as_str).filter(|s| !s.is_empty()).map(str::to_string);
+        let timestamp = text("timestamp").unwrap_or_default();
+        let level = text("level")
+            .map(|level| level.to_uppercase())
+            .or_else(|| detect_log_level(line).map(|level| format!("{:?}", level).to_uppercase()))
+            .unwrap_or_default();
+        let service = text("service").unwrap_or_default();
+        let message = text("message").unwrap_or_else(|| line.to_string());
+        let message = without_prefix(&message, &timestamp, &level);
+
+        let cells: Vec<String> = self.positions.iter()
+            .map(|&name| position.map(|p| if name == "line_number" { p.line } else { p.offset }).map_or_else(String::new, |n| n.to_string()))
+            .chain([timestamp, level, service])
+            .collect();
+        for ((cell, width), (heading, max)) in cells.iter().zip(&mut self.widths).zip(&self.columns) {
+            *width = (*width).max(heading.len()).max(visible_width(cell).min(*max));
+        }
+        self.render(cells, message)
+    }
+
+    fn render(&self, cells: Vec<String>, message: &str) -> String {
+        let mut row = String::new();
+        for (cell, &width) in cells.iter().zip(&self.widths) {
+            let cell = truncate(cell, width);
+            row.push_str(&cell);
+            row.push_str(&" ".repeat(width - visible_width(&cell)));
+            row.push_str(SEPARATOR);
+        }
+        match terminal_width().map(|width| width.saturating_sub(visible_width(&row))) {
+            Some(room) if room >= MIN_MESSAGE => row.push_str(&truncate(message, room)),
+            _ => row.push_str(message),
+        }
+        row
+    }
+}
+
+/// Lines without a parsed message carry their timestamp and level in it too; they already
+/// have columns of their own.
+fn without_prefix<'a>(message: &'a str, timestamp: &str, level: &str) -> &'a str {
+    let mut rest = message;
+    for prefix in [timestamp, level] {
+        let word = rest.trim_start().trim_start_matches('[');
+        if prefix.is_empty() || word.len() < prefix.len() || !word.is_char_boundary(prefix.len())
+            || !word[..prefix.len()].eq_ignore_ascii_case(prefix) {
+            continue;
+        }
+        let after = word[prefix.len()..].trim_start_matches([']', ':']);
+        if after.is_empty() || after.starts_with(char::is_whitespace) {
+            rest = after;
+        }
+    }
+    match rest.trim_start() {
+        "" => message,
+        rest => rest,
+    }
+}
+
+fn terminal_width() -> Option<usize> {
+    use is_terminal::IsTerminal;
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    crossterm::terminal::size().ok().map(|(width, _)| usize::from(width)).filter(|&width| width > 0)
+}
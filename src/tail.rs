@@ -1,20 +1,56 @@
 use crate::config::Config;
-use crate::theme::Theme;
-use crate::colorizer::Colorizer;
-use crate::filter::LineFilter;
-use crate::output::{OutputFormat, OutputFormatter};
+use crate::theme::{ColorDepth, ColorRule, Theme, HashFieldRule};
+use crate::alert::Alerts;
+use crate::ansi;
+use crate::bell::Bell;
+use crate::rate::RateMeter;
+use crate::stats::Stats;
+use crate::notification::Notifier;
+use crate::colorizer::{overlay_matches, overlay_ranges, Colorizer};
+use crate::control::ControlServer;
+use crate::duration::format_duration;
+use crate::delta::DeltaClock;
+use crate::escalation::EscalationTracker;
+use crate::filter::{detect_log_level, FilterOptions, LineFilter, LogLevel};
+use crate::focus::FocusFilter;
+use crate::forward::Forwarder;
+use crate::hooks::Hooks;
+use crate::envelope::{Envelope, Position, Positions};
+use crate::hyperlink::Hyperlinker;
+use crate::interactive::{spawn_follower, FilterSource, FollowSource, InteractiveMode};
+use crate::jsonview::JsonLayout;
+use crate::lanes::LaneAssigner;
+use crate::output::{parse_fields, OutputFormat, OutputFormatter, OutputSchema};
+use crate::parser::{cri_payload, glob_match, GenericParser, ParserRegistry};
+use crate::project::Projection;
+use crate::redact::Redactor;
+use crate::reload::ThemeWatcher;
+use crate::replay::Replay;
+use crate::merge::SkewMerger;
+use crate::metrics::Metrics;
+use crate::multiline::{MultilineGrouper, IDLE_FLUSH};
+use crate::order::OrderGuard;
+use crate::sink::{FlushPolicy, Sink, Tee};
+use crate::sqlite::SqliteSink;
+use crate::squash::RepeatSquasher;
+use crate::statusbar::StatusBar;
+use crate::throttle::{Rate, Throttle};
+use crate::timezone::DisplayZone;
+use crate::top::TopCounter;
+use crate::width::pad_ansi;
 use anyhow::{Context, Result, anyhow};
 use std::fs::File;
 use std::io::{BufRead, BufReader, Write as IoWrite, stdin, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
-use std::collections::VecDeque;
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
 use notify::{Watcher, RecommendedWatcher, RecursiveMode, Config as NotifyConfig};
 use std::sync::{mpsc, Arc, atomic::{AtomicBool, Ordering}};
 use std::time::Duration;
 use std::thread;
 use crossterm::{
     cursor::{Hide, Show, MoveTo},
-    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor},
     terminal::{size, EnterAlternateScreen, LeaveAlternateScreen,
                BeginSynchronizedUpdate, EndSynchronizedUpdate},
     event::{poll, read, Event, KeyCode, KeyModifiers, KeyEventKind},
@@ -24,110 +60,1018 @@ use std::io;
 
 struct FileTracker {
     path: PathBuf,
-    file: File,
+    /// None for a stdin source, which is read through `stdin` instead
+    file: Option<File>,
     position: u64,
     lines: VecDeque<String>,
     raw_lines: VecDeque<String>,
     max_lines: usize,
+    /// Scrollback budget from --max-memory, split between colored and raw lines
+    max_bytes: usize,
+    buffered_bytes: usize,
     line_count: usize,
     last_update: std::time::SystemTime,
     file_id: Option<(u64, u64)>,
     paused: bool,
     filter: Option<LineFilter>,
     search_term: Option<String>,
+    grouper: Option<MultilineGrouper>,
+    stats: SourceStats,
+    stdin: Option<StdinSource>,
+    squasher: Option<RepeatSquasher>,
+    throttle: Option<Throttle>,
+}
+
+impl FileTracker {
+    /// Append rendered rows and their raw text, then drop the oldest until the
+    /// scrollback fits both --buffer-lines and --max-memory.
+    fn push_rows<I, J>(&mut self, rows: I, raw: J)
+    where
+        I: IntoIterator<Item = String>,
+        J: IntoIterator<Item = String>,
+    {
+        for row in rows {
+            self.buffered_bytes += row.len();
+            self.lines.push_back(row);
+        }
+        for row in raw {
+            self.buffered_bytes += row.len();
+            self.raw_lines.push_back(row);
+        }
+        while self.lines.len() > self.max_lines || (self.buffered_bytes > self.max_bytes && self.lines.len() > 1) {
+            let Some(row) = self.lines.pop_front() else { break };
+            self.buffered_bytes -= row.len();
+            // Keep raw lines in step so search highlighting still lines up
+            if self.raw_lines.len() > self.lines.len() {
+                if let Some(raw) = self.raw_lines.pop_front() {
+                    self.buffered_bytes -= raw.len();
+                }
+            }
+        }
+        while self.raw_lines.len() > self.max_lines {
+            if let Some(raw) = self.raw_lines.pop_front() {
+                self.buffered_bytes -= raw.len();
+            }
+        }
+    }
+
+    fn clear_buffer(&mut self) {
+        self.lines.clear();
+        self.raw_lines.clear();
+        self.buffered_bytes = 0;
+    }
+}
+
+/// Stdin taking part in a multi-source session (`-` as a file argument). Lines
+/// are read on a background thread since stdin cannot be polled like a file.
+struct StdinSource {
+    lines: mpsc::Receiver<String>,
+    closed: bool,
+}
+
+/// What a source produced since it was last read.
+#[derive(Default)]
+struct SourceUpdate {
+    lines: Vec<String>,
+    rotated: bool,
+    truncated: bool,
+}
+
+/// Per-source counters for the statistics popup, covering lines read since the source was opened.
+struct SourceStats {
+    opened: std::time::Instant,
+    lines_read: usize,
+    lines_shown: usize,
+    errors: usize,
+    last_line: Option<std::time::Instant>,
+}
+
+impl SourceStats {
+    fn new() -> Self {
+        Self {
+            opened: std::time::Instant::now(),
+            lines_read: 0,
+            lines_shown: 0,
+            errors: 0,
+            last_line: None,
+        }
+    }
+
+    fn observe(&mut self, line: &str) {
+        self.lines_read += 1;
+        self.last_line = Some(std::time::Instant::now());
+        if detect_log_level(line).is_some_and(|level| level.priority() <= LogLevel::Error.priority()) {
+            self.errors += 1;
+        }
+    }
+
+    /// Lines read per minute since the source was opened.
+    fn growth_rate(&self) -> f64 {
+        let minutes = self.opened.elapsed().as_secs_f64() / 60.0;
+        if minutes > 0.0 { self.lines_read as f64 / minutes } else { 0.0 }
+    }
+}
+
+/// Command-line settings that shape how a `TailProcessor` reads, filters and renders lines.
+pub struct TailOptions {
+    pub no_color: bool,
+    pub filter: FilterOptions,
+    pub interactive: bool,
+    pub format: String,
+    pub buffer_size: usize,
+    pub quiet: bool,
+    pub verbose: bool,
+    pub max_buffer_lines: usize,
+    /// `--scrollback SIZE`: bytes of scrollback per window and in `-i`
+    pub scrollback_bytes: Option<usize>,
+    pub hash_fields: Vec<String>,
+    /// `--json-view`: lay out and color JSON lines by structure instead of theme rules
+    pub json_view: Option<JsonLayout>,
+    /// `--logfmt-colors`: color `key=value` pairs on top of the theme
+    pub logfmt_colors: bool,
+    /// `--emphasize`: the level from which whole lines are emphasized
+    pub emphasize: Option<LogLevel>,
+    /// `--jq`/`--fields`: show only these fields of structured lines
+    pub projection: Option<Projection>,
+    /// `--age-colors`: color each line's timestamp by how long ago it was
+    pub age_colors: bool,
+    /// `--utc`, `--local` or `--tz`
+    pub zone: Option<DisplayZone>,
+    pub relative: bool,
+    pub line_numbers: bool,
+    /// `--replay` at this `--speed`
+    pub replay: Option<f64>,
+    pub byte_offsets: bool,
+    /// `--hyperlinks`: make URLs and file paths clickable (OSC 8) in scrolling output
+    pub hyperlinks: bool,
+    pub lanes: Option<String>,
+    pub max_skew: Option<Duration>,
+    pub order_guard: bool,
+    pub delta: bool,
+    pub escalate: bool,
+    pub multiline: bool,
+    pub multiline_start: Option<String>,
+    pub focus: Option<String>,
+    pub stdin_label: Option<String>,
+    pub squash_repeats: bool,
+    pub merge: bool,
+    pub sample: Option<usize>,
+    pub max_rate: Option<Rate>,
+    pub max_count: Option<usize>,
+    pub presets: Vec<String>,
+    pub max_memory: Option<usize>,
+    pub highlights: Vec<String>,
+    pub handoff_less: bool,
+    pub schema: Option<String>,
+    pub csv_columns: Vec<String>,
+    pub json_colored: bool,
+    pub input_format: Option<String>,
+    pub flush: FlushPolicy,
+    pub output_file: Option<PathBuf>,
+    pub output_plain: bool,
+    pub forward: Option<String>,
+    pub sqlite: Option<PathBuf>,
+    pub redact: bool,
+    pub anonymize_ips: Option<usize>,
+    pub strip_ansi: bool,
+    pub alerts: Vec<String>,
+    pub notify_level: Option<String>,
+    pub bell: Vec<String>,
+    pub stats: Option<Duration>,
+    pub rate: Option<Duration>,
+    pub metrics_listen: Option<String>,
+    pub top: Option<String>,
+    pub count: bool,
+    pub control: Option<PathBuf>,
 }
 
 pub struct TailProcessor {
     colorizer: Colorizer,
+    /// `[profile."GLOB"]` themes, used instead of `colorizer` for matching sources
+    profiles: Vec<Profile>,
+    /// Which profile each source resolved to, so globs are matched once per source
+    profile_of: HashMap<PathBuf, Option<usize>>,
     config: Config,
     filter: LineFilter,
     interactive: bool,
+    /// What `filter` was built from, for `-i` to rebuild it with edited patterns
+    filter_source: Option<FilterSource>,
     output_formatter: OutputFormatter,
     buffer_size: usize,
-    bytes_mode: Option<usize>,
     quiet: bool,
     verbose: bool,
     max_buffer_lines: usize,
+    scrollback_limit: Option<usize>,
+    lanes: Option<LaneAssigner>,
+    log_parser: GenericParser,
+    max_skew: Option<Duration>,
+    order_guard: Option<OrderGuard>,
+    delta: Option<DeltaClock>,
+    escalation: Option<EscalationTracker>,
+    multiline: Option<MultilineGrouper>,
+    focus: Option<FocusFilter>,
+    stdin_label: Option<String>,
+    squash: Option<RepeatSquasher>,
+    merge: bool,
+    throttle: Option<Throttle>,
+    max_count: Option<usize>,
+    /// Lines printed so far, counted against `max_count`
+    printed: usize,
+    /// Whether scroll-mode lines carry a `[name]` prefix (more than one source)
+    prefix_sources: bool,
+    /// `--max-memory` in bytes, shared by file reads and every window's scrollback
+    max_memory: Option<usize>,
+    /// Number of sources being tailed, which split the scrollback budget
+    sources: usize,
+    /// `--handoff-less`: output collected here and paged at the end instead of printed
+    paged: Option<Vec<String>>,
+    /// `[hooks]` commands run on start, rotation, exit and alerting lines
+    hooks: Option<Hooks>,
+    /// Where printed lines go, flushed per `--flush`
+    output: Sink<io::Stdout>,
+    /// `--output-file`: where what's shown is also written
+    tee: Option<Tee>,
+    /// `--rate`: lines and errors per second, as sparklines
+    rate: Option<RateMeter>,
+    /// `--top`: counts of a field's values, shown in place of the lines
+    top: Option<TopCounter>,
+    /// `--count`: lines that passed the filters per source, printed instead of them
+    counts: Option<Vec<(PathBuf, usize)>>,
+    /// `--metrics-listen`: counters served to Prometheus
+    metrics: Option<Metrics>,
+    /// `--stats`: counts of lines looked at, reported on stderr
+    stats: Option<Stats>,
+    /// `--bell`: rings for matching lines read while following
+    bell: Option<Bell>,
+    /// `--notify-level`: desktop notifications for severe lines read while following
+    notifier: Option<Notifier>,
+    /// `--alert` and `[[alert]]` rules checked against lines read while following
+    alerts: Option<Alerts>,
+    /// `--forward`: where lines that are shown are also sent as syslog messages
+    forwarder: Option<Forwarder>,
+    /// `--redact` and `--anonymize-ips`: hide secrets and addresses in lines as they're read
+    redactor: Option<Redactor>,
+    strip_ansi: bool,
+    /// `--sqlite`: database lines shown are also inserted into
+    sqlite: Option<SqliteSink>,
+    /// `--control` socket that `ft ctl set-filter` swaps the filter through
+    control: Option<ControlServer>,
+    /// Watches the theme file and SIGHUP while following, to reload the theme
+    theme_watcher: Option<ThemeWatcher>,
+    /// `--hash-field` names, added to the theme again when it's reloaded
+    hash_fields: Vec<String>,
+    json_view: Option<JsonLayout>,
+    hyperlinker: Option<Hyperlinker>,
+    /// `--format ndjson`: tracks where lines were read so output can say
+    envelope: Option<Envelope>,
+    positions: Option<Positions>,
+    line_numbers: bool,
+    byte_offsets: bool,
+    replay: Option<Replay>,
+    projection: Option<Projection>,
+    age_colors: bool,
+    zone: Option<DisplayZone>,
+    relative: bool,
+    color_depth: ColorDepth,
+    /// A message shown in the status bars for a while, and when it was set
+    notice: Option<(String, std::time::Instant)>,
+}
+
+/// How long a notice stays in the status bars.
+const NOTICE_DURATION: Duration = Duration::from_secs(5);
+
+/// A `[profile."GLOB"]`: sources matching `glob` are colored with their own theme.
+struct Profile {
+    glob: String,
+    theme: String,
+    colorizer: Colorizer,
+}
+
+/// Theme `name` with `--hash-field`s added, in colors the terminal can show.
+fn load_theme(config: &Config, name: &str, hash_fields: &[String], depth: ColorDepth) -> Result<Theme> {
+    let mut theme = Theme::resolve(config, name)?;
+    for field in hash_fields {
+        theme.hash_fields.push(HashFieldRule::new(field)?);
+    }
+    theme.downgrade(depth);
+    Ok(theme)
 }
 
 impl TailProcessor {
-    pub fn new(
-        config: Config,
-        no_color: bool,
-        include: Option<String>,
-        exclude: Option<String>,
-        level: Option<String>,
-        interactive: bool,
-        format: String,
-        buffer_size: usize,
-        bytes_mode: Option<usize>,
-        quiet: bool,
-        verbose: bool,
-        max_buffer_lines: usize,
-    ) -> Result<Self> {
-        let theme_name = &config.general.theme;
-        let theme = if let Some(theme_path) = config.get_theme_path(theme_name) {
-            Theme::load_from_file(&theme_path, theme_name.clone())
-                .with_context(|| format!("Failed to load theme from {:?}", theme_path))?
-        } else if let Some(builtin) = Theme::load_builtin(theme_name) {
-            builtin?
+    pub fn new(config: Config, options: TailOptions) -> Result<Self> {
+        let depth = ColorDepth::detect();
+        let theme = load_theme(&config, &config.general.theme, &options.hash_fields, depth)?;
+
+        let escalation = options.escalate.then(|| {
+            EscalationTracker::new(theme.escalation_window, theme.escalation_rules.clone())
+        });
+        let highlights = options.highlights.iter()
+            .map(|spec| ColorRule::from_spec(spec))
+            .collect::<Result<Vec<_>>>()?;
+        let new_colorizer = |theme| {
+            let mut colorizer = Colorizer::new(theme, options.no_color)
+                .with_color_depth(depth);
+            if options.logfmt_colors {
+                colorizer = colorizer.with_logfmt();
+            }
+            if let Some(level) = options.emphasize {
+                colorizer = colorizer.with_emphasis(level);
+            }
+            for rule in &highlights {
+                colorizer.add_highlight(rule.clone());
+            }
+            colorizer
+        };
+        let colorizer = new_colorizer(theme);
+        let profiles = config.profiles.iter()
+            .map(|(glob, profile)| {
+                let theme = load_theme(&config, &profile.theme, &options.hash_fields, depth)
+                    .with_context(|| format!("Invalid [profile.\"{}\"]", glob))?;
+                Ok(Profile { glob: glob.clone(), theme: profile.theme.clone(), colorizer: new_colorizer(theme) })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let presets = options.presets.iter()
+            .map(|name| config.preset(name))
+            .collect::<Result<Vec<_>>>()?;
+        let parsers = ParserRegistry::new(&config, options.input_format.as_deref())?;
+        let control = options.control.as_deref()
+            .map(|path| ControlServer::bind(path, options.filter.clone(), presets.iter().map(|&p| p.clone()).collect(), parsers.default_parser()))
+            .transpose()?;
+        let filter_source = options.interactive.then(|| FilterSource {
+            options: options.filter.clone(),
+            presets: presets.iter().map(|&p| p.clone()).collect(),
+            parser: parsers.default_parser(),
+        });
+        let filter = LineFilter::with_presets(options.filter, &presets)?
+            .with_parser(parsers.default_parser());
+        let output_format = OutputFormat::from_string(&options.format);
+        let schema = config.schema(options.schema.as_deref())?
+            .map(|schema| OutputSchema::from_config(schema)
+                .with_context(|| format!("Invalid [schema.{}]", options.schema.as_deref().unwrap_or("default"))))
+            .transpose()?;
+        let output_formatter = OutputFormatter::new(output_format, parsers)
+            .with_schema(schema)
+            .with_csv_columns(options.csv_columns.clone())
+            .with_json_colored(options.json_colored)
+            .with_zone(options.zone)
+            .with_positions(options.line_numbers, options.byte_offsets);
+        let envelope = output_formatter.is_ndjson().then(Envelope::new);
+        let positions = (envelope.is_some() || options.line_numbers || options.byte_offsets).then(Positions::new);
+        let tee = options.output_file.as_deref()
+            .map(|path| Tee::open(path, options.output_plain, options.flush))
+            .transpose()?;
+        let forwarder = options.forward.as_deref()
+            .map(|url| {
+                let mut forwarder = Forwarder::parse(url)?;
+                forwarder.connect().with_context(|| format!("Failed to connect to {}", url))?;
+                anyhow::Ok(forwarder)
+            })
+            .transpose()?;
+        let sqlite = options.sqlite.as_deref().map(SqliteSink::open).transpose()?;
+        let secrets = options.redact || config.redact.as_ref().is_some_and(|redact| redact.always);
+        let redactor = if secrets || options.anonymize_ips.is_some() {
+            let mut redactor = Redactor::new();
+            if secrets {
+                redactor = redactor.with_secrets(config.redact.as_ref().map_or(&[][..], |redact| &redact.patterns))?;
+            }
+            if let Some(octets) = options.anonymize_ips {
+                redactor = redactor.with_ip_masking(octets);
+            }
+            Some(redactor)
         } else {
-            return Err(anyhow!("Theme '{}' not found", theme_name));
+            None
+        };
+        let lanes = options.lanes.as_deref().map(LaneAssigner::from_spec).transpose()?;
+        let focus = options.focus.as_deref().map(FocusFilter::new).transpose()?;
+        let multiline = if options.multiline || options.multiline_start.is_some() {
+            Some(MultilineGrouper::new(options.multiline_start.as_deref())?)
+        } else {
+            None
         };
 
-        let colorizer = Colorizer::new(theme, no_color);
-        let filter = LineFilter::new(include, exclude, level)?;
-        let output_format = OutputFormat::from_string(&format);
-        let output_formatter = OutputFormatter::new(output_format);
+        let hooks = config.hooks.as_ref().map(Hooks::new).transpose()?.flatten();
+        let alerts = Alerts::new(&options.alerts, &config.alerts)?;
+        let metrics = options.metrics_listen.as_deref()
+            .map(|address| Metrics::listen(address, alerts.as_ref().map(Alerts::counters).unwrap_or_default()))
+            .transpose()?;
+        let bell = Bell::new(&options.bell)?;
+        let notifier = options.notify_level.as_deref().map(Notifier::new).transpose()?;
 
         Ok(Self {
             colorizer,
+            profiles,
+            profile_of: HashMap::new(),
             config,
             filter,
-            interactive,
+            interactive: options.interactive,
+            filter_source,
             output_formatter,
-            buffer_size,
-            bytes_mode,
-            quiet,
-            verbose,
-            max_buffer_lines,
+            buffer_size: options.buffer_size,
+            quiet: options.quiet,
+            verbose: options.verbose,
+            max_buffer_lines: options.max_buffer_lines,
+            scrollback_limit: options.scrollback_bytes,
+            lanes,
+            log_parser: GenericParser::new(),
+            max_skew: options.max_skew,
+            order_guard: options.order_guard.then(OrderGuard::new),
+            delta: options.delta.then(DeltaClock::new),
+            escalation,
+            multiline,
+            focus,
+            stdin_label: options.stdin_label,
+            squash: options.squash_repeats.then(RepeatSquasher::new),
+            merge: options.merge,
+            throttle: Throttle::new(options.sample, options.max_rate),
+            max_count: options.max_count,
+            printed: 0,
+            prefix_sources: true,
+            max_memory: options.max_memory,
+            sources: 1,
+            paged: options.handoff_less.then(Vec::new),
+            hooks,
+            output: Sink::new(io::stdout(), options.flush),
+            tee,
+            forwarder,
+            sqlite,
+            redactor,
+            strip_ansi: options.strip_ansi,
+            alerts,
+            notifier,
+            bell,
+            stats: options.stats.map(|interval| match options.order_guard {
+                true => Stats::new(interval).with_order_guard(),
+                false => Stats::new(interval),
+            }),
+            rate: options.rate.map(RateMeter::new),
+            metrics,
+            top: options.top.as_deref().map(TopCounter::new),
+            counts: options.count.then(Vec::new),
+            control,
+            theme_watcher: None,
+            hash_fields: options.hash_fields,
+            json_view: options.json_view,
+            hyperlinker: options.hyperlinks.then(Hyperlinker::new),
+            envelope,
+            positions,
+            line_numbers: options.line_numbers,
+            replay: options.replay.map(Replay::new),
+            byte_offsets: options.byte_offsets,
+            age_colors: options.age_colors,
+            zone: options.zone,
+            relative: options.relative,
+            projection: options.projection.clone(),
+            color_depth: depth,
+            notice: None,
+        })
+    }
+
+    /// Name used for stdin in headers, prefixes and structured output.
+    fn stdin_name(&self) -> String {
+        self.stdin_label.clone().unwrap_or_else(|| "stdin".to_string())
+    }
+
+    /// Whether a line from `path` passes `filter` (or the command line's) and the focus
+    /// filter, counted for `--stats` and `--metrics-listen`.
+    fn admits(&mut self, path: &Path, filter: Option<&LineFilter>, line: &str) -> bool {
+        let matched = filter.unwrap_or(&self.filter).should_show_line(line) && self.passes_focus(line);
+        if let Some(metrics) = &self.metrics {
+            metrics.observe(path, detect_log_level(line), matched);
+        }
+        if self.stats.is_some() {
+            let fields = parse_fields(self.output_formatter.parser_for(path).as_ref(), line);
+            let service = fields.get("service").and_then(|s| s.as_str());
+            if let Some(stats) = self.stats.as_mut() {
+                stats.observe(path, detect_log_level(line), service, matched);
+            }
+        }
+        matched
+    }
+
+    /// Apply `--focus` to a line that already passed the regular filters.
+    fn passes_focus(&mut self, line: &str) -> bool {
+        self.focus.as_mut().is_none_or(|focus| focus.admits(line))
+    }
+
+    /// Release or re-arm `--focus` from the keyboard.
+    fn toggle_focus(&mut self) {
+        if let Some(focus) = self.focus.as_mut() {
+            focus.toggle();
+        }
+    }
+
+    /// Colorize a line from `source` for display and apply per-line decorations
+    /// such as lanes and out-of-order markers.
+    fn render_line(&mut self, source: &Path, line: &str) -> String {
+        self.render(source, line, true)
+    }
+
+    /// `render_line`, with `--hyperlinks` links added when `links` is set.
+    fn render(&mut self, source: &Path, line: &str, links: bool) -> String {
+        // Container logs show their payload; the stream and CRI time stay available as fields
+        let line = match self.output_formatter.parser_for(source).name() {
+            "auto" | "cri" => cri_payload(line).unwrap_or(line),
+            _ => line,
+        };
+        // Only what's shown changes; times are still read from the line as written
+        let displayed = match self.zone {
+            _ if self.relative => self.log_parser.relative_timestamps(line, chrono::Local::now()),
+            Some(zone) => self.log_parser.rewrite_timestamps(line, zone),
+            None => Cow::Borrowed(line),
+        };
+        let color = self.colorizer.colors_enabled();
+        let colorizer = match self.profile_index(source) {
+            Some(i) => &self.profiles[i].colorizer,
+            None => &self.colorizer,
+        };
+        // Under --json-view or --jq/--fields, matches and lanes work on the text as shown
+        let projected = self.projection.as_ref()
+            .and_then(|projection| projection.apply(self.output_formatter.parser_for(source).as_ref(), &displayed));
+        let (shown, mut rendered) = match self.json_view.and_then(|layout| colorizer.colorize_json(&displayed, layout)) {
+            Some((plain, colored)) => (Cow::Owned(plain), colored),
+            None => match projected {
+                Some(text) => {
+                    let colored = colorizer.colorize_line(&text);
+                    (Cow::Owned(text), colored)
+                }
+                None => {
+                    let colored = colorizer.colorize_line(&displayed);
+                    (displayed, colored)
+                }
+            },
+        };
+        if let Some(patterns) = self.filter.highlight_patterns().filter(|_| color) {
+            let style = colorizer.get_theme().match_style;
+            rendered = overlay_matches(&rendered, &shown, patterns, &style.to_ansi());
+        }
+        if self.age_colors && color {
+            let span = self.log_parser.timestamp_span(&shown);
+            if let (Some(span), Some(time)) = (span, self.log_parser.parse_timestamp(line)) {
+                // Timestamps from the future (clock skew) count as fresh
+                let age = (chrono::Local::now() - time).to_std().unwrap_or_default();
+                if let Some(style) = colorizer.age_style(age) {
+                    rendered = overlay_ranges(&rendered, &shown, &[span], &style.to_ansi());
+                }
+            }
+        }
+        if let Some(lanes) = self.lanes.as_mut() {
+            rendered = lanes.decorate(&shown, &rendered, color);
+        }
+        if let Some(escalation) = self.escalation.as_mut() {
+            if let Some(level) = detect_log_level(line) {
+                let time_ms = self.log_parser.parse_timestamp(line)
+                    .unwrap_or_else(chrono::Local::now)
+                    .timestamp_millis();
+                if let Some(badge) = escalation.observe(level, time_ms, color) {
+                    rendered = format!("{}{}", badge, rendered);
+                }
+            }
+        }
+        if let Some(guard) = self.order_guard.as_mut() {
+            let timestamp = self.log_parser.parse_timestamp(line);
+            if let Some(marker) = guard.check(source, timestamp, color) {
+                rendered = format!("{}{}", marker, rendered);
+                if let Some(stats) = self.stats.as_mut() {
+                    stats.observe_out_of_order();
+                }
+            }
+        }
+        if let Some(delta) = self.delta.as_mut() {
+            rendered = format!("{}{}", delta.stamp(self.log_parser.parse_timestamp(line), color), rendered);
+        }
+        match self.hyperlinker.as_ref().filter(|_| links) {
+            Some(linker) => linker.link(&rendered, &shown),
+            None => rendered,
+        }
+    }
+
+    /// The `[profile]` for lines from `source`: the one with the longest glob matching
+    /// the path as given or made absolute, so `*/nginx/error.log` beats `*/nginx/*`.
+    fn profile_index(&mut self, source: &Path) -> Option<usize> {
+        if self.profiles.is_empty() {
+            return None;
+        }
+        let profiles = &self.profiles;
+        *self.profile_of.entry(source.to_path_buf()).or_insert_with(|| {
+            let absolute = std::path::absolute(source).unwrap_or_else(|_| source.to_path_buf());
+            let names = [source.to_string_lossy(), absolute.to_string_lossy()];
+            profiles.iter()
+                .enumerate()
+                .filter(|(_, profile)| names.iter().any(|name| glob_match(&profile.glob, name)))
+                .max_by_key(|(_, profile)| profile.glob.len())
+                .map(|(i, _)| i)
         })
     }
 
+    /// Print one line of output, unless `--max-count` lines have been printed already.
+    fn print_line(&mut self, line: &str) {
+        if self.limit_reached() {
+            return;
+        }
+        self.print_text(line);
+        self.printed += 1;
+    }
+
+    /// Print a line in the `--format` output format, after the CSV header the first time.
+    fn print_formatted(&mut self, path: &Path, source: Option<&str>, line: &str, colored_line: &str) {
+        if self.limit_reached() {
+            return;
+        }
+        if let Some(replay) = self.replay.as_mut() {
+            // What's been printed goes out before waiting for the next line
+            self.output.flush();
+            replay.wait(self.log_parser.parse_timestamp(line));
+        }
+        self.forward(path, line);
+        if self.count_line(path, line) {
+            return;
+        }
+        let position = self.positions.as_ref().and_then(|positions| positions.take(path, line));
+        let colored_line = match self.output_formatter.is_text() {
+            true => self.position_prefix(position) + colored_line,
+            false => colored_line.to_string(),
+        };
+        let mut formatted = self.output_formatter.format_line(path, source, line, &colored_line, position);
+        // After the line, so a table's header is laid out for its first row
+        if let Some(header) = self.output_formatter.take_header() {
+            self.print_text(&header);
+        }
+        if self.output_formatter.is_table() {
+            formatted = self.colorizer.colorize_line(&formatted);
+        }
+        if let Some(envelope) = &self.envelope {
+            formatted = envelope.wrap(path, position, &formatted);
+        }
+        if self.output_formatter.is_pretty() {
+            if let Some((_, pretty)) = self.colorizer.colorize_json(&formatted, JsonLayout::Pretty) {
+                self.print_line(&pretty);
+                return;
+            }
+        }
+        self.print_line(&formatted);
+    }
+
+    /// `--line-numbers` and `--byte-offsets` in text output: `42:`, `1834:` or both, as
+    /// `grep -nb` has them, and `-:` for lines ft made up.
+    fn position_prefix(&self, position: Option<Position>) -> String {
+        let mut prefix = String::new();
+        for (on, value) in [(self.line_numbers, position.map(|p| p.line)), (self.byte_offsets, position.map(|p| p.offset))] {
+            if on {
+                prefix += &value.map_or_else(|| "-".to_string(), |n| n.to_string());
+                prefix.push(':');
+            }
+        }
+        match prefix.is_empty() || !self.colorizer.colors_enabled() {
+            true => prefix,
+            false => format!("\x1b[2m{}\x1b[0m", prefix),
+        }
+    }
+
+    /// A line just read, with `--strip-ansi`, `--redact` and `--anonymize-ips` applied.
+    fn clean_line(&self, line: String) -> String {
+        clean_line(line, self.strip_ansi, self.redactor.as_ref())
+    }
+
+    /// Send a line about to be shown to the `--forward` collector and `--sqlite` database.
+    fn forward(&mut self, path: &Path, line: &str) {
+        if self.limit_reached() {
+            return;
+        }
+        if let Some(forwarder) = self.forwarder.as_mut() {
+            forwarder.send(path, line);
+        }
+        if let Some(sqlite) = self.sqlite.as_mut() {
+            sqlite.insert(path, self.output_formatter.parser_for(path).as_ref(), line);
+        }
+    }
+
+    /// Count a line for `--top` or `--count`, returning whether it's counted rather than printed.
+    fn count_line(&mut self, path: &Path, line: &str) -> bool {
+        if let Some(top) = self.top.as_mut() {
+            top.observe(self.output_formatter.parser_for(path).as_ref(), line);
+        } else if self.counts.is_some() {
+            self.count_source(path);
+            if let Some((_, count)) = self.counts.as_mut().and_then(|counts| counts.iter_mut().find(|(p, _)| p == path)) {
+                *count += 1;
+            }
+        } else {
+            return false;
+        }
+        self.printed += 1;
+        true
+    }
+
+    /// Make sure `path` has a `--count` line, even if nothing in it matches.
+    fn count_source(&mut self, path: &Path) {
+        if let Some(counts) = self.counts.as_mut() {
+            if !counts.iter().any(|(p, _)| p == path) {
+                counts.push((path.to_path_buf(), 0));
+            }
+        }
+    }
+
+    /// The total of `--count`, once processing is done.
+    pub fn counted(&self) -> Option<usize> {
+        self.counts.as_ref().map(|counts| counts.iter().map(|(_, count)| count).sum())
+    }
+
+    /// Draw the `--top` table, over the previous one on a terminal.
+    fn show_top(&mut self, table: &str) {
+        use is_terminal::IsTerminal;
+        if io::stdout().is_terminal() {
+            self.output.write_line(&format!("\x1b[H\x1b[2J{}", table));
+        } else {
+            self.output.write_line(&format!("{}\n", table));
+        }
+    }
+
+    /// Print a line of output, or keep it for the pager with --handoff-less.
+    fn print_text(&mut self, text: &str) {
+        // --top and --count show their tables in place of everything else
+        if self.top.is_some() || self.counts.is_some() {
+            return;
+        }
+        if let Some(tee) = self.tee.as_mut() {
+            tee.write_line(text);
+        }
+        match self.paged.as_mut() {
+            Some(paged) => paged.push(text.to_string()),
+            None => self.output.write_line(text),
+        }
+    }
+
+    /// A batch of input has been handled: flush output that waits for one.
+    fn end_frame(&mut self) {
+        self.output.end_frame();
+        if let Some(tee) = self.tee.as_mut() {
+            tee.end_frame();
+        }
+        if let Some(sqlite) = self.sqlite.as_mut() {
+            sqlite.flush();
+        }
+        if let Some(report) = self.stats.as_mut().and_then(Stats::due_report) {
+            eprintln!("{}\n", report);
+        }
+        if let Some(report) = self.rate.as_mut().and_then(RateMeter::due_report) {
+            eprintln!("{}", report);
+        }
+        if let Some(table) = self.top.as_mut().and_then(TopCounter::due_table) {
+            self.show_top(&table);
+        }
+    }
+
+    /// Whether `--max-count` is satisfied, or output has been closed, and processing should stop.
+    fn limit_reached(&self) -> bool {
+        self.output.is_closed() || self.max_count.is_some_and(|max| self.printed >= max)
+    }
+
+    /// Run the `on_start` hook for the sources about to be tailed.
+    pub fn start(&self, files: &[PathBuf]) {
+        if let Some(hooks) = &self.hooks {
+            match files {
+                [] => hooks.start(&[PathBuf::from(self.stdin_name())]),
+                files => hooks.start(files),
+            }
+        }
+    }
+
+    /// Switch to a filter sent with `ft ctl set-filter`, if one arrived.
+    fn apply_control(&mut self) {
+        if let Some(filter) = self.control.as_ref().and_then(ControlServer::next_filter) {
+            self.filter = filter;
+        }
+    }
+
+    /// Start watching the theme for changes; called when following begins.
+    fn watch_theme(&mut self) {
+        if self.theme_watcher.is_none() && self.colorizer.colors_enabled() {
+            let files: Vec<PathBuf> = std::iter::once(&self.config.general.theme)
+                .chain(self.profiles.iter().map(|profile| &profile.theme))
+                .filter_map(|name| self.config.get_theme_path(name))
+                .collect();
+            self.theme_watcher = Some(ThemeWatcher::new(&files));
+        }
+    }
+
+    /// Reload the themes if one of their files changed or SIGHUP arrived, returning a
+    /// message saying how it went. A theme that fails to load leaves the current one in place.
+    fn reload_theme(&mut self) -> Option<String> {
+        if !self.theme_watcher.as_ref().is_some_and(ThemeWatcher::take) {
+            return None;
+        }
+        let name = &self.config.general.theme;
+        let mut message = match load_theme(&self.config, name, &self.hash_fields, self.color_depth) {
+            Ok(theme) => {
+                self.colorizer.set_theme(theme);
+                format!("theme {} reloaded", name)
+            }
+            Err(e) => format!("theme {} not reloaded: {:#}", name, e),
+        };
+        for profile in &mut self.profiles {
+            match load_theme(&self.config, &profile.theme, &self.hash_fields, self.color_depth) {
+                Ok(theme) => profile.colorizer.set_theme(theme),
+                Err(e) => message = format!("theme {} not reloaded: {:#}", profile.theme, e),
+            }
+        }
+        Some(message)
+    }
+
+    /// Report anything worth knowing once processing ends.
+    pub fn finish(&mut self) -> Result<()> {
+        if let Some(stats) = &self.stats {
+            eprintln!("{}", stats.report());
+        }
+        if let Some(footer) = self.output_formatter.footer() {
+            self.print_text(footer);
+        }
+        if let Some(top) = self.top.as_mut().filter(|top| !top.is_drawn()) {
+            let table = top.table();
+            self.output.write_line(&table);
+        }
+        if let Some(counts) = &self.counts {
+            // Like grep -c: just the number for one source, `name:count` for several
+            let lines: Vec<String> = match counts.as_slice() {
+                [] => vec!["0".to_string()],
+                [(_, count)] => vec![count.to_string()],
+                counts => counts.iter().map(|(path, count)| format!("{}:{}", path.display(), count)).collect(),
+            };
+            for line in lines {
+                self.output.write_line(&line);
+            }
+        }
+        self.output.flush();
+        if let Some(tee) = self.tee.as_mut() {
+            tee.flush();
+        }
+        if let Some(sqlite) = self.sqlite.as_mut() {
+            sqlite.flush();
+        }
+        if let Some(hooks) = &self.hooks {
+            hooks.exit(self.printed);
+        }
+        if let Some(paged) = self.paged.take() {
+            crate::pager::page(&paged, false)?;
+        }
+        Ok(())
+    }
+
     pub fn process_stdin(&mut self, lines: usize, follow: bool) -> Result<()> {
-        let stdin = stdin();
-        let reader = BufReader::with_capacity(self.buffer_size, stdin.lock());
+        if self.verbose && !self.quiet && self.output_formatter.is_text() {
+            self.print_text(&format!("==> {} <==", self.stdin_name()));
+        }
+        let source = self.stdin_label.clone();
+        self.read_stdin(lines, follow, source.as_deref())
+    }
 
+    fn read_stdin(&mut self, lines: usize, follow: bool, source: Option<&str>) -> Result<()> {
         if follow {
-            for line in reader.lines() {
-                let line = line.context("Failed to read from stdin")?;
-                if self.filter.should_show_line(&line) {
-                    let colored_line = self.colorizer.colorize_line(&line);
-                    let formatted = self.output_formatter.format_line(&line, &colored_line);
-                    println!("{}", formatted);
+            // Read on a separate thread so a partial multiline record can be flushed once input goes idle
+            let (tx, rx) = mpsc::channel();
+            let buffer_size = self.buffer_size;
+            thread::spawn(move || {
+                let reader = BufReader::with_capacity(buffer_size, stdin());
+                for line in reader.lines() {
+                    if tx.send(line).is_err() {
+                        break;
+                    }
+                }
+            });
+
+            let mut grouper = self.multiline.clone();
+            let mut squasher = self.squash.clone();
+            let mut throttle = self.throttle.clone();
+            self.watch_theme();
+            loop {
+                self.apply_control();
+                if let Some(message) = self.reload_theme() {
+                    eprintln!("ft: {}", message);
+                }
+                let record = match rx.recv_timeout(IDLE_FLUSH) {
+                    Ok(line) => {
+                        let line = line.context("Failed to read from stdin")?;
+                        let bytes = line.len() + 1;
+                        let line = self.clean_line(line);
+                        if let Some(positions) = &self.positions {
+                            positions.read(Path::new(&self.stdin_name()), None, &line, bytes);
+                        }
+                        if let Some(hooks) = &self.hooks {
+                            hooks.check_alert(Path::new(&self.stdin_name()), std::slice::from_ref(&line));
+                        }
+                        if let Some(alerts) = &self.alerts {
+                            alerts.check(Path::new(&self.stdin_name()), std::slice::from_ref(&line));
+                        }
+                        if let Some(notifier) = &self.notifier {
+                            notifier.check(Path::new(&self.stdin_name()), std::slice::from_ref(&line));
+                        }
+                        if let Some(bell) = &self.bell {
+                            bell.check(std::slice::from_ref(&line));
+                        }
+                        if let Some(rate) = self.rate.as_mut() {
+                            rate.observe(std::slice::from_ref(&line));
+                        }
+                        group_line(&mut grouper, line)
+                    }
+                    Err(mpsc::RecvTimeoutError::Timeout) => {
+                        if let Some(line) = squasher.as_mut().and_then(RepeatSquasher::flush_if_idle) {
+                            for line in throttle_line(&mut throttle, line) {
+                                self.write_stdin_line(source, &line);
+                            }
+                        }
+                        if let Some(summary) = throttle.as_mut().and_then(Throttle::flush_if_idle) {
+                            self.write_stdin_line(source, &summary);
+                        }
+                        // Input went quiet: whatever is buffered is a complete frame
+                        self.end_frame();
+                        grouper.as_mut().and_then(MultilineGrouper::flush_if_idle)
+                    }
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                };
+                if let Some(record) = record {
+                    self.print_stdin_record(source, record, &mut squasher, &mut throttle);
+                }
+                if self.limit_reached() {
+                    break;
+                }
+            }
+            if let Some(record) = grouper.as_mut().and_then(MultilineGrouper::flush) {
+                self.print_stdin_record(source, record, &mut squasher, &mut throttle);
+            }
+            if let Some(line) = squasher.as_mut().and_then(RepeatSquasher::flush) {
+                for line in throttle_line(&mut throttle, line) {
+                    self.write_stdin_line(source, &line);
                 }
             }
+            if let Some(summary) = throttle.as_mut().and_then(Throttle::flush) {
+                self.write_stdin_line(source, &summary);
+            }
         } else {
+            let stdin = stdin();
+            let reader = BufReader::with_capacity(self.buffer_size, stdin.lock());
             let all_lines: Vec<String> = reader.lines()
                 .collect::<Result<Vec<_>, _>>()
                 .context("Failed to read from stdin")?;
+            let all_lines: Vec<String> = all_lines.into_iter()
+                .map(|line| {
+                    let bytes = line.len() + 1;
+                    let line = self.clean_line(line);
+                    if let Some(positions) = &self.positions {
+                        positions.read(Path::new(&self.stdin_name()), None, &line, bytes);
+                    }
+                    line
+                })
+                .collect();
+            let all_records = self.group_lines(all_lines);
 
-            let filtered_lines: Vec<&String> = all_lines.iter()
-                .filter(|line| self.filter.should_show_line(line))
+            let stdin_path = PathBuf::from(self.stdin_name());
+            let filtered_lines: Vec<String> = all_records.into_iter()
+                .filter(|line| self.admits(&stdin_path, None, line))
                 .collect();
+            let filtered_lines = self.sample_lines(self.squash_lines(filtered_lines));
 
             let start_idx = filtered_lines.len().saturating_sub(lines);
+            if let Some(positions) = &self.positions {
+                positions.align(&stdin_path, &filtered_lines[start_idx..]);
+            }
             for line in &filtered_lines[start_idx..] {
-                let colored_line = self.colorizer.colorize_line(line);
-                let formatted = self.output_formatter.format_line(line, &colored_line);
-                println!("{}", formatted);
+                let colored_line = self.render_line(&stdin_path, line);
+                self.print_formatted(&stdin_path, source, line, &colored_line);
             }
         }
 
         Ok(())
     }
 
+    fn print_stdin_record(&mut self, source: Option<&str>, record: String, squasher: &mut Option<RepeatSquasher>, throttle: &mut Option<Throttle>) {
+        if self.admits(&PathBuf::from(self.stdin_name()), None, &record) {
+            if let Some(line) = squash_line(squasher, record) {
+                for line in throttle_line(throttle, line) {
+                    self.write_stdin_line(source, &line);
+                }
+            }
+        }
+    }
+
+    fn write_stdin_line(&mut self, source: Option<&str>, line: &str) {
+        let stdin_path = PathBuf::from(self.stdin_name());
+        let colored_line = self.render_line(&stdin_path, line);
+        self.print_formatted(&stdin_path, source, line, &colored_line);
+    }
+
     pub fn process_files(&mut self, files: &[PathBuf], lines: usize, follow: bool) -> Result<()> {
+        use is_terminal::IsTerminal;
+
+        if files.iter().filter(|path| is_stdin_path(path)).count() > 1 {
+            return Err(anyhow!("stdin ('-') can only be given once"));
+        }
+        self.sources = files.len().max(1);
+        for file in files {
+            let path = if is_stdin_path(file) { PathBuf::from(self.stdin_name()) } else { file.clone() };
+            self.count_source(&path);
+        }
+
+        if self.interactive && io::stdout().is_terminal() {
+            return self.run_interactive(files, lines, follow);
+        }
         if files.len() == 1 {
             self.process_single_file(&files[0], lines, follow)
         } else {
@@ -135,11 +1079,60 @@ impl TailProcessor {
         }
     }
 
-    fn process_single_file(&mut self, file_path: &Path, lines: usize, follow: bool) -> Result<()> {
+    /// `-i`: browse the last `lines` lines of each source in the interactive view and, with
+    /// `-f`, those added after, read on a thread of its own while the view runs.
+    fn run_interactive(&mut self, files: &[PathBuf], lines: usize, follow: bool) -> Result<()> {
+        let mut initial = Vec::new();
+        let mut names = Vec::new();
+        let mut sources = Vec::new();
+        for (source, file) in files.iter().enumerate() {
+            if is_stdin_path(file) {
+                names.push(self.stdin_name());
+                if follow {
+                    sources.push(FollowSource { path: file.clone(), offset: 0, stdin: true });
+                } else {
+                    let read: Vec<String> = stdin().lock().lines()
+                        .collect::<Result<Vec<_>, _>>()
+                        .context("Failed to read from stdin")?;
+                    let start = read.len().saturating_sub(lines);
+                    initial.extend(read.into_iter().skip(start).map(|line| (source, self.clean_line(line))));
+                }
+                continue;
+            }
+            names.push(file.file_name().and_then(|n| n.to_str()).unwrap_or("unknown").to_string());
+            let handle = File::open(file).with_context(|| format!("Failed to open file: {:?}", file))?;
+            // Lines written while the last ones are read are shown twice rather than missed
+            let offset = handle.metadata()?.len();
+            initial.extend(self.get_last_n_lines(file, handle, lines)?.into_iter().map(|line| (source, line)));
+            sources.push(FollowSource { path: file.clone(), offset, stdin: false });
+        }
+
+        let backlog = initial.len();
+        let mut view = InteractiveMode::new(initial, names, self.colorizer.clone(), self.filter.clone())
+            .with_clipboard(self.config.general.clipboard.clone());
+        if let Some(source) = self.filter_source.take() {
+            view = view.with_filter_source(source);
+        }
         if follow {
+            let strip_ansi = self.strip_ansi;
+            let redactor = self.redactor.take();
+            let feed = spawn_follower(sources, move |line| clean_line(line, strip_ansi, redactor.as_ref()));
+            // One buffer for all the sources, so it gets what the windows would share
+            let max_bytes = self.max_memory.map_or(usize::MAX, |max| max / 2)
+                .min(self.scrollback_limit.unwrap_or(usize::MAX));
+            // Room for at least the lines asked for with -n, or the first new one would drop them
+            view = view.with_feed(feed, self.max_buffer_lines.max(backlog), max_bytes);
+        }
+        view.run()
+    }
+
+    fn process_single_file(&mut self, file_path: &Path, lines: usize, follow: bool) -> Result<()> {
+        if is_stdin_path(file_path) {
+            self.process_stdin(lines, follow)?;
+        } else if follow {
             self.follow_file(file_path, lines)?;
         } else {
-            self.show_tail_lines(file_path, lines)?;
+            self.show_tail_lines(file_path, lines, None)?;
         }
 
         Ok(())
@@ -150,123 +1143,310 @@ impl TailProcessor {
             self.follow_multiple_files(files)?;
         } else {
             for (i, file_path) in files.iter().enumerate() {
-                if i > 0 && !self.quiet {
-                    println!();
+                if self.limit_reached() {
+                    break;
+                }
+                if i > 0 && !self.quiet && self.output_formatter.is_text() {
+                    self.print_text("");
+                }
+
+                if is_stdin_path(file_path) {
+                    let name = self.stdin_name();
+                    if !self.quiet {
+                        self.print_header(file_path, &name);
+                    }
+                    self.read_stdin(lines, false, Some(&name))?;
+                    continue;
                 }
 
                 if !self.quiet && (self.verbose || files.len() > 1) {
-                    println!("==> {} <==", file_path.display());
+                    self.print_header(file_path, &file_path.display().to_string());
                 }
-                self.show_tail_lines(file_path, lines)?;
+                let name = file_path.display().to_string();
+                self.show_tail_lines(file_path, lines, Some(&name))?;
             }
         }
 
         Ok(())
     }
 
-    fn show_tail_lines(&mut self, file_path: &Path, lines: usize) -> Result<()> {
-        let file = File::open(file_path)
-            .with_context(|| format!("Failed to open file: {:?}", file_path))?;
-
-        let tail_lines = self.get_last_n_lines(file, lines)?;
-
-        let filtered_lines: Vec<&String> = tail_lines.iter()
-            .filter(|line| self.filter.should_show_line(line))
-            .collect();
+    /// Print a `==> name <==` header above a source's lines. Only text output has them:
+    /// structured formats name the source in each record instead.
+    fn print_header(&mut self, path: &Path, name: &str) {
+        if self.output_formatter.is_text() {
+            let header = self.colorizer.paint_source(path, &format!("==> {} <==", name));
+            self.print_text(&header);
+        }
+    }
 
-        let start_idx = filtered_lines.len().saturating_sub(lines);
-        for line in &filtered_lines[start_idx..] {
-            let colored_line = self.colorizer.colorize_line(line);
-            let formatted = self.output_formatter.format_line(line, &colored_line);
-            println!("{}", formatted);
+    /// Print the last lines of a file. `source` names the file in structured output.
+    fn show_tail_lines(&mut self, file_path: &Path, lines: usize, source: Option<&str>) -> Result<()> {
+        let visible = self.visible_tail(file_path, lines)?;
+        if let Some(positions) = &self.positions {
+            positions.align(file_path, &visible);
+        }
+        for line in &visible {
+            let colored_line = self.render_line(file_path, line);
+            self.print_formatted(file_path, source, line, &colored_line);
         }
 
         Ok(())
     }
 
-    fn get_last_n_lines(&self, file: File, n: usize) -> Result<Vec<String>> {
+    /// The last `n` lines of a file, holding no more than `n` lines (and half of
+    /// --max-memory) while reading.
+    fn get_last_n_lines(&self, path: &Path, file: File, n: usize) -> Result<Vec<String>> {
         let mut reader = BufReader::with_capacity(self.buffer_size, file);
-        let mut all_lines = Vec::new();
+        let mut last_lines = VecDeque::new();
+        let mut bytes = 0usize;
+        let max_bytes = self.max_memory.map_or(usize::MAX, |max| max / 2);
+        let mut dropped_for_memory = false;
         let mut line = String::new();
+        let mut offset = 0u64;
 
         while reader.read_line(&mut line)? > 0 {
-            all_lines.push(line.trim_end_matches('\n').trim_end_matches('\r').to_string());
+            let text = self.clean_line(line.trim_end_matches('\n').trim_end_matches('\r').to_string());
+            if let Some(positions) = &self.positions {
+                positions.read(path, Some(offset), &text, line.len());
+            }
+            offset += line.len() as u64;
+            bytes += text.len();
+            last_lines.push_back(text);
+            while last_lines.len() > n || (bytes > max_bytes && last_lines.len() > 1) {
+                dropped_for_memory |= last_lines.len() <= n;
+                if let Some(old) = last_lines.pop_front() {
+                    bytes -= old.len();
+                }
+            }
             line.clear();
         }
 
-        let start_idx = all_lines.len().saturating_sub(n);
-        Ok(all_lines[start_idx..].to_vec())
+        if dropped_for_memory {
+            eprintln!("ft: --max-memory reached; reading only the last {} lines", last_lines.len());
+        }
+        Ok(last_lines.into())
     }
 
-    fn follow_file(&mut self, file_path: &Path, initial_lines: usize) -> Result<()> {
-        use crossterm::terminal::{enable_raw_mode, disable_raw_mode};
+    /// Scrollback bytes each window may keep: half of --max-memory split across the
+    /// sources, and no more than --scrollback.
+    fn scrollback_bytes(&self) -> usize {
+        let share = self.max_memory.map_or(usize::MAX, |max| max / 2 / self.sources);
+        share.min(self.scrollback_limit.unwrap_or(usize::MAX))
+    }
 
-        if let Err(_) = enable_raw_mode() {
-            return Ok(());
+    /// The last `n` records of a file. With multiline grouping, repeat squashing or
+    /// sampling the whole file is read so the oldest record shown is never cut in half
+    /// and sampling counts from the start of the file.
+    fn last_records(&self, path: &Path, n: usize) -> Result<Vec<String>> {
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open file: {:?}", path))?;
+        let whole_file = self.multiline.is_some() || self.squash.is_some() || self.throttle.is_some();
+        let lines = self.get_last_n_lines(path, file, if whole_file { usize::MAX } else { n })?;
+        let mut records = self.group_lines(lines);
+        // Squashing and sampling happen after filtering, so keep everything for visible_tail to trim
+        if self.squash.is_none() && self.throttle.is_none() {
+            let start_idx = records.len().saturating_sub(n);
+            records = records.split_off(start_idx);
         }
+        Ok(records)
+    }
 
-        let mut stdout = io::stdout();
-        if let Err(_) = execute!(stdout, EnterAlternateScreen, Hide) {
-            let _ = disable_raw_mode();
-            return Ok(());
+    /// The last `n` records of a file as they would be shown: filtered, squashed and sampled.
+    fn visible_tail(&mut self, path: &Path, n: usize) -> Result<Vec<String>> {
+        let records: Vec<String> = self.last_records(path, n)?.into_iter()
+            .filter(|line| self.admits(path, None, line))
+            .collect();
+        let mut records = self.sample_lines(self.squash_lines(records));
+        let start_idx = records.len().saturating_sub(n);
+        Ok(records.split_off(start_idx))
+    }
+
+    /// Collapse runs of repeated lines in a batch when --squash-repeats is set.
+    fn squash_lines(&self, lines: Vec<String>) -> Vec<String> {
+        match self.squash.clone() {
+            Some(mut squasher) => squasher.squash_all(lines),
+            None => lines,
         }
+    }
 
-        let result = self.follow_file_fullscreen(file_path, initial_lines);
+    /// Keep every Nth line of a batch when --sample is set.
+    fn sample_lines(&self, lines: Vec<String>) -> Vec<String> {
+        match self.throttle.clone() {
+            Some(mut throttle) => throttle.sample_all(lines),
+            None => lines,
+        }
+    }
 
-        let _ = execute!(stdout, Show, LeaveAlternateScreen);
-        let _ = disable_raw_mode();
-        result
+    /// Group a batch of lines into multiline records, or pass them through unchanged.
+    fn group_lines(&self, lines: Vec<String>) -> Vec<String> {
+        match self.multiline.clone() {
+            Some(mut grouper) => grouper.group_all(lines),
+            None => lines,
+        }
     }
 
-    fn follow_file_fullscreen(&mut self, file_path: &Path, initial_lines: usize) -> Result<()> {
-        let file = File::open(file_path)
-            .with_context(|| format!("Failed to open file: {:?}", file_path))?;
-        let pos = file.metadata()?.len();
+    fn open_tracker(&self, path: &Path, max_lines: usize) -> Result<FileTracker> {
+        if is_stdin_path(path) {
+            return Ok(self.open_stdin_tracker(max_lines));
+        }
+
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open file: {:?}", path))?;
+        let position = file.metadata()?.len();
         let file_id = get_open_file_id(&file);
 
-        let mut tracker = FileTracker {
-            path: file_path.to_path_buf(),
-            file,
-            position: pos,
+        Ok(FileTracker {
+            path: path.to_path_buf(),
+            file: Some(file),
+            position,
             lines: VecDeque::new(),
             raw_lines: VecDeque::new(),
-            max_lines: self.max_buffer_lines,
+            max_lines,
+            max_bytes: self.scrollback_bytes(),
+            buffered_bytes: 0,
             line_count: 0,
             last_update: std::time::SystemTime::now(),
             file_id,
             paused: false,
             filter: None,
             search_term: None,
-        };
+            grouper: self.multiline.clone(),
+            stats: SourceStats::new(),
+            stdin: None,
+            squasher: self.squash.clone(),
+            throttle: self.throttle.clone(),
+        })
+    }
 
-        // Load initial lines
-        tracker.line_count = self.count_lines_in_file(&tracker.path).unwrap_or(0);
-        if let Ok(lines) = self.get_last_n_lines(File::open(file_path)?, initial_lines) {
-            for line in lines {
-                if self.filter.should_show_line(&line) {
-                    let colored_line = self.colorizer.colorize_line(&line);
-                    tracker.lines.push_back(colored_line);
-                    tracker.raw_lines.push_back(line);
+    fn open_stdin_tracker(&self, max_lines: usize) -> FileTracker {
+        let (tx, rx) = mpsc::channel();
+        let buffer_size = self.buffer_size;
+        thread::spawn(move || {
+            let reader = BufReader::with_capacity(buffer_size, stdin());
+            for line in reader.lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
                 }
             }
+        });
+
+        FileTracker {
+            path: PathBuf::from(self.stdin_name()),
+            file: None,
+            position: 0,
+            lines: VecDeque::new(),
+            raw_lines: VecDeque::new(),
+            max_lines,
+            max_bytes: self.scrollback_bytes(),
+            buffered_bytes: 0,
+            line_count: 0,
+            last_update: std::time::SystemTime::now(),
+            file_id: None,
+            paused: false,
+            filter: None,
+            search_term: None,
+            grouper: self.multiline.clone(),
+            stats: SourceStats::new(),
+            stdin: Some(StdinSource { lines: rx, closed: false }),
+            squasher: self.squash.clone(),
+            throttle: self.throttle.clone(),
+        }
+    }
+
+    fn load_initial_lines(&mut self, tracker: &mut FileTracker, n: usize) {
+        // Stdin has no backlog to load
+        if tracker.stdin.is_some() {
+            return;
+        }
+        if let Ok(records) = self.visible_tail(&tracker.path, n) {
+            for record in records {
+                self.display_record(tracker, record);
+            }
+        }
+        tracker.line_count = self.count_lines_in_file(&tracker.path).unwrap_or(0);
+        // Statistics cover what arrives while tailing, not the initial backlog
+        tracker.stats = SourceStats::new();
+    }
+
+    /// Filter a record and buffer it in a tracker, squashing repeats and throttling when enabled.
+    fn append_record(&mut self, tracker: &mut FileTracker, record: String) {
+        if !self.admits(&tracker.path, tracker.filter.as_ref(), &record) {
+            return;
+        }
+        if let Some(record) = squash_line(&mut tracker.squasher, record) {
+            self.throttle_record(tracker, record);
+        }
+    }
+
+    /// Buffer a filtered, squashed record in a tracker, subject to --sample and --max-rate.
+    fn throttle_record(&mut self, tracker: &mut FileTracker, record: String) {
+        for record in throttle_line(&mut tracker.throttle, record) {
+            self.display_record(tracker, record);
+        }
+    }
+
+    /// Render and buffer a record in a tracker, one row per physical line.
+    fn display_record(&mut self, tracker: &mut FileTracker, record: String) {
+        // Pane rows are cut to width by counting columns, which OSC 8 links would throw off
+        let mut colored_line = self.render(&tracker.path, &record, false);
+        if let Some(positions) = &self.positions {
+            colored_line = self.position_prefix(positions.take(&tracker.path, &record)) + &colored_line;
+        }
+        if let Some(tee) = self.tee.as_mut() {
+            tee.write_line(&colored_line);
+        }
+        self.forward(&tracker.path, &record);
+        self.count_line(&tracker.path, &record);
+        tracker.stats.lines_shown += 1;
+        tracker.push_rows(split_rendered_rows(&colored_line), record.split('\n').map(str::to_string));
+        tracker.line_count += 1;
+        tracker.last_update = std::time::SystemTime::now();
+    }
+
+    fn follow_file(&mut self, file_path: &Path, initial_lines: usize) -> Result<()> {
+        use crossterm::terminal::{enable_raw_mode, disable_raw_mode};
+        use is_terminal::IsTerminal;
+
+        self.output.flush();
+        // Piped output and --max-count want a plain stream, not a full-screen view
+        if self.max_count.is_some() || self.top.is_some() || !io::stdout().is_terminal() {
+            return self.follow_files_scroll(&[file_path.to_path_buf()], initial_lines);
+        }
+
+        if enable_raw_mode().is_err() {
+            return self.follow_files_scroll(&[file_path.to_path_buf()], initial_lines);
+        }
+
+        let mut stdout = io::stdout();
+        if execute!(stdout, EnterAlternateScreen, Hide).is_err() {
+            let _ = disable_raw_mode();
+            return self.follow_files_scroll(&[file_path.to_path_buf()], initial_lines);
         }
 
+        let result = self.follow_file_fullscreen(file_path, initial_lines);
+
+        let _ = execute!(stdout, Show, LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+        result
+    }
+
+    fn follow_file_fullscreen(&mut self, file_path: &Path, initial_lines: usize) -> Result<()> {
+        let mut tracker = self.open_tracker(file_path, self.max_buffer_lines)?;
+        self.load_initial_lines(&mut tracker, initial_lines);
+
         // Set up file watcher
         let (tx, rx) = mpsc::channel();
         let mut watcher: RecommendedWatcher = Watcher::new(tx, NotifyConfig::default())?;
         watcher.watch(file_path, RecursiveMode::NonRecursive)?;
 
+        self.watch_theme();
+
         // Initial render
         self.render_single_frame(&tracker)?;
 
         loop {
             // Drain watcher events
-            loop {
-                match rx.try_recv() {
-                    Ok(_) => {}
-                    Err(_) => break,
-                }
-            }
+            while rx.try_recv().is_ok() {}
 
             // Check for new content and log rotation
             let (rotated, had_new) = self.check_file_updates(&mut tracker)?;
@@ -287,16 +1467,14 @@ impl TailProcessor {
                     match key.code {
                         KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
                         KeyCode::Char('q') | KeyCode::Esc => break,
+                        // Fuzzy finder over the buffer
+                        KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            self.find_in_buffer(&tracker)?;
+                            self.render_single_frame(&tracker)?;
+                        }
                         KeyCode::Enter => {
-                            // Add blank margin line
-                            tracker.lines.push_back(String::new());
-                            tracker.raw_lines.push_back(String::new());
-                            while tracker.lines.len() > tracker.max_lines {
-                                tracker.lines.pop_front();
-                            }
-                            while tracker.raw_lines.len() > tracker.max_lines {
-                                tracker.raw_lines.pop_front();
-                            }
+                            // Add blank margin line
+                            tracker.push_rows([String::new()], [String::new()]);
                             self.render_single_frame(&tracker)?;
                         }
                         // Pause/resume
@@ -304,9 +1482,32 @@ impl TailProcessor {
                             tracker.paused = !tracker.paused;
                             self.render_single_frame(&tracker)?;
                         }
+                        // Release/re-arm focus
+                        KeyCode::Char('r') => {
+                            self.toggle_focus();
+                            self.render_single_frame(&tracker)?;
+                        }
                         // Scrollback browser
                         KeyCode::Char('b') => {
-                            self.show_scrollback(&tracker)?;
+                            self.show_scrollback(&tracker, None)?;
+                            self.render_single_frame(&tracker)?;
+                        }
+                        // Open a file:line reference in $EDITOR
+                        KeyCode::Char('E') => {
+                            let (_, th) = size()?;
+                            self.open_reference(tracker.raw_lines.iter().rev().take(th as usize))?;
+                            self.render_single_frame(&tracker)?;
+                        }
+                        // Yank visible lines to the clipboard
+                        KeyCode::Char('y') => {
+                            let (_, th) = size()?;
+                            let skip = tracker.raw_lines.len().saturating_sub(th.saturating_sub(1) as usize);
+                            self.yank(tracker.raw_lines.iter().skip(skip))?;
+                            self.render_single_frame(&tracker)?;
+                        }
+                        // Read the buffer in less, then come back
+                        KeyCode::Char('L') => {
+                            self.open_in_less(&tracker.lines)?;
                             self.render_single_frame(&tracker)?;
                         }
                         // Search
@@ -320,8 +1521,7 @@ impl TailProcessor {
                         }
                         // Clear buffer
                         KeyCode::Char('o') => {
-                            tracker.lines.clear();
-                            tracker.raw_lines.clear();
+                            tracker.clear_buffer();
                             self.render_single_frame(&tracker)?;
                         }
                         // Save buffer
@@ -350,7 +1550,11 @@ impl TailProcessor {
                                 "  Ctrl+C       Emergency exit".to_string(),
                                 "  Enter        Add blank margin line".to_string(),
                                 "  p            Pause/resume".to_string(),
+                                "  r            Release/re-arm --focus".to_string(),
                                 "  b            Scrollback browser".to_string(),
+                                "  E            Open file:line reference in $EDITOR".to_string(),
+                                "  y            Yank visible lines to clipboard".to_string(),
+                                "  L            Read the buffer in less".to_string(),
                                 "  /            Search".to_string(),
                                 "  o            Clear buffer".to_string(),
                                 "  w            Save buffer to file".to_string(),
@@ -390,14 +1594,23 @@ impl TailProcessor {
     fn follow_multiple_files(&mut self, files: &[PathBuf]) -> Result<()> {
         use crossterm::terminal::{enable_raw_mode, disable_raw_mode};
 
-        if let Err(_) = enable_raw_mode() {
-            return self.follow_multiple_files_scroll(files);
+        self.output.flush();
+        // A timestamp-merged stream only makes sense as a single interleaved stream,
+        // and --max-count needs lines printed rather than drawn into panes
+        // as do structured formats, which are for piping rather than watching
+        if self.merge || self.max_skew.is_some() || self.max_count.is_some() || self.top.is_some()
+            || !self.output_formatter.is_text() {
+            return self.follow_files_scroll(files, 5);
+        }
+
+        if enable_raw_mode().is_err() {
+            return self.follow_files_scroll(files, 5);
         }
 
         let mut stdout = io::stdout();
-        if let Err(_) = execute!(stdout, EnterAlternateScreen, Hide) {
+        if execute!(stdout, EnterAlternateScreen, Hide).is_err() {
             let _ = disable_raw_mode();
-            return self.follow_multiple_files_scroll(files);
+            return self.follow_files_scroll(files, 5);
         }
 
         let result = self.follow_multiple_files_panes(files);
@@ -411,59 +1624,26 @@ impl TailProcessor {
         let mut file_trackers: Vec<FileTracker> = Vec::new();
 
         for file_path in files {
-            let file = File::open(file_path)
-                .with_context(|| format!("Failed to open file: {:?}", file_path))?;
-            let pos = file.metadata()?.len();
-            let file_id = get_open_file_id(&file);
-
-            let mut tracker = FileTracker {
-                path: file_path.clone(),
-                file,
-                position: pos,
-                lines: VecDeque::new(),
-                raw_lines: VecDeque::new(),
-                max_lines: self.max_buffer_lines,
-                line_count: 0,
-                last_update: std::time::SystemTime::now(),
-                file_id,
-                paused: false,
-                filter: None,
-                search_term: None,
-            };
-
-            tracker.line_count = self.count_lines_in_file(&tracker.path).unwrap_or(0);
-
-            if let Ok(initial_lines) = self.get_last_n_lines(File::open(file_path)?, 100) {
-                for line in initial_lines {
-                    if self.filter.should_show_line(&line) {
-                        let colored_line = self.colorizer.colorize_line(&line);
-                        tracker.lines.push_back(colored_line);
-                        tracker.raw_lines.push_back(line);
-                    }
-                }
-            }
-
+            let mut tracker = self.open_tracker(file_path, self.max_buffer_lines)?;
+            self.load_initial_lines(&mut tracker, 100);
             file_trackers.push(tracker);
         }
 
         let (tx, rx) = mpsc::channel();
         let mut watcher: RecommendedWatcher = Watcher::new(tx, NotifyConfig::default())?;
 
-        for file_path in files {
-            watcher.watch(file_path, RecursiveMode::NonRecursive)?;
+        for tracker in file_trackers.iter().filter(|t| t.file.is_some()) {
+            watcher.watch(&tracker.path, RecursiveMode::NonRecursive)?;
         }
 
+        self.watch_theme();
+
         // Initial render
         self.render_frame(&file_trackers)?;
 
         loop {
             // Drain all pending watcher events (non-blocking)
-            loop {
-                match rx.try_recv() {
-                    Ok(_) => {}
-                    Err(_) => break,
-                }
-            }
+            while rx.try_recv().is_ok() {}
 
             // Check for new content and log rotation
             let mut needs_render = false;
@@ -489,6 +1669,22 @@ impl TailProcessor {
                     match key.code {
                         KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
                         KeyCode::Char('q') | KeyCode::Esc => break,
+                        // Fuzzy finder over a window's buffer
+                        KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            let names = Self::get_window_names(&file_trackers);
+                            let colors = crate::popup::PopupColors::from_theme(self.colorizer.get_theme());
+                            let chosen = match file_trackers.len() {
+                                1 => Some(0),
+                                _ => match crate::popup::popup_select_window(&names, &colors)? {
+                                    crate::popup::PopupResult::Selected(idx) => Some(idx),
+                                    _ => None,
+                                },
+                            };
+                            if let Some(idx) = chosen {
+                                self.find_in_buffer(&file_trackers[idx])?;
+                            }
+                            self.render_frame(&file_trackers)?;
+                        }
                         KeyCode::Char('h') | KeyCode::F(1) => {
                             self.show_help(&file_trackers)?;
                             self.render_frame(&file_trackers)?;
@@ -502,6 +1698,11 @@ impl TailProcessor {
                             }
                             self.render_frame(&file_trackers)?;
                         }
+                        // Release/re-arm focus
+                        KeyCode::Char('r') => {
+                            self.toggle_focus();
+                            self.render_frame(&file_trackers)?;
+                        }
                         // Pause/resume one window
                         KeyCode::Char('P') => {
                             let names = Self::get_window_names(&file_trackers);
@@ -514,8 +1715,7 @@ impl TailProcessor {
                         // Clear all buffers
                         KeyCode::Char('O') => {
                             for tracker in &mut file_trackers {
-                                tracker.lines.clear();
-                                tracker.raw_lines.clear();
+                                tracker.clear_buffer();
                             }
                             self.render_frame(&file_trackers)?;
                         }
@@ -524,8 +1724,7 @@ impl TailProcessor {
                             let names = Self::get_window_names(&file_trackers);
                             let colors = crate::popup::PopupColors::from_theme(self.colorizer.get_theme());
                             if let crate::popup::PopupResult::Selected(idx) = crate::popup::popup_select_window(&names, &colors)? {
-                                file_trackers[idx].lines.clear();
-                                file_trackers[idx].raw_lines.clear();
+                                file_trackers[idx].clear_buffer();
                             }
                             self.render_frame(&file_trackers)?;
                         }
@@ -534,6 +1733,39 @@ impl TailProcessor {
                             self.show_info_popup(&file_trackers)?;
                             self.render_frame(&file_trackers)?;
                         }
+                        // Per-source statistics
+                        KeyCode::Char('s') => {
+                            self.show_stats_popup(&file_trackers)?;
+                            self.render_frame(&file_trackers)?;
+                        }
+                        // Open a file:line reference from any pane in $EDITOR
+                        KeyCode::Char('E') => {
+                            let (_, th) = size()?;
+                            let rows = th as usize / file_trackers.len().max(1);
+                            self.open_reference(file_trackers.iter().flat_map(|t| t.raw_lines.iter().rev().take(rows)))?;
+                            self.render_frame(&file_trackers)?;
+                        }
+                        // Yank one pane's visible lines to the clipboard
+                        KeyCode::Char('y') => {
+                            let names = Self::get_window_names(&file_trackers);
+                            let colors = crate::popup::PopupColors::from_theme(self.colorizer.get_theme());
+                            if let crate::popup::PopupResult::Selected(idx) = crate::popup::popup_select_window(&names, &colors)? {
+                                let (_, th) = size()?;
+                                let rows = (th as usize / file_trackers.len().max(1)).saturating_sub(1);
+                                let lines = &file_trackers[idx].raw_lines;
+                                self.yank(lines.iter().skip(lines.len().saturating_sub(rows)))?;
+                            }
+                            self.render_frame(&file_trackers)?;
+                        }
+                        // Read one pane's buffer in less
+                        KeyCode::Char('L') => {
+                            let names = Self::get_window_names(&file_trackers);
+                            let colors = crate::popup::PopupColors::from_theme(self.colorizer.get_theme());
+                            if let crate::popup::PopupResult::Selected(idx) = crate::popup::popup_select_window(&names, &colors)? {
+                                self.open_in_less(&file_trackers[idx].lines)?;
+                            }
+                            self.render_frame(&file_trackers)?;
+                        }
                         // Delete window
                         KeyCode::Char('d') => {
                             if file_trackers.len() > 1 {
@@ -565,9 +1797,12 @@ impl TailProcessor {
                                 if let crate::popup::PopupResult::Text(include_str) = include_result {
                                     let exclude_result = crate::popup::popup_input(" Filter ", "Exclude regex (empty=none):", "", &colors)?;
                                     if let crate::popup::PopupResult::Text(exclude_str) = exclude_result {
-                                        let include = if include_str.is_empty() { None } else { Some(include_str) };
-                                        let exclude = if exclude_str.is_empty() { None } else { Some(exclude_str) };
-                                        if let Ok(filter) = LineFilter::new(include, exclude, None) {
+                                        let filter_options = FilterOptions {
+                                            includes: if include_str.is_empty() { Vec::new() } else { vec![include_str] },
+                                            excludes: if exclude_str.is_empty() { Vec::new() } else { vec![exclude_str] },
+                                            ..Default::default()
+                                        };
+                                        if let Ok(filter) = LineFilter::new(filter_options) {
                                             file_trackers[idx].filter = Some(filter);
                                         }
                                     }
@@ -582,33 +1817,8 @@ impl TailProcessor {
                             if let crate::popup::PopupResult::Text(path_str) = result {
                                 let path = PathBuf::from(path_str.trim());
                                 if path.exists() {
-                                    if let Ok(file) = File::open(&path) {
-                                        let pos = file.metadata().map(|m| m.len()).unwrap_or(0);
-                                        let file_id = get_open_file_id(&file);
-                                        let mut tracker = FileTracker {
-                                            path: path.clone(),
-                                            file,
-                                            position: pos,
-                                            lines: VecDeque::new(),
-                                            raw_lines: VecDeque::new(),
-                                            max_lines: self.max_buffer_lines,
-                                            line_count: 0,
-                                            last_update: std::time::SystemTime::now(),
-                                            file_id,
-                                            paused: false,
-                                            filter: None,
-                                            search_term: None,
-                                        };
-                                        tracker.line_count = self.count_lines_in_file(&path).unwrap_or(0);
-                                        if let Ok(initial_lines) = self.get_last_n_lines(File::open(&path)?, 100) {
-                                            for line in initial_lines {
-                                                if self.filter.should_show_line(&line) {
-                                                    let colored_line = self.colorizer.colorize_line(&line);
-                                                    tracker.lines.push_back(colored_line);
-                                                    tracker.raw_lines.push_back(line);
-                                                }
-                                            }
-                                        }
+                                    if let Ok(mut tracker) = self.open_tracker(&path, self.max_buffer_lines) {
+                                        self.load_initial_lines(&mut tracker, 100);
                                         let _ = watcher.watch(&path, RecursiveMode::NonRecursive);
                                         file_trackers.push(tracker);
                                     }
@@ -640,7 +1850,7 @@ impl TailProcessor {
                             let names = Self::get_window_names(&file_trackers);
                             let colors = crate::popup::PopupColors::from_theme(self.colorizer.get_theme());
                             if let crate::popup::PopupResult::Selected(idx) = crate::popup::popup_select_window(&names, &colors)? {
-                                self.show_scrollback(&file_trackers[idx])?;
+                                self.show_scrollback(&file_trackers[idx], None)?;
                             }
                             self.render_frame(&file_trackers)?;
                         }
@@ -768,37 +1978,34 @@ impl TailProcessor {
         let datetime = chrono::DateTime::<chrono::Local>::from(now);
         let time_str = datetime.format("%b %d %H:%M:%S %Y").to_string();
 
-        let mut indicators = String::new();
+        // Drop order when narrow: time, focus term, flags; index, path and count stay
+        let mut status = StatusBar::new().left(format!("{}] {}", index, filepath), 0);
         if tracker.paused {
-            indicators.push_str(" [PAUSED]");
+            status = status.left(" [PAUSED]", 1);
         }
         if tracker.filter.is_some() {
-            indicators.push_str(" [FILTER]");
+            status = status.left(" [FILTER]", 1);
         }
         if tracker.search_term.is_some() {
-            indicators.push_str(" [SEARCH]");
-        }
-
-        let left = format!("{}] {}{}", index, filepath, indicators);
-        let right = format!("{} - {}", tracker.line_count, time_str);
-        let gap = w.saturating_sub(left.len() + right.len());
-        let mut bar = left;
-        if gap > 0 {
-            bar.push_str(&" ".repeat(gap));
-        }
-        bar.push_str(&right);
-        if bar.len() > w { bar.truncate(w); }
-        while bar.len() < w { bar.push(' '); }
-
-        let bar_row = y + height - 1;
-        queue!(
-            buf,
-            MoveTo(0, bar_row),
-            SetBackgroundColor(bg),
-            SetForegroundColor(fg),
-            Print(&bar),
-            ResetColor
-        )?;
+            status = status.left(" [SEARCH]", 1);
+        }
+        if tracker.stdin.as_ref().is_some_and(|stdin| stdin.closed) {
+            status = status.left(" [EOF]", 1);
+        }
+        if let Some(term) = self.focus.as_ref().and_then(FocusFilter::term) {
+            status = status.left(format!(" [FOCUS {}]", term), 2);
+        }
+        if let Some((notice, _)) = self.notice.as_ref().filter(|(_, at)| at.elapsed() < NOTICE_DURATION) {
+            status = status.left(format!(" [{}]", notice), 2);
+        }
+        let bar = status
+            .right(format!(" {}", tracker.line_count), 0)
+            .right(format!(" - {}", time_str), 3)
+            .render(w);
+
+        let bar_row = y + height.saturating_sub(1);
+        queue!(buf, MoveTo(0, bar_row))?;
+        queue_bar(buf, &bar, bg, fg, self.colorizer.colors_enabled())?;
 
         Ok(())
     }
@@ -820,8 +2027,11 @@ impl TailProcessor {
         let mut lines = vec![
             format!("Windows: {}", trackers.len()),
             format!("Buffer limit: {} lines", self.max_buffer_lines),
-            String::new(),
         ];
+        if self.max_memory.is_some() || self.scrollback_limit.is_some() {
+            lines.push(format!("Memory limit: {} KiB per window", self.scrollback_bytes() / 1024));
+        }
+        lines.push(String::new());
         for (i, tracker) in trackers.iter().enumerate() {
             let name = tracker.path.file_name()
                 .and_then(|n| n.to_str())
@@ -838,15 +2048,62 @@ impl TailProcessor {
         crate::popup::popup_info(" Window Info ", &lines, &colors)
     }
 
-    fn show_scrollback(&self, tracker: &FileTracker) -> Result<()> {
+    fn show_stats_popup(&self, trackers: &[FileTracker]) -> Result<()> {
+        use crate::popup::TableCell;
+
+        let colors = crate::popup::PopupColors::from_theme(self.colorizer.get_theme());
+        let number = |n: f64, text: String| TableCell { text, sort: Some(n) };
+        let rows: Vec<Vec<TableCell>> = trackers.iter()
+            .map(|tracker| {
+                let stats = &tracker.stats;
+                let name = tracker.path.file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("unknown")
+                    .to_string();
+                let age = stats.last_line.map(|at| at.elapsed());
+                vec![
+                    TableCell { text: name, sort: None },
+                    number(stats.lines_read as f64, stats.lines_read.to_string()),
+                    number(stats.lines_shown as f64, stats.lines_shown.to_string()),
+                    number(stats.errors as f64, stats.errors.to_string()),
+                    number(
+                        age.map(|a| a.as_secs_f64()).unwrap_or(f64::INFINITY),
+                        age.map(format_duration).unwrap_or_else(|| "-".to_string()),
+                    ),
+                    number(stats.growth_rate(), format!("{:.1}/min", stats.growth_rate())),
+                ]
+            })
+            .collect();
+        crate::popup::popup_table(
+            " Source Statistics ",
+            &["Source", "Read", "Shown", "Errors", "Last line", "Growth"],
+            &rows,
+            &colors,
+        )
+    }
+
+    /// Fuzzy-find a line in a window's buffer and show it in the scrollback browser.
+    fn find_in_buffer(&self, tracker: &FileTracker) -> Result<()> {
+        let colors = crate::popup::PopupColors::from_theme(self.colorizer.get_theme());
+        let lines: Vec<String> = tracker.raw_lines.iter().cloned().collect();
+        if let crate::popup::PopupResult::Selected(idx) = crate::popup::popup_fuzzy(" Find ", &lines, &colors)? {
+            self.show_scrollback(tracker, Some(idx))?;
+        }
+        Ok(())
+    }
+
+    /// Browse a window's buffer, from the bottom or centered on line `at`.
+    fn show_scrollback(&self, tracker: &FileTracker, at: Option<usize>) -> Result<()> {
         let (tw, th) = size()?;
         let total_lines = tracker.raw_lines.len();
         if total_lines == 0 {
             return Ok(());
         }
 
-        let content_h = (th - 2) as usize; // header + footer rows
-        let mut scroll_offset = total_lines.saturating_sub(content_h); // start at bottom
+        let content_h = th.saturating_sub(2) as usize; // header + footer rows
+        let bottom = total_lines.saturating_sub(content_h);
+        let centered = |line: usize| line.saturating_sub(content_h / 2).min(bottom);
+        let mut scroll_offset = at.map_or(bottom, centered);
         let search_re: Option<regex::Regex> = tracker.search_term.as_ref().and_then(|term| {
             regex::RegexBuilder::new(&regex::escape(term))
                 .case_insensitive(true)
@@ -863,10 +2120,12 @@ impl TailProcessor {
             let bar_bg = theme_color_to_ansi256(theme.statusbar_bg.as_ref(), 103);
             let bar_fg = theme_color_to_ansi256(theme.statusbar_fg.as_ref(), 231);
 
-            let header = format!(" Scrollback: {} ({} lines) ", tracker.path.display(), total_lines);
-            let header_padded = format!("{:<width$}", header, width = tw as usize);
-            queue!(buf, MoveTo(0, 0), SetBackgroundColor(bar_bg), SetForegroundColor(bar_fg),
-                Print(&header_padded), ResetColor)?;
+            let header_padded = StatusBar::new()
+                .left(format!(" Scrollback: {}", tracker.path.display()), 0)
+                .left(format!(" ({} lines) ", total_lines), 1)
+                .render(tw as usize);
+            queue!(buf, MoveTo(0, 0))?;
+            queue_bar(&mut buf, &header_padded, bar_bg, bar_fg, self.colorizer.colors_enabled())?;
 
             // Content lines
             for i in 0..content_h {
@@ -874,7 +2133,7 @@ impl TailProcessor {
                 queue!(buf, MoveTo(0, (i + 1) as u16))?;
                 if line_idx < total_lines {
                     let raw_line = &tracker.raw_lines[line_idx];
-                    let colored = self.colorizer.colorize_line(raw_line);
+                    let colored = tracker.lines.get(line_idx).cloned().unwrap_or_default();
 
                     // Highlight search matches
                     let display = if let Some(ref re) = search_re {
@@ -895,13 +2154,19 @@ impl TailProcessor {
             }
 
             // Footer
-            let pos_str = format!(" Line {}-{} of {} | Arrows/PgUp/PgDn to scroll | q to return ",
-                scroll_offset + 1,
-                (scroll_offset + content_h).min(total_lines),
-                total_lines);
-            let footer_padded = format!("{:<width$}", pos_str, width = tw as usize);
-            queue!(buf, MoveTo(0, th - 1), SetBackgroundColor(bar_bg), SetForegroundColor(bar_fg),
-                Print(&footer_padded), ResetColor)?;
+            // Key hints go before the position when the terminal is narrow
+            let footer_padded = StatusBar::new()
+                .left(format!(" Line {}-{} of {}",
+                    scroll_offset + 1,
+                    (scroll_offset + content_h).min(total_lines),
+                    total_lines), 0)
+                .left(" | Arrows/PgUp/PgDn to scroll", 2)
+                .left(" | y to yank", 3)
+                .left(" | ^F to find", 4)
+                .left(" | q to return ", 1)
+                .render(tw as usize);
+            queue!(buf, MoveTo(0, th.saturating_sub(1)))?;
+            queue_bar(&mut buf, &footer_padded, bar_bg, bar_fg, self.colorizer.colors_enabled())?;
 
             queue!(buf, EndSynchronizedUpdate)?;
             let mut stdout = io::stdout().lock();
@@ -915,13 +2180,27 @@ impl TailProcessor {
                     match key.code {
                         KeyCode::Char('q') | KeyCode::Esc => break,
                         KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+                        KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            let colors = crate::popup::PopupColors::from_theme(self.colorizer.get_theme());
+                            let lines: Vec<String> = tracker.raw_lines.iter().cloned().collect();
+                            if let crate::popup::PopupResult::Selected(idx) = crate::popup::popup_fuzzy(" Find ", &lines, &colors)? {
+                                scroll_offset = centered(idx);
+                            }
+                        }
+                        KeyCode::Char('E') => {
+                            self.open_reference(tracker.raw_lines.iter().skip(scroll_offset).take(content_h).rev())?;
+                        }
+                        KeyCode::Char('y') => {
+                            self.yank(tracker.raw_lines.iter().skip(scroll_offset).take(content_h))?;
+                        }
+                        KeyCode::Char('L') => {
+                            self.open_in_less(&tracker.lines)?;
+                        }
                         KeyCode::Up => {
                             scroll_offset = scroll_offset.saturating_sub(1);
                         }
-                        KeyCode::Down => {
-                            if scroll_offset + content_h < total_lines {
-                                scroll_offset += 1;
-                            }
+                        KeyCode::Down if scroll_offset + content_h < total_lines => {
+                            scroll_offset += 1;
                         }
                         KeyCode::PageUp => {
                             scroll_offset = scroll_offset.saturating_sub(content_h);
@@ -958,12 +2237,18 @@ impl TailProcessor {
             "  p            Pause/resume all windows".to_string(),
             "  P            Pause/resume one window".to_string(),
             "  b            Scrollback buffer browser".to_string(),
+            "  E            Open file:line reference in $EDITOR".to_string(),
+            "  y            Yank a window's visible lines to clipboard".to_string(),
+            "  L            Read a window's buffer in less".to_string(),
             "  /            Search in buffer".to_string(),
+            "  Ctrl+F       Fuzzy-find a line in a buffer".to_string(),
             "  i            Window info/stats".to_string(),
+            "  s            Per-source statistics table".to_string(),
             String::new(),
             "FILTERING".to_string(),
             "  f            Set include/exclude filter".to_string(),
             "  e            Clear filter from window".to_string(),
+            "  r            Release/re-arm --focus".to_string(),
             String::new(),
             "WINDOW MANAGEMENT".to_string(),
             "  a            Add new file to monitor".to_string(),
@@ -977,6 +2262,59 @@ impl TailProcessor {
         crate::popup::popup_info(" FuzzyTail Help ", &lines, &colors)
     }
 
+    /// Open a file:line reference from `lines` (most relevant first) in $EDITOR,
+    /// asking which one when there are several.
+    fn open_reference<'a>(&self, lines: impl Iterator<Item = &'a String>) -> Result<()> {
+        let colors = crate::popup::PopupColors::from_theme(self.colorizer.get_theme());
+        let mut references: Vec<crate::editor::SourceRef> = Vec::new();
+        for line in lines {
+            for reference in crate::editor::find_references(line) {
+                if !references.contains(&reference) {
+                    references.push(reference);
+                }
+            }
+        }
+
+        let chosen = match references.len() {
+            0 => {
+                let lines = vec![String::new(), "  No file:line reference to an existing file in view".to_string()];
+                return crate::popup::popup_info(" Open in Editor ", &lines, &colors);
+            }
+            1 => &references[0],
+            _ => {
+                let names: Vec<String> = references.iter().map(|r| r.to_string()).collect();
+                match crate::popup::popup_menu(" Open in Editor ", &names, &colors)? {
+                    crate::popup::PopupResult::Selected(idx) => &references[idx],
+                    _ => return Ok(()),
+                }
+            }
+        };
+        if let Err(e) = crate::editor::open(chosen) {
+            crate::popup::popup_info(" Open in Editor ", &[String::new(), format!("  {:#}", e)], &colors)?;
+        }
+        Ok(())
+    }
+
+    /// Page a buffer with `less -R +G`, reporting failures in a popup.
+    fn open_in_less(&self, lines: &VecDeque<String>) -> Result<()> {
+        if let Err(e) = crate::pager::page(lines, true) {
+            let colors = crate::popup::PopupColors::from_theme(self.colorizer.get_theme());
+            crate::popup::popup_info(" Less ", &[String::new(), format!("  {:#}", e)], &colors)?;
+        }
+        Ok(())
+    }
+
+    /// Copy `lines` to the clipboard and report how it went.
+    fn yank<'a>(&self, lines: impl Iterator<Item = &'a String>) -> Result<()> {
+        let colors = crate::popup::PopupColors::from_theme(self.colorizer.get_theme());
+        let lines: Vec<&str> = lines.map(String::as_str).collect();
+        let message = match crate::clipboard::copy(&lines.join("\n"), self.config.general.clipboard.as_deref()) {
+            Ok(backend) => format!("  Copied {} line(s) via {}", lines.len(), backend.name()),
+            Err(e) => format!("  {:#}", e),
+        };
+        crate::popup::popup_info(" Yank ", &[String::new(), message], &colors)
+    }
+
     fn show_single_pane(&self, tracker: &FileTracker, index: usize) -> Result<()> {
         let (width, height) = size()?;
 
@@ -1000,58 +2338,42 @@ impl TailProcessor {
         Ok(())
     }
 
-    fn follow_multiple_files_scroll(&mut self, files: &[PathBuf]) -> Result<()> {
+    /// Follow files as a plain scrolling stream. With several sources each line is
+    /// prefixed with its file name; a single file streams like `tail -f`.
+    fn follow_files_scroll(&mut self, files: &[PathBuf], initial_lines: usize) -> Result<()> {
+        self.prefix_sources = files.len() > 1;
         let mut file_trackers: Vec<FileTracker> = Vec::new();
-
         for file_path in files {
-            let file = File::open(file_path)
-                .with_context(|| format!("Failed to open file: {:?}", file_path))?;
-            let pos = file.metadata()?.len();
-            let file_id = get_open_file_id(&file);
-
-            let mut tracker = FileTracker {
-                path: file_path.clone(),
-                file,
-                position: pos,
-                lines: VecDeque::new(),
-                raw_lines: VecDeque::new(),
-                max_lines: 10,
-                line_count: 0,
-                last_update: std::time::SystemTime::now(),
-                file_id,
-                paused: false,
-                filter: None,
-                search_term: None,
-            };
-
-            if let Ok(initial_lines) = self.get_last_n_lines(File::open(file_path)?, 5) {
-                for line in initial_lines {
-                    if self.filter.should_show_line(&line) {
-                        let colored_line = self.colorizer.colorize_line(&line);
-                        tracker.lines.push_back(colored_line);
-                    }
-                }
-            }
-
-            file_trackers.push(tracker);
+            file_trackers.push(self.open_tracker(file_path, 10)?);
         }
 
         let (tx, rx) = mpsc::channel();
         let mut watcher: RecommendedWatcher = Watcher::new(tx, NotifyConfig::default())?;
 
-        for file_path in files {
-            watcher.watch(file_path, RecursiveMode::NonRecursive)?;
+        for tracker in file_trackers.iter().filter(|t| t.file.is_some()) {
+            watcher.watch(&tracker.path, RecursiveMode::NonRecursive)?;
         }
 
-        for tracker in &file_trackers {
-            let filename = tracker.path.file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("unknown");
-            println!("==> {} <==", filename);
-            for line in &tracker.lines {
-                println!("{}", line);
+        if self.prefix_sources {
+            for tracker in &file_trackers {
+                let filename = tracker.path.file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("unknown");
+                self.print_header(&tracker.path, filename);
+                // Stdin has no backlog to show
+                if tracker.stdin.is_none() {
+                    let name = tracker.path.display().to_string();
+                    self.show_tail_lines(&tracker.path, initial_lines, Some(&name))?;
+                }
+                if self.output_formatter.is_text() {
+                    self.print_text("");
+                }
+            }
+        } else {
+            if self.verbose && !self.quiet {
+                self.print_text(&format!("==> {} <==", files[0].display()));
             }
-            println!();
+            self.show_tail_lines(&files[0], initial_lines, None)?;
         }
 
         let running = Arc::new(AtomicBool::new(true));
@@ -1059,70 +2381,54 @@ impl TailProcessor {
         let _ = ctrlc::set_handler(move || {
             r.store(false, Ordering::SeqCst);
         });
+        // SIGTERM too, so a service manager stopping ft still gets a clean exit;
+        // SIGHUP is left to the theme watcher
+        let terminated = Arc::new(AtomicBool::new(false));
+        #[cfg(unix)]
+        {
+            let _ = signal_hook::flag::register(signal_hook::consts::SIGTERM, terminated.clone());
+        }
 
-        while running.load(Ordering::SeqCst) {
-            for tracker in &mut file_trackers {
-                let filename = tracker.path.file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("unknown")
-                    .to_string();
-
-                // Check for log rotation
-                let mut was_rotated = false;
-                if let (Some(ref open_id), Some(path_id)) = (&tracker.file_id, get_file_id(&tracker.path)) {
-                    if *open_id != path_id {
-                        // Drain remaining data from old (rotated) file
-                        let old_size = tracker.file.metadata().map(|m| m.len()).unwrap_or(tracker.position);
-                        if old_size > tracker.position {
-                            tracker.file.seek(SeekFrom::Start(tracker.position))?;
-                            let mut reader = BufReader::with_capacity(self.buffer_size, &tracker.file);
-                            let mut line = String::new();
-                            while reader.read_line(&mut line)? > 0 {
-                                let clean_line = line.trim_end().to_string();
-                                if self.filter.should_show_line(&clean_line) {
-                                    let colored_line = self.colorizer.colorize_line(&clean_line);
-                                    println!("[{}] {}", filename, colored_line);
-                                }
-                                line.clear();
-                            }
-                            tracker.position = old_size;
-                        }
+        let mut merger = self.max_skew.map(SkewMerger::new);
+        self.watch_theme();
 
-                        // Reopen the new file
-                        if let Ok(new_file) = File::open(&tracker.path) {
-                            tracker.file = new_file;
-                            tracker.position = 0;
-                            tracker.file_id = get_open_file_id(&tracker.file);
-                            let _ = watcher.unwatch(&tracker.path);
-                            let _ = watcher.watch(&tracker.path, RecursiveMode::NonRecursive);
-                            was_rotated = true;
-                        }
+        while running.load(Ordering::SeqCst) && !terminated.load(Ordering::SeqCst) && !self.limit_reached() {
+            self.apply_control();
+            if let Some(message) = self.reload_theme() {
+                eprintln!("ft: {}", message);
+            }
+            for (source, tracker) in file_trackers.iter_mut().enumerate() {
+                let update = self.read_source(tracker)?;
+                if let Some(rate) = self.rate.as_mut() {
+                    rate.observe(&update.lines);
+                }
+                if update.rotated {
+                    let _ = watcher.unwatch(&tracker.path);
+                    let _ = watcher.watch(&tracker.path, RecursiveMode::NonRecursive);
+                }
+                for line in update.lines {
+                    if let Some(record) = group_line(&mut tracker.grouper, line) {
+                        self.emit_scroll_line(merger.as_mut(), source, tracker, record);
                     }
                 }
 
-                let current_size = tracker.file.metadata()?.len();
-
-                if current_size > tracker.position {
-                    tracker.file.seek(SeekFrom::Start(tracker.position))?;
-                    let mut reader = BufReader::with_capacity(self.buffer_size, &tracker.file);
-
-                    let mut line = String::new();
-                    while reader.read_line(&mut line)? > 0 {
-                        let clean_line = line.trim_end().to_string();
-                        if self.filter.should_show_line(&clean_line) {
-                            let colored_line = self.colorizer.colorize_line(&clean_line);
-                            println!("[{}] {}", filename, colored_line);
-                        }
-                        line.clear();
-                    }
+                if let Some(record) = tracker.grouper.as_mut().and_then(MultilineGrouper::flush_if_idle) {
+                    self.emit_scroll_line(merger.as_mut(), source, tracker, record);
+                }
+                if let Some(line) = tracker.squasher.as_mut().and_then(RepeatSquasher::flush_if_idle) {
+                    self.throttle_scroll_line(merger.as_mut(), source, tracker, line);
+                }
+                if let Some(summary) = tracker.throttle.as_mut().and_then(Throttle::flush_if_idle) {
+                    self.queue_scroll_line(merger.as_mut(), source, &tracker.path, summary);
+                }
+            }
 
-                    tracker.position = current_size;
-                } else if current_size < tracker.position && !was_rotated {
-                    // File truncated in place
-                    tracker.position = 0;
-                    tracker.file.seek(SeekFrom::Start(0))?;
+            if let Some(merger) = merger.as_mut() {
+                for (source, line) in merger.drain_ready() {
+                    self.print_merged_line(&file_trackers[source].path, &line);
                 }
             }
+            self.end_frame();
 
             match rx.try_recv() {
                 Ok(_) => {}
@@ -1132,9 +2438,77 @@ impl TailProcessor {
             }
         }
 
+        for (source, tracker) in file_trackers.iter_mut().enumerate() {
+            if let Some(record) = tracker.grouper.as_mut().and_then(MultilineGrouper::flush) {
+                self.emit_scroll_line(merger.as_mut(), source, tracker, record);
+            }
+            if let Some(line) = tracker.squasher.as_mut().and_then(RepeatSquasher::flush) {
+                self.throttle_scroll_line(merger.as_mut(), source, tracker, line);
+            }
+            if let Some(summary) = tracker.throttle.as_mut().and_then(Throttle::flush) {
+                self.queue_scroll_line(merger.as_mut(), source, &tracker.path, summary);
+            }
+        }
+
+        if let Some(merger) = merger.as_mut() {
+            for (source, line) in merger.drain_all() {
+                self.print_merged_line(&file_trackers[source].path, &line);
+            }
+        }
+
         Ok(())
     }
 
+    /// Filter and squash a line from `tracker` in scroll mode, then print or queue it.
+    fn emit_scroll_line(&mut self, merger: Option<&mut SkewMerger>, source: usize, tracker: &mut FileTracker, line: String) {
+        if !self.admits(&tracker.path, None, &line) {
+            return;
+        }
+        if let Some(line) = squash_line(&mut tracker.squasher, line) {
+            self.throttle_scroll_line(merger, source, tracker, line);
+        }
+    }
+
+    /// Apply --sample and --max-rate to a scroll-mode line, then print or queue what survives.
+    fn throttle_scroll_line(&mut self, mut merger: Option<&mut SkewMerger>, source: usize, tracker: &mut FileTracker, line: String) {
+        for line in throttle_line(&mut tracker.throttle, line) {
+            self.queue_scroll_line(merger.as_deref_mut(), source, &tracker.path, line);
+        }
+    }
+
+    /// Print a line in scroll mode, or queue it in the skew merger when --max-skew is set.
+    fn queue_scroll_line(&mut self, merger: Option<&mut SkewMerger>, source: usize, path: &Path, line: String) {
+        match merger {
+            Some(merger) => {
+                let parsed = self.log_parser.parse_timestamp(&line);
+                merger.push(source, parsed, line);
+            }
+            None => self.print_merged_line(path, &line),
+        }
+    }
+
+    /// Print a line from one of several sources: after a `[name]` prefix as text, or named
+    /// in the record's `source` field in structured formats, which a prefix would break.
+    fn print_merged_line(&mut self, path: &Path, line: &str) {
+        let colored_line = self.render_line(path, line);
+        if !self.output_formatter.is_text() {
+            let source = self.prefix_sources.then(|| path.display().to_string());
+            self.print_formatted(path, source.as_deref(), line, &colored_line);
+        } else if self.prefix_sources {
+            let filename = path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown");
+            let prefix = self.colorizer.paint_source(path, &format!("[{}]", filename));
+            self.forward(path, line);
+            if !self.count_line(path, line) {
+                let position = self.positions.as_ref().and_then(|positions| positions.take(path, line));
+                self.print_line(&format!("{} {}{}", prefix, self.position_prefix(position), colored_line));
+            }
+        } else {
+            self.print_formatted(path, None, line, &colored_line);
+        }
+    }
+
     fn count_lines_in_file(&self, path: &PathBuf) -> Result<usize> {
         let file = File::open(path)?;
         let reader = BufReader::new(file);
@@ -1149,94 +2523,161 @@ impl TailProcessor {
             return Ok((false, false));
         }
 
-        let mut rotated = false;
+        self.apply_control();
+        let reloaded = self.reload_theme();
         let old_line_count = tracker.line_count;
+        let update = self.read_source(tracker)?;
+        if let Some(rate) = self.rate.as_mut() {
+            rate.observe(&update.lines);
+        }
+
+        if update.truncated {
+            // File truncated in place (e.g., logrotate copytruncate)
+            tracker.clear_buffer();
+            tracker.line_count = 0;
+            tracker.grouper = self.multiline.clone();
+            tracker.squasher = self.squash.clone();
+            tracker.throttle = self.throttle.clone();
+        }
+
+        for line in update.lines {
+            tracker.stats.observe(&line);
+            if let Some(record) = group_line(&mut tracker.grouper, line) {
+                self.append_record(tracker, record);
+            }
+        }
+
+        if let Some(record) = tracker.grouper.as_mut().and_then(MultilineGrouper::flush_if_idle) {
+            self.append_record(tracker, record);
+        }
+        if let Some(record) = tracker.squasher.as_mut().and_then(RepeatSquasher::flush_if_idle) {
+            self.throttle_record(tracker, record);
+        }
+        if let Some(summary) = tracker.throttle.as_mut().and_then(Throttle::flush_if_idle) {
+            self.display_record(tracker, summary);
+        }
+
+        // A reload notice needs a redraw to be seen
+        let notice = reloaded.is_some();
+        if let Some(message) = reloaded {
+            // Regex errors span lines; a status bar has one
+            let message = message.split_whitespace().collect::<Vec<_>>().join(" ");
+            self.notice = Some((message, std::time::Instant::now()));
+        }
+        Ok((update.rotated, tracker.line_count != old_line_count || notice))
+    }
+
+    /// Read whatever a source produced since the last call, following log
+    /// rotation (the path now names a different file) and in-place truncation.
+    fn read_source(&self, tracker: &mut FileTracker) -> Result<SourceUpdate> {
+        let mut update = SourceUpdate::default();
+
+        if let Some(stdin) = tracker.stdin.as_mut() {
+            loop {
+                match stdin.lines.try_recv() {
+                    Ok(line) => {
+                        let bytes = line.len() + 1;
+                        let line = self.clean_line(line);
+                        if let Some(positions) = &self.positions {
+                            positions.read(&tracker.path, None, &line, bytes);
+                        }
+                        update.lines.push(line);
+                    }
+                    Err(mpsc::TryRecvError::Empty) => break,
+                    Err(mpsc::TryRecvError::Disconnected) => {
+                        stdin.closed = true;
+                        break;
+                    }
+                }
+            }
+            self.run_source_hooks(&tracker.path, &update);
+            return Ok(update);
+        }
+
+        let Some(file) = tracker.file.as_mut() else {
+            return Ok(update);
+        };
 
         // Check for log rotation: file at path has different inode than our open handle
-        if let (Some(ref open_id), Some(path_id)) = (&tracker.file_id, get_file_id(&tracker.path)) {
-            if *open_id != path_id {
+        if let (Some(open_id), Some(path_id)) = (tracker.file_id, get_file_id(&tracker.path)) {
+            if open_id != path_id {
                 // Drain remaining data from old (rotated) file before switching
-                let old_size = tracker.file.metadata().map(|m| m.len()).unwrap_or(tracker.position);
+                let old_size = file.metadata().map(|m| m.len()).unwrap_or(tracker.position);
                 if old_size > tracker.position {
-                    tracker.file.seek(SeekFrom::Start(tracker.position))?;
-                    let mut reader = BufReader::with_capacity(self.buffer_size, &tracker.file);
-                    let mut line = String::new();
-                    while reader.read_line(&mut line)? > 0 {
-                        let clean_line = line.trim_end().to_string();
-                        let active_filter = tracker.filter.as_ref().unwrap_or(&self.filter);
-                        if active_filter.should_show_line(&clean_line) {
-                            let colored_line = self.colorizer.colorize_line(&clean_line);
-                            tracker.lines.push_back(colored_line);
-                            tracker.raw_lines.push_back(clean_line);
-                            tracker.line_count += 1;
-                            tracker.last_update = std::time::SystemTime::now();
-                            while tracker.lines.len() > tracker.max_lines {
-                                tracker.lines.pop_front();
-                            }
-                            while tracker.raw_lines.len() > tracker.max_lines {
-                                tracker.raw_lines.pop_front();
-                            }
-                        }
-                        line.clear();
-                    }
+                    self.read_lines_from(&tracker.path, file, tracker.position, &mut update.lines)?;
                     tracker.position = old_size;
                 }
 
                 // Reopen the new file at the same path
                 match File::open(&tracker.path) {
                     Ok(new_file) => {
-                        tracker.file = new_file;
+                        *file = new_file;
                         tracker.position = 0;
-                        tracker.file_id = get_open_file_id(&tracker.file);
-                        rotated = true;
+                        tracker.file_id = get_open_file_id(file);
+                        update.rotated = true;
                         // Fall through to read new content below
                     }
                     Err(_) => {
                         // New file not yet created (brief window during rotation)
-                        return Ok((false, false));
+                        return Ok(update);
                     }
                 }
             }
         }
 
-        let current_size = tracker.file.metadata()?.len();
+        let current_size = file.metadata()?.len();
 
         if current_size > tracker.position {
-            tracker.file.seek(SeekFrom::Start(tracker.position))?;
-            let mut reader = BufReader::with_capacity(self.buffer_size, &tracker.file);
-
-            let mut line = String::new();
-            while reader.read_line(&mut line)? > 0 {
-                let clean_line = line.trim_end().to_string();
-                let active_filter = tracker.filter.as_ref().unwrap_or(&self.filter);
-                if active_filter.should_show_line(&clean_line) {
-                    let colored_line = self.colorizer.colorize_line(&clean_line);
-                    tracker.lines.push_back(colored_line);
-                    tracker.raw_lines.push_back(clean_line);
-                    tracker.line_count += 1;
-                    tracker.last_update = std::time::SystemTime::now();
-
-                    while tracker.lines.len() > tracker.max_lines {
-                        tracker.lines.pop_front();
-                    }
-                    while tracker.raw_lines.len() > tracker.max_lines {
-                        tracker.raw_lines.pop_front();
-                    }
-                }
-                line.clear();
-            }
-
+            self.read_lines_from(&tracker.path, file, tracker.position, &mut update.lines)?;
             tracker.position = current_size;
-        } else if current_size < tracker.position && !rotated {
-            // File truncated in place (e.g., logrotate copytruncate)
+        } else if current_size < tracker.position && !update.rotated {
             tracker.position = 0;
-            tracker.lines.clear();
-            tracker.raw_lines.clear();
-            tracker.line_count = 0;
-            tracker.file.seek(SeekFrom::Start(0))?;
+            file.seek(SeekFrom::Start(0))?;
+            update.truncated = true;
+        }
+
+        self.run_source_hooks(&tracker.path, &update);
+        Ok(update)
+    }
+
+    /// Fire `on_rotate` and `on_alert` hooks, alert rules, notifications and the bell for
+    /// what a read of `path` turned up.
+    fn run_source_hooks(&self, path: &Path, update: &SourceUpdate) {
+        if let Some(alerts) = &self.alerts {
+            alerts.check(path, &update.lines);
+        }
+        if let Some(notifier) = &self.notifier {
+            notifier.check(path, &update.lines);
+        }
+        if let Some(bell) = &self.bell {
+            bell.check(&update.lines);
         }
+        let Some(hooks) = &self.hooks else {
+            return;
+        };
+        if update.rotated {
+            hooks.rotate(path, "rotated");
+        } else if update.truncated {
+            hooks.rotate(path, "truncated");
+        }
+        hooks.check_alert(path, &update.lines);
+    }
 
-        Ok((rotated, tracker.line_count != old_line_count))
+    fn read_lines_from(&self, path: &Path, file: &mut File, position: u64, lines: &mut Vec<String>) -> Result<()> {
+        file.seek(SeekFrom::Start(position))?;
+        let mut reader = BufReader::with_capacity(self.buffer_size, &*file);
+        let mut line = String::new();
+        let mut offset = position;
+        while reader.read_line(&mut line)? > 0 {
+            let text = self.clean_line(line.trim_end().to_string());
+            if let Some(positions) = &self.positions {
+                positions.read(path, Some(offset), &text, line.len());
+            }
+            offset += line.len() as u64;
+            lines.push(text);
+            line.clear();
+        }
+        Ok(())
     }
 
     pub fn show_default_logs(&mut self, lines: usize) -> Result<()> {
@@ -1248,8 +2689,8 @@ impl TailProcessor {
             "/var/log/dmesg",
         ];
 
-        println!("ft - No files specified. Showing available system logs:");
-        println!();
+        self.print_text("ft - No files specified. Showing available system logs:");
+        self.print_text("");
 
         let mut found_logs = Vec::new();
         for log_path in &default_logs {
@@ -1264,33 +2705,39 @@ impl TailProcessor {
         }
 
         if found_logs.is_empty() {
-            println!("No accessible log files found. Try:");
-            println!("  ft /var/log/syslog     # System logs");
-            println!("  ft -f myapp.log        # Follow your application log");
-            println!("  echo 'test' | ft       # Pipe data to ft");
-            println!("  ft --help              # See all options");
+            self.print_text("No accessible log files found. Run `ft examples` to get started.");
             return Ok(());
         }
 
         let log_file = &found_logs[0];
-        println!("Showing last {} lines from: {}", lines, log_file.display());
-        println!("  Tip: ft {} -f  to follow this log", log_file.display());
-        println!();
+        self.print_text(&format!("Showing last {} lines from: {}", lines, log_file.display()));
+        self.print_text("");
 
-        self.show_tail_lines(log_file, lines)?;
+        self.show_tail_lines(log_file, lines, None)?;
 
         if found_logs.len() > 1 {
-            println!();
-            println!("Other available logs:");
+            self.print_text("");
+            self.print_text("Other available logs:");
             for log in &found_logs[1..] {
-                println!("  ft {}", log.display());
+                self.print_text(&format!("  ft {}", log.display()));
             }
         }
+        self.print_text("");
+        self.print_text("More ways to use ft: ft examples");
 
         Ok(())
     }
 }
 
+/// `TailProcessor::clean_line`, for threads that read lines on their own.
+fn clean_line(line: String, strip_ansi: bool, redactor: Option<&Redactor>) -> String {
+    let line = if strip_ansi && line.contains('\x1b') { ansi::strip(&line).into_owned() } else { line };
+    match redactor {
+        Some(redactor) => redactor.redact(line),
+        None => line,
+    }
+}
+
 /// Get the filesystem identity (device, inode) of a file path for rotation detection.
 #[cfg(unix)]
 fn get_file_id(path: &Path) -> Option<(u64, u64)> {
@@ -1315,135 +2762,73 @@ fn get_open_file_id(_file: &File) -> Option<(u64, u64)> {
     None
 }
 
+/// Draw a status bar at the cursor. Without colors it's drawn in reverse video so it
+/// still stands out from the lines around it.
+fn queue_bar(buf: &mut Vec<u8>, text: &str, bg: Color, fg: Color, color: bool) -> io::Result<()> {
+    if color {
+        queue!(buf, SetBackgroundColor(bg), SetForegroundColor(fg), Print(text), ResetColor)
+    } else {
+        queue!(buf, SetAttribute(Attribute::Reverse), Print(text), SetAttribute(Attribute::NoReverse))
+    }
+}
+
 /// Convert theme Color to crossterm AnsiValue (256-color).
 /// Always uses AnsiValue for maximum terminal compatibility.
 pub(crate) fn theme_color_to_ansi256(c: Option<&crate::theme::Color>, default: u8) -> Color {
-    match c {
-        Some(crate::theme::Color::Xterm256(n)) => Color::AnsiValue(*n),
-        Some(crate::theme::Color::TrueColor { r, g, b }) => {
-            // Approximate RGB to nearest xterm-256 color
-            Color::AnsiValue(rgb_to_xterm256(*r, *g, *b))
-        }
-        None => Color::AnsiValue(default),
+    match c.map(|c| c.downgrade(ColorDepth::Ansi256)) {
+        Some(crate::theme::Color::Xterm256(n) | crate::theme::Color::Ansi16(n)) => Color::AnsiValue(n),
+        Some(crate::theme::Color::TrueColor { .. }) | None => Color::AnsiValue(default),
     }
 }
 
-/// Approximate an RGB color to the nearest xterm-256 color (16-231 cube).
-pub(crate) fn rgb_to_xterm256(r: u8, g: u8, b: u8) -> u8 {
-    let ri = ((r as u16 * 5 + 127) / 255) as u8;
-    let gi = ((g as u16 * 5 + 127) / 255) as u8;
-    let bi = ((b as u16 * 5 + 127) / 255) as u8;
-    16 + 36 * ri + 6 * gi + bi
-}
-
-/// Truncate an ANSI-colored string to `max_width` visible chars, then pad with spaces
-/// to exactly `max_width`. No Clear escape codes needed — full overwrite.
 /// Highlight search matches in a colored line by cross-referencing the raw line.
 /// Inserts reverse-video ANSI codes around matched portions.
-fn highlight_search_matches(colored: &str, raw: &str, re: &regex::Regex) -> String {
-    // Find match positions in the raw line
-    let match_ranges: Vec<(usize, usize)> = re.find_iter(raw).map(|m| (m.start(), m.end())).collect();
-    if match_ranges.is_empty() {
-        return colored.to_string();
-    }
-
-    // Walk through the colored string, mapping visible character positions to raw positions
-    let mut result = String::with_capacity(colored.len() + match_ranges.len() * 10);
-    let mut visible_pos = 0usize;
-    let mut in_escape = false;
-    let mut in_highlight = false;
-
-    for ch in colored.chars() {
-        if in_escape {
-            result.push(ch);
-            if ch.is_ascii_alphabetic() {
-                in_escape = false;
-            }
-            continue;
-        }
-
-        if ch == '\x1b' {
-            in_escape = true;
-            result.push(ch);
-            continue;
-        }
-
-        if ch.is_control() {
-            result.push(ch);
-            continue;
-        }
-
-        // Check if this visible position should be highlighted
-        let should_highlight = match_ranges.iter().any(|(s, e)| visible_pos >= *s && visible_pos < *e);
+pub(crate) fn highlight_search_matches(colored: &str, raw: &str, re: &regex::Regex) -> String {
+    overlay_matches(colored, raw, std::slice::from_ref(re), "\x1b[7m")
+}
 
-        if should_highlight && !in_highlight {
-            result.push_str("\x1b[7m"); // reverse video on
-            in_highlight = true;
-        } else if !should_highlight && in_highlight {
-            result.push_str("\x1b[27m"); // reverse video off
-            in_highlight = false;
-        }
+/// `-` as a file argument means stdin.
+fn is_stdin_path(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
 
-        result.push(ch);
-        visible_pos += 1;
+/// Feed a line through a multiline grouper, if any, yielding completed records.
+fn group_line(grouper: &mut Option<MultilineGrouper>, line: String) -> Option<String> {
+    match grouper {
+        Some(grouper) => grouper.push(line),
+        None => Some(line),
     }
+}
 
-    if in_highlight {
-        result.push_str("\x1b[27m");
+/// Feed a line through --sample/--max-rate, if set, yielding lines ready to show.
+fn throttle_line(throttle: &mut Option<Throttle>, line: String) -> Vec<String> {
+    match throttle {
+        Some(throttle) => throttle.push(line),
+        None => vec![line],
     }
-
-    result
 }
 
-pub(crate) fn pad_ansi(s: &str, max_width: usize) -> String {
-    let mut result = String::with_capacity(s.len() + max_width);
-    let mut visible = 0usize;
-    let mut in_escape = false;
-
-    for ch in s.chars() {
-        if in_escape {
-            result.push(ch);
-            if ch.is_ascii_alphabetic() {
-                in_escape = false;
-            }
-            continue;
-        }
-
-        if ch == '\x1b' {
-            if visible >= max_width {
-                break;
-            }
-            in_escape = true;
-            result.push(ch);
-            continue;
-        }
+/// Feed a line through a repeat squasher, if any, yielding lines ready to show.
+fn squash_line(squasher: &mut Option<RepeatSquasher>, line: String) -> Option<String> {
+    match squasher {
+        Some(squasher) => squasher.push(line),
+        None => Some(line),
+    }
+}
 
-        if ch == '\t' {
-            let spaces = 4 - (visible % 4);
-            for _ in 0..spaces {
-                if visible >= max_width { break; }
-                result.push(' ');
-                visible += 1;
+/// Split a rendered multiline record into display rows, re-opening the color
+/// active at the end of each row so continuation lines keep their styling.
+fn split_rendered_rows(rendered: &str) -> Vec<String> {
+    let mut rows = Vec::new();
+    let mut active = String::new();
+    for row in rendered.split('\n') {
+        rows.push(format!("{}{}", active, row));
+        if let Some(start) = row.rfind("\x1b[") {
+            if let Some(len) = row[start..].find('m') {
+                let seq = &row[start..start + len + 1];
+                active = if seq == "\x1b[0m" || seq == "\x1b[m" { String::new() } else { seq.to_string() };
             }
-            continue;
-        }
-
-        if visible >= max_width {
-            break;
-        }
-
-        if !ch.is_control() {
-            result.push(ch);
-            visible += 1;
         }
     }
-
-    // Reset color, then pad remaining width with spaces
-    result.push_str("\x1b[0m");
-    while visible < max_width {
-        result.push(' ');
-        visible += 1;
-    }
-
-    result
+    rows
 }
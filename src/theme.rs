@@ -1,29 +1,345 @@
 use anyhow::{Context, Result, anyhow};
 use regex::Regex;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::duration::parse_duration;
+use crate::filter::LogLevel;
 
 #[derive(Debug, Clone)]
 pub struct Theme {
-    pub name: String,
     pub base_color: Option<u8>,
     pub statusbar_bg: Option<Color>,
     pub statusbar_fg: Option<Color>,
     pub line_rules: Vec<ColorRule>,
     pub word_rules: Vec<ColorRule>,
+    /// `level: ERROR = #ff5555`: colors for the level keyword of lines detected at each level
+    pub level_rules: Vec<LevelRule>,
+    pub hash_fields: Vec<HashFieldRule>,
+    pub hash_palette: Vec<Color>,
+    /// Colors for file names in headers and `[name]` prefixes, picked by hashing the path
+    pub source_palette: Vec<Color>,
+    pub escalation_window: Option<Duration>,
+    pub escalation_rules: Vec<EscalationRule>,
+    /// How `--highlight-matches` marks include-pattern matches (`match_style:bold,reverse`)
+    pub match_style: Attributes,
+    /// How `--emphasize` marks whole lines at or above its level (`emphasis_style: bold on 52`)
+    pub emphasis_style: Option<Style>,
+    /// `age: 1m = 114`: `--age-colors` styles for timestamps, by how long ago they were
+    pub age_rules: Vec<AgeRule>,
+    /// `smart_highlight: on`: color common tokens (IPs, URLs, UUIDs...) after the theme's own rules
+    pub smart_highlight: Option<bool>,
+    /// `--json-view` token styles from `json_key:`, `json_string:`, `json_number:` and `json_literal:`
+    pub json_styles: JsonStyles,
+}
+
+/// IPv6, full or with ::
+pub const IPV6_PATTERN: &str = r"\b(?:[0-9a-fA-F]{1,4}:){7}[0-9a-fA-F]{1,4}\b|\b(?:[0-9a-fA-F]{1,4}:){1,6}:[0-9a-fA-F]{1,4}(?::[0-9a-fA-F]{1,4})*\b";
+/// IPv4, with an optional port
+pub const IPV4_PATTERN: &str = r"\b(?:(?:25[0-5]|2[0-4][0-9]|1?[0-9]?[0-9])\.){3}(?:25[0-5]|2[0-4][0-9]|1?[0-9]?[0-9])(?::[0-9]{1,5})?\b";
+
+/// Tokens `smart_highlight` colors, tried after the theme's word rules so a theme's
+/// own pattern for any of them wins. URLs come before IPs so an IP inside one stays part of it.
+const SMART_RULES: &[(&str, &str)] = &[
+    // URLs
+    (r#"\b(?:https?|ftp|wss?)://[^\s"'<>]+"#, "underline 75"),
+    // UUIDs
+    (r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b", "141"),
+    // SHA-256, SHA-1 and MD5 hashes, and 0x numbers
+    (r"\b(?:[0-9a-fA-F]{64}|[0-9a-fA-F]{40}|[0-9a-fA-F]{32}|0x[0-9a-fA-F]+)\b", "180"),
+    (IPV6_PATTERN, "117"),
+    (IPV4_PATTERN, "117"),
+    // Byte sizes: 512B, 1.5 KiB, 20MB, 300 bytes
+    (r"\b[0-9]+(?:\.[0-9]+)? ?(?:[kKMGTP]i?B|B|bytes?)\b", "216"),
+    // Durations: 250ms, 1.5s, 1h30m
+    (r"\b(?:[0-9]+(?:\.[0-9]+)?(?:ns|us|µs|ms|s|m|h|d))+\b", "150"),
+];
+
+/// Styles for the parts of a JSON line under `--json-view`; unset ones use ft's defaults.
+#[derive(Debug, Clone, Default)]
+pub struct JsonStyles {
+    pub key: Option<Style>,
+    pub string: Option<Style>,
+    pub number: Option<Style>,
+    /// `true`, `false` and `null`
+    pub literal: Option<Style>,
+}
+
+impl JsonStyles {
+    /// The style a `json_*:` setting sets, by its name without the prefix.
+    fn slot(&mut self, name: &str) -> Option<&mut Option<Style>> {
+        match name {
+            "key" => Some(&mut self.key),
+            "string" => Some(&mut self.string),
+            "number" => Some(&mut self.number),
+            "literal" => Some(&mut self.literal),
+            _ => None,
+        }
+    }
+
+    /// Parse a `json_NAME: STYLE` line into its slot; `Ok(false)` when it isn't one.
+    fn parse_line(&mut self, line: &str) -> Result<bool> {
+        let Some((name, value)) = line.strip_prefix("json_").and_then(|rest| rest.split_once(':')) else {
+            return Ok(false);
+        };
+        let slot = self.slot(name.trim())
+            .ok_or_else(|| anyhow!("Unknown setting json_{}: expected json_key, json_string, json_number or json_literal", name.trim()))?;
+        *slot = Some(Style::parse(value.trim())?);
+        Ok(true)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct ColorRule {
     pub pattern: Regex,
-    pub color: Color,
+    pub style: Style,
+    /// Color only this capture group of each match (`word:(user):(\S+) = 2:#8aadf4`)
+    pub group: Option<usize>,
     pub original_pattern: String,
 }
 
+impl ColorRule {
+    /// Parse a `REGEX=COLOR` rule given outside a theme file, e.g. `--highlight 'req-42=#ff5555'`.
+    pub fn from_spec(spec: &str) -> Result<Self> {
+        Theme::parse_rule(spec, "highlight")?
+            .ok_or_else(|| anyhow!("Invalid highlight rule, expected REGEX=COLOR: {}", spec))
+    }
+}
+
+/// A field whose value is colored by hashing it into the theme's hash palette,
+/// so the same value (e.g. a request id) always gets the same color.
+#[derive(Debug, Clone)]
+pub struct HashFieldRule {
+    pub pattern: Regex,
+}
+
+impl HashFieldRule {
+    /// Build a rule matching `field=value`, `field: value` and `"field":"value"`.
+    /// Capture group 1 is the value.
+    pub fn new(field: &str) -> Result<Self> {
+        let field = field.trim();
+        if field.is_empty() {
+            return Err(anyhow!("Hash field name cannot be empty"));
+        }
+        let pattern = Regex::new(&format!(
+            r#"(?:^|[^A-Za-z0-9_.-])"?{}"?\s*[=:]\s*"?([^\s",;}}\]]+)"#,
+            regex::escape(field)
+        ))
+        .with_context(|| format!("Invalid hash field name: {}", field))?;
+
+        Ok(Self { pattern })
+    }
+}
+
+/// Colors the level keyword (`ERROR`, `err`...) of lines whose detected level is `level`.
+#[derive(Debug, Clone)]
+pub struct LevelRule {
+    pub level: LogLevel,
+    pub rule: ColorRule,
+}
+
+impl LevelRule {
+    /// Parse `LEVEL = STYLE`, e.g. `ERROR = #ff5555` or `WARN = bold 214`.
+    fn parse(value: &str) -> Result<Self> {
+        let (name, style) = value.split_once('=')
+            .ok_or_else(|| anyhow!("Invalid level rule, expected LEVEL=STYLE: {}", value))?;
+        let name = name.trim();
+        let level = LogLevel::from_name(name)
+            .ok_or_else(|| anyhow!("Unknown level '{}': expected EMERG, ALERT, CRIT, ERROR, WARN, NOTICE, INFO or DEBUG", name))?;
+        let style = Style::parse(style.trim())
+            .with_context(|| format!("Invalid style in level rule: {}", style.trim()))?;
+        let pattern = Regex::new(&format!(r"(?i)\b(?:{})\b", level.keywords().join("|")))?;
+        Ok(Self { level, rule: ColorRule { pattern, style, group: None, original_pattern: name.to_string() } })
+    }
+}
+
+/// Burst threshold: once this many lines of one severity land within the
+/// escalation window, their badge uses `color`.
+#[derive(Debug, Clone)]
+pub struct EscalationRule {
+    pub threshold: usize,
+    pub color: Color,
+}
+
+/// Timestamps less than `max_age` old (any age when None, from `age: older = ...`)
+/// are styled with `style`; the rule with the smallest `max_age` that fits wins.
+#[derive(Debug, Clone)]
+pub struct AgeRule {
+    pub max_age: Option<Duration>,
+    pub style: Style,
+}
+
+impl AgeRule {
+    /// `1m = 114` or `older = 244`
+    pub fn parse(value: &str) -> Result<Self> {
+        let (age, style) = value.split_once('=')
+            .ok_or_else(|| anyhow!("Invalid age rule, expected AGE=STYLE (e.g. 10m=229 or older=244): {}", value))?;
+        let max_age = match age.trim() {
+            "older" => None,
+            age => Some(parse_duration(age)?),
+        };
+        Ok(Self { max_age, style: Style::parse(style.trim())? })
+    }
+}
+
+/// Text attributes that emphasize text without changing its colors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Attributes {
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub reverse: bool,
+}
+
+impl Attributes {
+    /// Parse a comma- or space-separated list such as `bold,reverse`.
+    pub fn parse(value: &str) -> Result<Self> {
+        let mut attributes = Self::default();
+        for name in value.split(|c: char| c == ',' || c.is_whitespace()).filter(|n| !n.is_empty()) {
+            if !attributes.set(name) {
+                return Err(anyhow!("Unknown text attribute '{}' (expected bold, dim, italic, underline or reverse)", name));
+            }
+        }
+        Ok(attributes)
+    }
+
+    /// Turn on the attribute called `name`; false if there is no such attribute.
+    fn set(&mut self, name: &str) -> bool {
+        match name.to_lowercase().as_str() {
+            "bold" => self.bold = true,
+            "dim" | "faint" => self.dim = true,
+            "italic" => self.italic = true,
+            "underline" => self.underline = true,
+            "reverse" | "inverse" => self.reverse = true,
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn to_ansi(self) -> String {
+        let codes: Vec<&str> = [(self.bold, "1"), (self.dim, "2"), (self.italic, "3"), (self.underline, "4"), (self.reverse, "7")]
+            .iter()
+            .filter(|(on, _)| *on)
+            .map(|(_, code)| *code)
+            .collect();
+        if codes.is_empty() {
+            String::new()
+        } else {
+            format!("\x1b[{}m", codes.join(";"))
+        }
+    }
+}
+
+/// What a theme rule paints: attributes, a foreground and a background color,
+/// written like `bold 231 on #aa0000`.
+#[derive(Debug, Clone, Default)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub attributes: Attributes,
+}
+
+impl Style {
+    /// Parse `[ATTRIBUTE...] [COLOR] [on COLOR]`, e.g. `203`, `bold #ff5555` or `underline 231 on 52`.
+    pub fn parse(value: &str) -> Result<Self> {
+        let mut style = Style::default();
+        let mut words = value.split(|c: char| c == ',' || c.is_whitespace()).filter(|w| !w.is_empty());
+        while let Some(word) = words.next() {
+            if word.eq_ignore_ascii_case("on") {
+                let color = words.next().ok_or_else(|| anyhow!("Missing background color after 'on' in: {}", value))?;
+                style.bg = Some(Theme::parse_color(color)?);
+            } else if !style.attributes.set(word) {
+                if style.fg.is_some() {
+                    return Err(anyhow!("Unexpected '{}' in style (expected [ATTRIBUTE...] [COLOR] [on COLOR]): {}", word, value));
+                }
+                style.fg = Some(Theme::parse_color(word)?);
+            }
+        }
+        if style.fg.is_none() && style.bg.is_none() && style.attributes == Attributes::default() {
+            return Err(anyhow!("Empty style"));
+        }
+        Ok(style)
+    }
+
+    pub fn to_ansi(&self) -> String {
+        let mut ansi = self.attributes.to_ansi();
+        if let Some(fg) = &self.fg {
+            ansi.push_str(&fg.to_ansi_fg());
+        }
+        if let Some(bg) = &self.bg {
+            ansi.push_str(&bg.to_ansi_bg());
+        }
+        ansi
+    }
+
+    /// Whether ending the style takes a full reset rather than just restoring the foreground.
+    pub fn needs_reset(&self) -> bool {
+        self.bg.is_some() || self.attributes != Attributes::default()
+    }
+
+    /// The same style in colors a terminal of `depth` can show.
+    pub fn downgrade(&mut self, depth: ColorDepth) {
+        for color in [&mut self.fg, &mut self.bg].into_iter().flatten() {
+            *color = color.downgrade(depth);
+        }
+    }
+}
+
+impl From<Color> for Style {
+    fn from(color: Color) -> Self {
+        Style { fg: Some(color), ..Style::default() }
+    }
+}
+
 #[derive(Debug, Clone)]
+#[allow(clippy::enum_variant_names)]
 pub enum Color {
     Xterm256(u8),
     TrueColor { r: u8, g: u8, b: u8 },
+    /// One of the 16 basic colors, written with the codes every color terminal knows
+    Ansi16(u8),
+}
+
+/// How many colors a terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Ask the environment: `COLORTERM`, then `TERM`, then terminfo via `tput colors`.
+    /// Terminals that say nothing get 256 colors.
+    pub fn detect() -> Self {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default().to_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorDepth::TrueColor;
+        }
+        let term = std::env::var("TERM").unwrap_or_default().to_lowercase();
+        if term.ends_with("-direct") {
+            return ColorDepth::TrueColor;
+        }
+        if term.contains("256color") {
+            return ColorDepth::Ansi256;
+        }
+        let colors = std::process::Command::new("tput")
+            .arg("colors")
+            .stderr(std::process::Stdio::null())
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| String::from_utf8_lossy(&output.stdout).trim().parse::<u32>().ok());
+        match colors {
+            Some(n) if n >= 1 << 24 => ColorDepth::TrueColor,
+            Some(n) if n >= 256 => ColorDepth::Ansi256,
+            Some(_) => ColorDepth::Ansi16,
+            None => ColorDepth::Ansi256,
+        }
+    }
 }
 
 impl Color {
@@ -31,6 +347,29 @@ impl Color {
         match self {
             Color::Xterm256(n) => format!("\x1b[38;5;{}m", n),
             Color::TrueColor { r, g, b } => format!("\x1b[38;2;{};{};{}m", r, g, b),
+            Color::Ansi16(n) if *n < 8 => format!("\x1b[{}m", 30 + n),
+            Color::Ansi16(n) => format!("\x1b[{}m", 90 + n - 8),
+        }
+    }
+    
+    pub fn to_ansi_bg(&self) -> String {
+        match self {
+            Color::Xterm256(n) => format!("\x1b[48;5;{}m", n),
+            Color::TrueColor { r, g, b } => format!("\x1b[48;2;{};{};{}m", r, g, b),
+            Color::Ansi16(n) if *n < 8 => format!("\x1b[{}m", 40 + n),
+            Color::Ansi16(n) => format!("\x1b[{}m", 100 + n - 8),
+        }
+    }
+
+    /// The nearest color a terminal of `depth` can show.
+    pub fn downgrade(&self, depth: ColorDepth) -> Color {
+        match (self, depth) {
+            (_, ColorDepth::TrueColor) | (Color::Ansi16(_), _) => self.clone(),
+            (Color::Xterm256(_), ColorDepth::Ansi256) => self.clone(),
+            (Color::TrueColor { r, g, b }, ColorDepth::Ansi256) => Color::Xterm256(nearest_xterm((*r, *g, *b), 16..=255)),
+            (Color::Xterm256(n), ColorDepth::Ansi16) if *n < 16 => Color::Ansi16(*n),
+            (Color::Xterm256(n), ColorDepth::Ansi16) => Color::Ansi16(nearest_xterm(xterm_to_rgb(*n), 0..=15)),
+            (Color::TrueColor { r, g, b }, ColorDepth::Ansi16) => Color::Ansi16(nearest_xterm((*r, *g, *b), 0..=15)),
         }
     }
     
@@ -39,6 +378,39 @@ impl Color {
     }
 }
 
+/// The standard xterm-256 palette: 16 system colors, a 6×6×6 cube, then a gray ramp.
+pub fn xterm_to_rgb(n: u8) -> (u8, u8, u8) {
+    const SYSTEM: [(u8, u8, u8); 16] = [
+        (0, 0, 0), (205, 0, 0), (0, 205, 0), (205, 205, 0),
+        (0, 0, 238), (205, 0, 205), (0, 205, 205), (229, 229, 229),
+        (127, 127, 127), (255, 0, 0), (0, 255, 0), (255, 255, 0),
+        (92, 92, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255),
+    ];
+    match n {
+        0..=15 => SYSTEM[n as usize],
+        16..=231 => {
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            let i = n - 16;
+            (level(i / 36), level((i / 6) % 6), level(i % 6))
+        }
+        _ => {
+            let gray = 8 + (n - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
+
+/// The palette entry in `candidates` closest to `rgb`, by squared distance.
+fn nearest_xterm((r, g, b): (u8, u8, u8), candidates: std::ops::RangeInclusive<u8>) -> u8 {
+    candidates
+        .min_by_key(|&n| {
+            let (pr, pg, pb) = xterm_to_rgb(n);
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(r, pr) + d(g, pg) + d(b, pb)
+        })
+        .unwrap_or(0)
+}
+
 impl Theme {
     const BUILTIN_CATPPUCCIN: &'static str = include_str!("../themes/ft.conf.catppuccin");
     const BUILTIN_DRACULA: &'static str = include_str!("../themes/ft.conf.dracula");
@@ -47,14 +419,87 @@ impl Theme {
     const BUILTIN_ROSE_PINE: &'static str = include_str!("../themes/ft.conf.rose-pine");
     const BUILTIN_TOKYO_NIGHT: &'static str = include_str!("../themes/ft.conf.tokyo-night");
 
-    pub fn load_from_file<P: AsRef<Path>>(path: P, name: String) -> Result<Self> {
-        let contents = fs::read_to_string(&path)
-            .with_context(|| format!("Failed to read theme file: {:?}", path.as_ref()))?;
+    /// Themes compiled into ft, so it colors output even with no theme directories.
+    pub const BUILTIN_NAMES: [&'static str; 6] = ["catppuccin", "dracula", "lackluster", "miasma", "rose-pine", "tokyo-night"];
 
-        Self::parse_theme_contents(contents, name)
+    /// Find a theme by name: user and system theme directories first, then the built-ins.
+    /// A theme that `extends:` another is layered over it.
+    pub fn resolve(config: &Config, name: &str) -> Result<Self> {
+        let mut theme = Self::resolve_chain(config, name, false, &mut Vec::new())?;
+        // The config's `smart_highlight` wins over the theme's
+        if config.general.smart_highlight.or(theme.smart_highlight).unwrap_or(false) {
+            theme.word_rules.extend(Self::smart_rules());
+        }
+        Ok(theme)
+    }
+
+    /// The `smart_highlight` token rules.
+    fn smart_rules() -> impl Iterator<Item = ColorRule> {
+        SMART_RULES.iter().map(|(pattern, style)| ColorRule {
+            pattern: Regex::new(pattern).expect("built-in pattern"),
+            style: Style::parse(style).expect("built-in style"),
+            group: None,
+            original_pattern: pattern.to_string(),
+        })
+    }
+
+    /// `on`/`off` (or `true`/`false`, `yes`/`no`) for a theme setting.
+    fn parse_switch(value: &str) -> Result<bool> {
+        match value.trim().to_lowercase().as_str() {
+            "on" | "true" | "yes" => Ok(true),
+            "off" | "false" | "no" => Ok(false),
+            other => Err(anyhow!("Expected on or off, got '{}'", other)),
+        }
+    }
+
+    /// `builtin_only` is for a theme extending its own name, e.g. a user `dracula`
+    /// that tweaks the built-in one; `chain` catches themes that extend each other.
+    fn resolve_chain(config: &Config, name: &str, builtin_only: bool, chain: &mut Vec<String>) -> Result<Self> {
+        if chain.iter().any(|seen| seen == name) && !builtin_only {
+            chain.push(name.to_string());
+            return Err(anyhow!("Themes extend each other in a loop: {}", chain.join(" → ")));
+        }
+        chain.push(name.to_string());
+
+        match config.get_theme_path(name).filter(|_| !builtin_only) {
+            Some(theme_path) => Self::load_from_file(config, &theme_path, name, chain)
+                .with_context(|| format!("Failed to load theme from {:?}", theme_path)),
+            None => Self::load_builtin(name).unwrap_or_else(|| Err(anyhow!("Theme '{}' not found", name))),
+        }
+    }
+
+    fn load_from_file(config: &Config, path: &Path, name: &str, chain: &mut Vec<String>) -> Result<Self> {
+        let contents = Self::read_file(path)?;
+
+        let base = match Self::parent_name(&contents)? {
+            Some(parent) => Some(Self::resolve_chain(config, &parent, parent == name, chain)
+                .with_context(|| format!("Theme '{}' extends '{}'", name, parent))?),
+            None => None,
+        };
+        Self::parse_theme_contents(contents, name.to_string(), base)
+    }
+
+    /// The contents of a theme file, with `.toml` themes translated to the text format.
+    pub fn read_file(path: &Path) -> Result<String> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read theme file: {:?}", path))?;
+        if Self::is_toml(path) {
+            return crate::tomltheme::to_theme_text(&contents);
+        }
+        Ok(contents)
+    }
+
+    pub fn is_toml(path: &Path) -> bool {
+        path.extension().is_some_and(|ext| ext == "toml")
     }
 
     pub fn load_builtin(name: &str) -> Option<Result<Self>> {
+        let contents = Self::builtin_source(name)?;
+        Some(Self::parse_theme_contents(contents.to_string(), name.to_string(), None))
+    }
+
+    /// The theme file a built-in theme was compiled from.
+    pub fn builtin_source(name: &str) -> Option<&'static str> {
         let contents = match name {
             "catppuccin" => Self::BUILTIN_CATPPUCCIN,
             "dracula" => Self::BUILTIN_DRACULA,
@@ -64,15 +509,102 @@ impl Theme {
             "tokyo-night" => Self::BUILTIN_TOKYO_NIGHT,
             _ => return None,
         };
-        Some(Self::parse_theme_contents(contents.to_string(), name.to_string()))
+        Some(contents)
     }
-    
-    fn parse_theme_contents(contents: String, name: String) -> Result<Self> {
+
+    /// `ft themes export`: print one built-in theme, or write built-ins (all of them
+    /// unless `name` is given) into `dir` as `ft.conf.NAME` files to start from.
+    pub fn export_builtins(name: Option<&str>, dir: Option<&Path>, force: bool) -> Result<()> {
+        let names = match name {
+            Some(name) if Self::builtin_source(name).is_none() => {
+                return Err(anyhow!("No built-in theme '{}' (built-in: {})", name, Self::BUILTIN_NAMES.join(", ")));
+            }
+            Some(name) => vec![name],
+            None if dir.is_some() => Self::BUILTIN_NAMES.to_vec(),
+            None => return Err(anyhow!("Name a theme to print, or give --dir to write them all")),
+        };
+        let Some(dir) = dir else {
+            print!("{}", Self::builtin_source(names[0]).unwrap_or_default());
+            return Ok(());
+        };
+
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {:?}", dir))?;
+        for name in names {
+            let path = dir.join(format!("ft.conf.{}", name));
+            if path.exists() && !force {
+                eprintln!("Skipping {} (already exists; --force overwrites it)", path.display());
+                continue;
+            }
+            fs::write(&path, Self::builtin_source(name).unwrap_or_default())
+                .with_context(|| format!("Failed to write {:?}", path))?;
+            println!("Wrote {}", path.display());
+        }
+        Ok(())
+    }
+
+    /// Every theme `resolve` can find, by name, with the file it would load (None
+    /// for a built-in that no theme directory overrides).
+    pub fn available(config: &Config) -> Vec<(String, Option<PathBuf>)> {
+        let mut names: Vec<String> = Self::BUILTIN_NAMES.iter().map(|name| name.to_string()).collect();
+        for dir in [&config.themes.user_path, &config.themes.builtin_path] {
+            let Ok(entries) = fs::read_dir(dir) else {
+                continue;
+            };
+            names.extend(entries.flatten().filter_map(|entry| {
+                let file_name = entry.file_name().to_str()?.to_string();
+                file_name.strip_prefix("ft.conf.")
+                    .or_else(|| file_name.strip_prefix("ft.theme.")?.strip_suffix(".toml"))
+                    .map(str::to_string)
+            }));
+        }
+        names.sort();
+        names.dedup();
+        names.into_iter()
+            .map(|name| {
+                let path = config.get_theme_path(&name);
+                (name, path)
+            })
+            .collect()
+    }
+
+    /// The theme named by an `extends:` (or `include:`) line, if there is one.
+    fn parent_name(contents: &str) -> Result<Option<String>> {
+        let mut parents = contents.lines()
+            .filter_map(|line| {
+                let line = line.trim();
+                line.strip_prefix("extends:").or_else(|| line.strip_prefix("include:"))
+            })
+            .map(|name| name.trim().to_string());
+        let parent = parents.next();
+        if parents.next().is_some() {
+            return Err(anyhow!("A theme can only extend one other theme"));
+        }
+        match parent {
+            Some(name) if name.is_empty() => Err(anyhow!("extends: needs a theme name")),
+            parent => Ok(parent),
+        }
+    }
+
+    /// Parse a theme file. Over a `base` theme, settings given here replace the base's
+    /// and rules here come first, so they win wherever both match.
+    fn parse_theme_contents(contents: String, name: String, base: Option<Theme>) -> Result<Self> {
         let mut base_color = None;
         let mut statusbar_bg = None;
         let mut statusbar_fg = None;
         let mut line_rules = Vec::new();
         let mut word_rules = Vec::new();
+        let mut level_rules = Vec::new();
+        let mut hash_fields = Vec::new();
+        let mut hash_palette = Vec::new();
+        let mut source_palette = Vec::new();
+        let mut escalation_window = None;
+        let mut escalation_rules = Vec::new();
+        let mut match_style = None;
+        let mut json_styles = JsonStyles::default();
+        let mut smart_highlight = None;
+        let mut emphasis_style = None;
+        let mut age_rules = Vec::new();
 
         for (line_num, line) in contents.lines().enumerate() {
             let line = line.trim();
@@ -84,82 +616,175 @@ impl Theme {
 
             let line_context = || format!("Line {}: {}", line_num + 1, line);
 
-            if let Some(caps) = Self::parse_base_line(line) {
+            if line.starts_with("extends:") || line.starts_with("include:") {
+                // Resolved before parsing, see `parent_name`
+            } else if let Some(caps) = Self::parse_base_line(line) {
                 base_color = Some(caps);
-            } else if line.starts_with("statusbar_bg:") {
-                if let Ok(c) = Self::parse_color(line["statusbar_bg:".len()..].trim()) {
+            } else if let Some(value) = line.strip_prefix("statusbar_bg:") {
+                if let Ok(c) = Self::parse_color(value.trim()) {
                     statusbar_bg = Some(c);
                 }
-            } else if line.starts_with("statusbar_fg:") {
-                if let Ok(c) = Self::parse_color(line["statusbar_fg:".len()..].trim()) {
+            } else if let Some(value) = line.strip_prefix("statusbar_fg:") {
+                if let Ok(c) = Self::parse_color(value.trim()) {
                     statusbar_fg = Some(c);
                 }
+            } else if let Some(field) = line.strip_prefix("hash_field:") {
+                hash_fields.push(HashFieldRule::new(field).with_context(line_context)?);
+            } else if let Some(value) = line.strip_prefix("hash_palette:") {
+                for color_str in value.split(',') {
+                    hash_palette.push(Self::parse_color(color_str.trim()).with_context(line_context)?);
+                }
+            } else if let Some(value) = line.strip_prefix("source_palette:") {
+                for color_str in value.split(',') {
+                    source_palette.push(Self::parse_color(color_str.trim()).with_context(line_context)?);
+                }
+            } else if let Some(value) = line.strip_prefix("escalation_window:") {
+                escalation_window = Some(parse_duration(value).with_context(line_context)?);
+            } else if let Some(value) = line.strip_prefix("match_style:") {
+                match_style = Some(Attributes::parse(value).with_context(line_context)?);
+            } else if let Some(value) = line.strip_prefix("escalate:") {
+                escalation_rules.push(Self::parse_escalation_rule(value).with_context(line_context)?);
+            } else if let Some(value) = line.strip_prefix("level:") {
+                level_rules.push(LevelRule::parse(value).with_context(line_context)?);
+            } else if let Some(value) = line.strip_prefix("smart_highlight:") {
+                smart_highlight = Some(Self::parse_switch(value).with_context(line_context)?);
+            } else if let Some(value) = line.strip_prefix("emphasis_style:") {
+                emphasis_style = Some(Style::parse(value.trim()).with_context(line_context)?);
+            } else if let Some(value) = line.strip_prefix("age:") {
+                age_rules.push(AgeRule::parse(value).with_context(line_context)?);
+            } else if json_styles.parse_line(line).with_context(line_context)? {
+                // Stored by `parse_line`
             } else if let Some(rule) = Self::parse_line_rule(line).with_context(line_context)? {
                 line_rules.push(rule);
             } else if let Some(rule) = Self::parse_word_rule(line).with_context(line_context)? {
                 word_rules.push(rule);
             } else if !line.trim().is_empty() {
-                eprintln!("Warning: Unrecognized line in theme {}: {}", name, line);
+                eprintln!("Warning: Unrecognized line {} in theme {}: {} (`ft themes check {}` lists every problem)", line_num + 1, name, line, name);
             }
         }
 
-        Ok(Theme {
-            name,
+        let default_match_style = Attributes { bold: true, reverse: true, ..Attributes::default() };
+        let theme = Theme {
             base_color,
             statusbar_bg,
             statusbar_fg,
             line_rules,
             word_rules,
+            level_rules,
+            hash_fields,
+            hash_palette,
+            source_palette,
+            escalation_window,
+            escalation_rules,
+            match_style: match_style.unwrap_or(default_match_style),
+            emphasis_style,
+            age_rules,
+            smart_highlight,
+            json_styles,
+        };
+        Ok(match base {
+            Some(base) => theme.over(base, match_style.is_some()),
+            None => theme,
         })
     }
-    
-    fn parse_base_line(line: &str) -> Option<u8> {
-        if line.starts_with("base:") {
-            let color_str = &line[5..].trim();
-            color_str.parse().ok()
-        } else {
-            None
-        }
-    }
 
-    fn parse_u8_field(line: &str, prefix: &str) -> Option<u8> {
-        if line.starts_with(prefix) {
-            line[prefix.len()..].trim().parse().ok()
-        } else {
-            None
+    /// Layer this theme over `base`: what this one sets wins, the rest comes from `base`.
+    fn over(mut self, base: Theme, sets_match_style: bool) -> Self {
+        self.base_color = self.base_color.or(base.base_color);
+        self.statusbar_bg = self.statusbar_bg.or(base.statusbar_bg);
+        self.statusbar_fg = self.statusbar_fg.or(base.statusbar_fg);
+        self.line_rules.extend(base.line_rules);
+        self.word_rules.extend(base.word_rules);
+        self.level_rules.extend(base.level_rules);
+        self.hash_fields.extend(base.hash_fields);
+        if self.hash_palette.is_empty() {
+            self.hash_palette = base.hash_palette;
+        }
+        if self.source_palette.is_empty() {
+            self.source_palette = base.source_palette;
+        }
+        self.escalation_window = self.escalation_window.or(base.escalation_window);
+        if self.escalation_rules.is_empty() {
+            self.escalation_rules = base.escalation_rules;
+        }
+        if !sets_match_style {
+            self.match_style = base.match_style;
+        }
+        self.emphasis_style = self.emphasis_style.or(base.emphasis_style);
+        if self.age_rules.is_empty() {
+            self.age_rules = base.age_rules;
         }
+        self.smart_highlight = self.smart_highlight.or(base.smart_highlight);
+        let json = &mut self.json_styles;
+        json.key = json.key.take().or(base.json_styles.key);
+        json.string = json.string.take().or(base.json_styles.string);
+        json.number = json.number.take().or(base.json_styles.number);
+        json.literal = json.literal.take().or(base.json_styles.literal);
+        self
     }
     
+    fn parse_base_line(line: &str) -> Option<u8> {
+        line.strip_prefix("base:")
+            .and_then(|color_str| {
+                let color_str = color_str.trim();
+                color_str.parse().ok().or_else(|| Self::named_color(color_str))
+            })
+    }
+
     fn parse_line_rule(line: &str) -> Result<Option<ColorRule>> {
-        if line.starts_with("line:") {
-            Self::parse_rule(&line[5..], "line")
-        } else {
-            Ok(None)
+        match line.strip_prefix("line:") {
+            Some(rule_content) => Self::parse_rule(rule_content, "line"),
+            None => Ok(None),
         }
     }
     
     fn parse_word_rule(line: &str) -> Result<Option<ColorRule>> {
-        if line.starts_with("word:") {
-            Self::parse_rule(&line[5..], "word")
-        } else {
-            Ok(None)
+        match line.strip_prefix("word:") {
+            Some(rule_content) => Self::parse_rule(rule_content, "word"),
+            None => Ok(None),
         }
     }
     
+    /// Parse `COUNT=COLOR`, e.g. `escalate:20=214`.
+    fn parse_escalation_rule(value: &str) -> Result<EscalationRule> {
+        let (threshold, color) = value.split_once('=')
+            .ok_or_else(|| anyhow!("Invalid escalate rule, expected COUNT=COLOR: {}", value))?;
+        let threshold = threshold.trim().parse::<usize>()
+            .with_context(|| format!("Invalid escalate threshold: {}", threshold.trim()))?;
+        let color = Self::parse_color(color.trim())?;
+        Ok(EscalationRule { threshold, color })
+    }
+    
     fn parse_rule(rule_content: &str, rule_type: &str) -> Result<Option<ColorRule>> {
         if let Some(eq_pos) = rule_content.rfind('=') {
             let pattern_str = rule_content[..eq_pos].trim();
-            let color_str = rule_content[eq_pos + 1..].trim();
+            let style_str = rule_content[eq_pos + 1..].trim();
             
             let pattern = Regex::new(pattern_str)
                 .with_context(|| format!("Invalid regex pattern in {} rule: {}", rule_type, pattern_str))?;
             
-            let color = Self::parse_color(color_str)
-                .with_context(|| format!("Invalid color in {} rule: {}", rule_type, color_str))?;
+            // A leading `N:` on the style scopes it to capture group N
+            let (group, style_str) = match style_str.split_once(':') {
+                Some((n, rest)) if !n.is_empty() && n.trim().chars().all(|c| c.is_ascii_digit()) => {
+                    let n: usize = n.trim().parse()?;
+                    if rule_type != "word" {
+                        return Err(anyhow!("Capture group colors only apply to word rules: {}", rule_content));
+                    }
+                    if n == 0 || n >= pattern.captures_len() {
+                        return Err(anyhow!("Pattern has no capture group {}: {}", n, pattern_str));
+                    }
+                    (Some(n), rest.trim())
+                }
+                _ => (None, style_str),
+            };
+
+            let style = Style::parse(style_str)
+                .with_context(|| format!("Invalid style in {} rule: {}", rule_type, style_str))?;
             
             Ok(Some(ColorRule {
                 pattern,
-                color,
+                style,
+                group,
                 original_pattern: pattern_str.to_string(),
             }))
         } else {
@@ -179,24 +804,168 @@ impl Theme {
             let b = u8::from_str_radix(&color_str[5..7], 16)?;
             
             Ok(Color::TrueColor { r, g, b })
+        } else if let Some(n) = Self::named_color(color_str) {
+            Ok(Color::Xterm256(n))
         } else {
             // xterm-256 color: 123
-            let color_num = color_str.parse::<u8>()
-                .with_context(|| format!("Invalid color number: {}", color_str))?;
-            
-            if color_num > 255 {
-                return Err(anyhow!("Color number out of range (0-255): {}", color_num));
+            if color_str.is_empty() || !color_str.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(anyhow!("Unknown color: {}", color_str));
             }
+            let color_num = color_str.parse::<u8>()
+                .map_err(|_| anyhow!("Color number out of range (0-255): {}", color_str))?;
             
             Ok(Color::Xterm256(color_num))
         }
     }
     
-    pub fn get_base_color_ansi(&self) -> String {
-        if let Some(base) = self.base_color {
-            format!("\x1b[38;5;{}m", base)
-        } else {
-            String::new()
+    /// The xterm number of a basic ANSI color name: `red`, `bright_blue`, `gray`...
+    fn named_color(name: &str) -> Option<u8> {
+        const NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+        let name = name.to_lowercase().replace('-', "_");
+        let (bright, base) = match name.strip_prefix("bright_") {
+            Some(base) => (8, base),
+            None => (0, name.as_str()),
+        };
+        match base {
+            "gray" | "grey" if bright == 0 => Some(8),
+            _ => NAMES.iter().position(|n| *n == base).map(|i| i as u8 + bright),
+        }
+    }
+
+    /// Swap every color for the nearest one a terminal of `depth` can show.
+    pub fn downgrade(&mut self, depth: ColorDepth) {
+        let rules = self.line_rules.iter_mut()
+            .chain(self.word_rules.iter_mut())
+            .chain(self.level_rules.iter_mut().map(|level| &mut level.rule));
+        for rule in rules {
+            rule.style.downgrade(depth);
+        }
+        let styles = self.emphasis_style.iter_mut()
+            .chain(self.age_rules.iter_mut().map(|rule| &mut rule.style));
+        for style in styles {
+            style.downgrade(depth);
+        }
+        let json = &mut self.json_styles;
+        for style in [&mut json.key, &mut json.string, &mut json.number, &mut json.literal].into_iter().flatten() {
+            style.downgrade(depth);
+        }
+        let colors = self.statusbar_bg.iter_mut()
+            .chain(self.statusbar_fg.iter_mut())
+            .chain(self.hash_palette.iter_mut())
+            .chain(self.source_palette.iter_mut())
+            .chain(self.escalation_rules.iter_mut().map(|rule| &mut rule.color));
+        for color in colors {
+            *color = color.downgrade(depth);
         }
     }
-}
\ No newline at end of file
+
+}
+/// A problem `ft themes check` found on one line of a theme file.
+pub struct Diagnostic {
+    pub line: usize,
+    /// Errors stop the theme from loading; the rest only make it behave unexpectedly
+    pub error: bool,
+    pub message: String,
+}
+
+impl Theme {
+    /// `ft themes check`: go through a theme file the way loading it does, but note
+    /// every problem instead of stopping at the first, plus rules that never apply:
+    /// duplicates, and line rules an earlier line rule always wins over.
+    pub fn check(config: &Config, contents: &str) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut line_rules: Vec<(usize, ColorRule)> = Vec::new();
+        let mut word_rules: Vec<(usize, ColorRule)> = Vec::new();
+        let mut level_rules: Vec<(usize, LogLevel)> = Vec::new();
+        let mut extends = None;
+
+        for (line_num, line) in contents.lines().enumerate() {
+            let line_num = line_num + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let result: Result<()> = if let Some(parent) = line.strip_prefix("extends:").or_else(|| line.strip_prefix("include:")) {
+                let parent = parent.trim();
+                match extends.replace(line_num) {
+                    Some(first) => Err(anyhow!("A theme can only extend one other theme (already extends on line {})", first)),
+                    None if parent.is_empty() => Err(anyhow!("extends: needs a theme name")),
+                    None => Self::resolve(config, parent).map(|_| ()),
+                }
+            } else if let Some(value) = line.strip_prefix("base:") {
+                Self::parse_base_line(line).map(|_| ())
+                    .ok_or_else(|| anyhow!("Invalid base color, expected 0-255 or a color name: {}", value.trim()))
+            } else if let Some(value) = line.strip_prefix("statusbar_bg:").or_else(|| line.strip_prefix("statusbar_fg:")) {
+                Self::parse_color(value.trim()).map(|_| ())
+            } else if let Some(field) = line.strip_prefix("hash_field:") {
+                HashFieldRule::new(field).map(|_| ())
+            } else if let Some(value) = line.strip_prefix("hash_palette:").or_else(|| line.strip_prefix("source_palette:")) {
+                value.split(',').try_for_each(|color| Self::parse_color(color.trim()).map(|_| ()))
+            } else if let Some(value) = line.strip_prefix("escalation_window:") {
+                parse_duration(value).map(|_| ())
+            } else if let Some(value) = line.strip_prefix("match_style:") {
+                Attributes::parse(value).map(|_| ())
+            } else if let Some(value) = line.strip_prefix("escalate:") {
+                Self::parse_escalation_rule(value).map(|_| ())
+            } else if let Some(value) = line.strip_prefix("level:") {
+                LevelRule::parse(value).map(|rule| {
+                    if let Some((first, _)) = level_rules.iter().find(|(_, level)| *level == rule.level) {
+                        diagnostics.push(Diagnostic { line: line_num, error: false, message: format!("Never applies: the level rule on line {} already colors this level", first) });
+                    }
+                    level_rules.push((line_num, rule.level));
+                })
+            } else if let Some(value) = line.strip_prefix("smart_highlight:") {
+                Self::parse_switch(value).map(|_| ())
+            } else if let Some(value) = line.strip_prefix("emphasis_style:") {
+                Style::parse(value.trim()).map(|_| ())
+            } else if let Some(value) = line.strip_prefix("age:") {
+                AgeRule::parse(value).map(|_| ())
+            } else if line.starts_with("json_") {
+                JsonStyles::default().parse_line(line).map(|_| ())
+            } else if line.starts_with("line:") {
+                Self::parse_line_rule(line).map(|rule| {
+                    if let Some(rule) = rule {
+                        diagnostics.extend(Self::check_line_rule(line_num, &rule, &line_rules));
+                        line_rules.push((line_num, rule));
+                    }
+                })
+            } else if line.starts_with("word:") {
+                Self::parse_word_rule(line).map(|rule| {
+                    if let Some(rule) = rule {
+                        if let Some((first, _)) = word_rules.iter().find(|(_, earlier)| earlier.original_pattern == rule.original_pattern && earlier.group == rule.group) {
+                            diagnostics.push(Diagnostic { line: line_num, error: false, message: format!("Duplicate of the word rule on line {}", first) });
+                        }
+                        word_rules.push((line_num, rule));
+                    }
+                })
+            } else {
+                Err(anyhow!("Unrecognized line: {}", line))
+            };
+
+            if let Err(e) = result {
+                diagnostics.push(Diagnostic { line: line_num, error: true, message: format!("{:#}", e) });
+            }
+        }
+        diagnostics
+    }
+
+    /// Whether an earlier line rule matches every line `rule` does. Only the cases
+    /// that can be told for sure are caught: the same pattern, an earlier pattern
+    /// that matches anything, and a literal pattern an unanchored earlier one matches.
+    fn check_line_rule(line_num: usize, rule: &ColorRule, earlier: &[(usize, ColorRule)]) -> Option<Diagnostic> {
+        let literal = regex::escape(&rule.original_pattern) == rule.original_pattern;
+        earlier.iter().find_map(|(first, earlier)| {
+            let message = if earlier.original_pattern == rule.original_pattern {
+                format!("Duplicate of the line rule on line {}", first)
+            } else {
+                let anchored = ["^", "$", "\\b", "\\B", "\\A", "\\z"].iter().any(|anchor| earlier.original_pattern.contains(anchor));
+                if anchored || !(earlier.pattern.is_match("") || literal && earlier.pattern.is_match(&rule.original_pattern)) {
+                    return None;
+                }
+                format!("Never applies: every line it matches also matches the line rule on line {}, which wins", first)
+            };
+            Some(Diagnostic { line: line_num, error: false, message })
+        })
+    }
+}
@@ -0,0 +1,124 @@
+use anyhow::{anyhow, Result};
+use std::time::{Duration, Instant};
+
+/// `--max-rate`: at most `limit` lines per `window`.
+#[derive(Debug, Clone, Copy)]
+pub struct Rate {
+    pub limit: usize,
+    pub window: Duration,
+}
+
+/// Parse a `--sample` ratio: `1/100` or just `100` keeps every 100th line.
+pub fn parse_sample(input: &str) -> Result<usize> {
+    let text = input.trim();
+    let every = match text.split_once('/') {
+        Some(("1", n)) => n.trim(),
+        Some(_) => return Err(anyhow!("Invalid sample '{}': expected 1/N", input)),
+        None => text,
+    };
+    match every.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(anyhow!("Invalid sample '{}': expected 1/N with N > 0", input)),
+    }
+}
+
+/// Parse a `--max-rate` such as `200/s`, `5000/m` or a bare `200` (per second).
+pub fn parse_rate(input: &str) -> Result<Rate> {
+    let text = input.trim().to_lowercase();
+    let (count, unit) = text.split_once('/').unwrap_or((text.as_str(), "s"));
+    let window = match unit.trim() {
+        "s" | "sec" | "second" => Duration::from_secs(1),
+        "m" | "min" | "minute" => Duration::from_secs(60),
+        "h" | "hour" => Duration::from_secs(3600),
+        other => return Err(anyhow!("Invalid rate unit '{}' in: {}", other, input)),
+    };
+    match count.trim().parse::<usize>() {
+        Ok(limit) if limit > 0 => Ok(Rate { limit, window }),
+        _ => Err(anyhow!("Invalid rate '{}': expected N/s with N > 0", input)),
+    }
+}
+
+/// Thins out chatty sources: `--sample` keeps every Nth line, `--max-rate` drops
+/// lines beyond the limit and reports how many were dropped once the window ends.
+#[derive(Clone)]
+pub struct Throttle {
+    sample: Option<usize>,
+    rate: Option<Rate>,
+    seen: usize,
+    window_start: Instant,
+    in_window: usize,
+    dropped: usize,
+}
+
+impl Throttle {
+    pub fn new(sample: Option<usize>, rate: Option<Rate>) -> Option<Self> {
+        if sample.is_none() && rate.is_none() {
+            return None;
+        }
+        Some(Self {
+            sample,
+            rate,
+            seen: 0,
+            window_start: Instant::now(),
+            in_window: 0,
+            dropped: 0,
+        })
+    }
+
+    /// Feed a live line. Returns what to show: a summary of lines dropped in the
+    /// previous window, then the line itself unless it was sampled out or over the rate.
+    pub fn push(&mut self, line: String) -> Vec<String> {
+        if !self.sampled() {
+            return Vec::new();
+        }
+        let mut out: Vec<String> = self.flush_if_idle().into_iter().collect();
+        match self.rate {
+            Some(rate) if self.in_window >= rate.limit => self.dropped += 1,
+            _ => {
+                self.in_window += 1;
+                out.push(line);
+            }
+        }
+        out
+    }
+
+    /// Close the rate window once it has run out, summarizing what it dropped.
+    pub fn flush_if_idle(&mut self) -> Option<String> {
+        let rate = self.rate?;
+        if self.window_start.elapsed() < rate.window {
+            return None;
+        }
+        self.window_start = Instant::now();
+        self.in_window = 0;
+        self.flush()
+    }
+
+    /// Summarize lines dropped so far, if any.
+    pub fn flush(&mut self) -> Option<String> {
+        if self.dropped == 0 {
+            return None;
+        }
+        let dropped = std::mem::take(&mut self.dropped);
+        let rate = self.rate?;
+        Some(format!("… {} lines dropped (over {})", dropped, describe_rate(rate)))
+    }
+
+    /// Sample a complete batch of lines. Rate limiting only applies to live output.
+    pub fn sample_all<I: IntoIterator<Item = String>>(&mut self, lines: I) -> Vec<String> {
+        lines.into_iter().filter(|_| self.sampled()).collect()
+    }
+
+    fn sampled(&mut self) -> bool {
+        self.seen += 1;
+        self.sample.is_none_or(|every| (self.seen - 1).is_multiple_of(every))
+    }
+}
+
+fn describe_rate(rate: Rate) -> String {
+    let unit = match rate.window.as_secs() {
+        60 => "m",
+        3600 => "h",
+        _ => "s",
+    };
+    format!("{}/{}", rate.limit, unit)
+}
@@ -0,0 +1,47 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, FixedOffset, Local, SecondsFormat, Utc};
+use chrono_tz::Tz;
+
+/// `--utc`, `--local` and `--tz`: the zone timestamps are shown in, whatever zone the
+/// line was written in.
+#[derive(Clone, Copy)]
+pub enum DisplayZone {
+    Utc,
+    Local,
+    Named(Tz),
+}
+
+impl DisplayZone {
+    /// `--tz`: an IANA zone name such as `Europe/Berlin`, or `UTC` or `local`.
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "utc" | "z" => Ok(DisplayZone::Utc),
+            "local" => Ok(DisplayZone::Local),
+            _ => name.parse::<Tz>()
+                .map(DisplayZone::Named)
+                .map_err(|_| anyhow!("Unknown time zone: {} (expected a name like Europe/Berlin)", name)),
+        }
+    }
+
+    /// `time` as a clock in this zone reads it.
+    pub fn convert(self, time: DateTime<Local>) -> DateTime<FixedOffset> {
+        match self {
+            DisplayZone::Utc => time.with_timezone(&Utc).fixed_offset(),
+            DisplayZone::Local => time.fixed_offset(),
+            DisplayZone::Named(tz) => time.with_timezone(&tz).fixed_offset(),
+        }
+    }
+
+    /// `time` in this zone as ISO 8601 with milliseconds, `Z` for UTC.
+    pub fn iso8601(self, time: DateTime<Local>) -> String {
+        self.convert(time).to_rfc3339_opts(SecondsFormat::Millis, matches!(self, DisplayZone::Utc))
+    }
+
+    /// The offset suffix for ISO 8601 timestamps: `Z` in UTC, `+02:00` otherwise.
+    pub fn offset_suffix(self, time: &DateTime<FixedOffset>) -> String {
+        match self {
+            DisplayZone::Utc => "Z".to_string(),
+            _ => time.format("%:z").to_string(),
+        }
+    }
+}
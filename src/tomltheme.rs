@@ -0,0 +1,172 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// A color or style: `214`, `"#ff5555"` or `"bold 214 on 52"`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Spec {
+    Number(u64),
+    Text(String),
+}
+
+impl Spec {
+    fn text(&self) -> String {
+        match self {
+            Spec::Number(n) => n.to_string(),
+            Spec::Text(s) => s.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TomlTheme {
+    extends: Option<String>,
+    base: Option<Spec>,
+    smart_highlight: Option<bool>,
+    #[serde(default)]
+    hash_fields: Vec<String>,
+    #[serde(default)]
+    hash_palette: Vec<Spec>,
+    #[serde(default)]
+    source_palette: Vec<Spec>,
+    statusbar: Option<Statusbar>,
+    attributes: Option<Attributes>,
+    #[serde(default)]
+    line: Vec<Rule>,
+    #[serde(default)]
+    word: Vec<Rule>,
+    #[serde(default)]
+    level: BTreeMap<String, Spec>,
+    escalation: Option<Escalation>,
+    #[serde(default)]
+    age: BTreeMap<String, Spec>,
+    json: Option<Json>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Statusbar {
+    bg: Option<Spec>,
+    fg: Option<Spec>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Attributes {
+    /// `match_style:`
+    #[serde(rename = "match")]
+    matches: Option<String>,
+    /// `emphasis_style:`
+    emphasis: Option<Spec>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Rule {
+    pattern: String,
+    style: Spec,
+    group: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Escalation {
+    window: Option<String>,
+    #[serde(default)]
+    steps: Vec<EscalationStep>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EscalationStep {
+    count: usize,
+    color: Spec,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Json {
+    key: Option<Spec>,
+    string: Option<Spec>,
+    number: Option<Spec>,
+    literal: Option<Spec>,
+}
+
+/// Translate an `ft.theme.NAME.toml` theme into the `line:`/`word:` text format,
+/// one setting per line, so both kinds of theme go through the same parser.
+pub fn to_theme_text(contents: &str) -> Result<String> {
+    let theme: TomlTheme = toml::from_str(contents).context("Invalid TOML theme")?;
+    let mut lines = Vec::new();
+    let mut push = |key: &str, value: String| {
+        if value.contains('\n') {
+            return Err(anyhow!("{} must fit on one line: {:?}", key, value));
+        }
+        lines.push(format!("{}:{}", key, value));
+        Ok(())
+    };
+
+    if let Some(parent) = theme.extends {
+        push("extends", parent)?;
+    }
+    if let Some(base) = theme.base {
+        push("base", base.text())?;
+    }
+    if let Some(statusbar) = theme.statusbar {
+        if let Some(bg) = statusbar.bg {
+            push("statusbar_bg", bg.text())?;
+        }
+        if let Some(fg) = statusbar.fg {
+            push("statusbar_fg", fg.text())?;
+        }
+    }
+    if let Some(attributes) = theme.attributes {
+        if let Some(matches) = attributes.matches {
+            push("match_style", matches)?;
+        }
+        if let Some(emphasis) = attributes.emphasis {
+            push("emphasis_style", emphasis.text())?;
+        }
+    }
+    if let Some(on) = theme.smart_highlight {
+        push("smart_highlight", on.to_string())?;
+    }
+    for field in theme.hash_fields {
+        push("hash_field", field)?;
+    }
+    for (key, palette) in [("hash_palette", theme.hash_palette), ("source_palette", theme.source_palette)] {
+        if !palette.is_empty() {
+            push(key, palette.iter().map(Spec::text).collect::<Vec<_>>().join(","))?;
+        }
+    }
+    for (key, rules) in [("line", theme.line), ("word", theme.word)] {
+        for rule in rules {
+            let group = rule.group.map(|n| format!("{}:", n)).unwrap_or_default();
+            push(key, format!("{}={}{}", rule.pattern, group, rule.style.text()))?;
+        }
+    }
+    for (level, style) in theme.level {
+        push("level", format!("{} = {}", level, style.text()))?;
+    }
+    if let Some(escalation) = theme.escalation {
+        if let Some(window) = escalation.window {
+            push("escalation_window", window)?;
+        }
+        for step in escalation.steps {
+            push("escalate", format!("{}={}", step.count, step.color.text()))?;
+        }
+    }
+    for (age, style) in theme.age {
+        push("age", format!("{}={}", age, style.text()))?;
+    }
+    if let Some(json) = theme.json {
+        for (key, style) in [("json_key", json.key), ("json_string", json.string), ("json_number", json.number), ("json_literal", json.literal)] {
+            if let Some(style) = style {
+                push(key, style.text())?;
+            }
+        }
+    }
+
+    Ok(lines.join("\n"))
+}
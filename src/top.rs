@@ -0,0 +1,90 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::filter::json_path;
+use crate::output::parse_fields;
+use crate::parser::Parser;
+
+/// Values listed in the table; the rest are added up on one line
+const ROWS: usize = 10;
+/// How often the table is redrawn while following
+const REFRESH: Duration = Duration::from_secs(1);
+
+/// `--top FIELD`: counts of each value of a field, such as the client IP or status code
+/// of access log lines, shown as a table instead of the lines themselves.
+pub struct TopCounter {
+    field: String,
+    counts: HashMap<String, usize>,
+    total: usize,
+    /// Lines that didn't have the field
+    missing: usize,
+    last_draw: Option<Instant>,
+    changed: bool,
+}
+
+impl TopCounter {
+    pub fn new(field: &str) -> Self {
+        let field = match field.to_lowercase().as_str() {
+            "status" | "code" => "status_code".to_string(),
+            "msg" => "message".to_string(),
+            _ => field.to_string(),
+        };
+        Self { field, counts: HashMap::new(), total: 0, missing: 0, last_draw: None, changed: false }
+    }
+
+    /// Count the field's value in `line`, parsed with the source's parser.
+    pub fn observe(&mut self, parser: &dyn Parser, line: &str) {
+        let fields = parse_fields(parser, line);
+        let value = fields.get(&self.field).or_else(|| json_path(&fields, &self.field));
+        let value = match value {
+            Some(Value::String(text)) if !text.is_empty() => Some(text.clone()),
+            Some(Value::Null | Value::String(_)) | None => None,
+            Some(other) => Some(other.to_string()),
+        };
+        match value {
+            Some(value) => *self.counts.entry(value).or_default() += 1,
+            None => self.missing += 1,
+        }
+        self.total += 1;
+        self.changed = true;
+    }
+
+    /// The table, when something was counted and it was last drawn long enough ago.
+    pub fn due_table(&mut self) -> Option<String> {
+        if !self.changed || self.last_draw.is_some_and(|at| at.elapsed() < REFRESH) {
+            return None;
+        }
+        Some(self.table())
+    }
+
+    /// Whether the table has been drawn since the last line was counted.
+    pub fn is_drawn(&self) -> bool {
+        self.last_draw.is_some() && !self.changed
+    }
+
+    pub fn table(&mut self) -> String {
+        self.last_draw = Some(Instant::now());
+        self.changed = false;
+        let percent = |n: usize| if self.total == 0 { 0.0 } else { n as f64 * 100.0 / self.total as f64 };
+        let mut values: Vec<(&String, &usize)> = self.counts.iter().collect();
+        values.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        let width = values.iter().take(ROWS).map(|(value, _)| value.chars().count()).max().unwrap_or(0)
+            .max(self.field.len()).clamp(12, 48);
+
+        let mut out = vec![format!("ft top {}: {} lines, {} distinct", self.field, self.total, values.len())];
+        out.push(format!("  {:<width$} {:>10} {:>7}", self.field.to_uppercase(), "LINES", "%"));
+        for (value, count) in values.iter().take(ROWS) {
+            let value: String = value.chars().take(width).collect();
+            out.push(format!("  {:<width$} {:>10} {:>6.1}%", value, count, percent(**count)));
+        }
+        if values.len() > ROWS {
+            let rest: usize = values[ROWS..].iter().map(|(_, count)| **count).sum();
+            out.push(format!("  {:<width$} {:>10} {:>6.1}%", format!("({} more)", values.len() - ROWS), rest, percent(rest)));
+        }
+        if self.missing > 0 {
+            out.push(format!("  {:<width$} {:>10} {:>6.1}%", "(none)", self.missing, percent(self.missing)));
+        }
+        out.join("\n")
+    }
+}
@@ -0,0 +1,214 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+const RELEASES_API: &str = "https://api.github.com/repos/yodabytz/fuzzytail/releases";
+
+/// Which releases `ft self-update` follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    /// The latest tagged release
+    Stable,
+    /// The rolling `nightly` release, rebuilt from main
+    Nightly,
+}
+
+impl Channel {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "stable" => Ok(Channel::Stable),
+            "nightly" => Ok(Channel::Nightly),
+            _ => Err(anyhow!("Invalid channel '{}': expected stable or nightly", name)),
+        }
+    }
+
+    fn release_url(self) -> String {
+        match self {
+            Channel::Stable => format!("{}/latest", RELEASES_API),
+            Channel::Nightly => format!("{}/tags/nightly", RELEASES_API),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+#[derive(Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+impl Release {
+    fn asset(&self, name: &str) -> Option<&Asset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+}
+
+/// `ft self-update`: fetch the newest release on `channel` from GitHub, check the
+/// binary against its published SHA-256, and swap it in for the running executable.
+/// Downloads go through `curl`, so no TLS stack is built into ft.
+pub fn self_update(channel: Channel, check_only: bool) -> Result<()> {
+    let current = env!("CARGO_PKG_VERSION");
+    let reply = curl(&["-H", "Accept: application/vnd.github+json", &channel.release_url()])
+        .with_context(|| format!("Could not look up the latest {:?} release", channel))?;
+    let release: Release = serde_json::from_slice(&reply)
+        .context("Unexpected reply from the GitHub releases API")?;
+
+    let exe = std::env::current_exe().context("Cannot find the running ft")?;
+    let exe = exe.canonicalize().unwrap_or(exe);
+    let latest = release.tag_name.trim_start_matches('v');
+    let newer = match channel {
+        Channel::Stable => version_key(latest) > version_key(current),
+        // The nightly tag moves, so compare when it was published with the one installed
+        Channel::Nightly => match (&release.published_at, installed_nightly(&exe)) {
+            (Some(published), Some(installed)) => *published != installed,
+            _ => true,
+        },
+    };
+    let published = release.published_at.as_deref().unwrap_or("unknown date");
+    if !newer {
+        match channel {
+            Channel::Stable => println!("ft {} is up to date", current),
+            Channel::Nightly => println!("ft nightly (published {}) is up to date", published),
+        }
+        return Ok(());
+    }
+    if check_only {
+        println!("ft {} is available (installed: {}, published {})", latest, current, published);
+        return Ok(());
+    }
+
+    let name = asset_name()?;
+    let binary = release.asset(&name)
+        .with_context(|| format!("Release {} has no build for this platform ({})", release.tag_name, name))?;
+    let checksum = release.asset(&format!("{}.sha256", name))
+        .with_context(|| format!("Release {} has no {}.sha256; refusing to install an unverified binary", release.tag_name, name))?;
+
+    // Download next to the executable so the final rename stays on one filesystem
+    let staged = exe.with_file_name(format!(".ft-update-{}", std::process::id()));
+    let result = download(&binary.browser_download_url, &staged)
+        .and_then(|_| verify(&staged, &checksum.browser_download_url))
+        .and_then(|_| install(&staged, &exe));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&staged);
+    }
+    result?;
+    // A stable build replaces the nightly, so the next nightly update installs it again
+    match (channel, &release.published_at) {
+        (Channel::Nightly, Some(published)) => {
+            if let Err(e) = std::fs::write(nightly_marker(&exe), published) {
+                eprintln!("ft: could not record the installed nightly, so the next update will fetch it again: {}", e);
+            }
+        }
+        _ => {
+            let _ = std::fs::remove_file(nightly_marker(&exe));
+        }
+    }
+
+    println!("Updated ft {} → {} ({})", current, latest, exe.display());
+    Ok(())
+}
+
+/// Where the `published_at` of the nightly installed at `exe` is kept, next to it.
+fn nightly_marker(exe: &Path) -> PathBuf {
+    exe.with_file_name(".ft-nightly")
+}
+
+/// When the nightly installed at `exe` was published, if it came from `self-update`.
+fn installed_nightly(exe: &Path) -> Option<String> {
+    std::fs::read_to_string(nightly_marker(exe)).ok().map(|published| published.trim().to_string())
+}
+
+/// The release asset built for this machine, named like `install.sh` names platforms.
+fn asset_name() -> Result<String> {
+    let os = match std::env::consts::OS {
+        "linux" => "linux",
+        "macos" => "macos",
+        other => return Err(anyhow!("No prebuilt ft for {}; build from source instead", other)),
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "x86_64",
+        "aarch64" => "arm64",
+        "arm" => "armv7",
+        other => return Err(anyhow!("No prebuilt ft for {}; build from source instead", other)),
+    };
+    Ok(format!("ft-{}-{}", os, arch))
+}
+
+/// `1.2.10` → [1, 2, 10], so versions compare numerically; pre-release suffixes are ignored.
+fn version_key(version: &str) -> Vec<u64> {
+    version.split(['.', '-', '+'])
+        .map_while(|part| part.parse().ok())
+        .collect()
+}
+
+fn curl(args: &[&str]) -> Result<Vec<u8>> {
+    let output = Command::new("curl")
+        .args(["-fsSL", "--proto", "=https"])
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .context("self-update needs curl on PATH")?;
+    if !output.status.success() {
+        return Err(anyhow!("Download failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(output.stdout)
+}
+
+fn download(url: &str, to: &Path) -> Result<()> {
+    let to_arg = to.to_string_lossy();
+    curl(&["-o", &to_arg, url])
+        .with_context(|| format!("Failed to download {}", url))?;
+    Ok(())
+}
+
+/// Compare the SHA-256 of `file` with the first word of the published checksum file.
+fn verify(file: &Path, checksum_url: &str) -> Result<()> {
+    let published = String::from_utf8_lossy(&curl(&[checksum_url])?).to_string();
+    let expected = published.split_whitespace().next()
+        .filter(|hash| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+        .context("Malformed checksum file")?
+        .to_lowercase();
+    let actual = sha256(file)?;
+    if actual != expected {
+        return Err(anyhow!("Checksum mismatch for the downloaded ft (expected {}, got {}); not installing", expected, actual));
+    }
+    Ok(())
+}
+
+fn sha256(file: &Path) -> Result<String> {
+    for (tool, args) in [("sha256sum", &[][..]), ("shasum", &["-a", "256"][..])] {
+        let Ok(output) = Command::new(tool).args(args).arg(file).stdin(Stdio::null()).output() else {
+            continue;
+        };
+        if output.status.success() {
+            if let Some(hash) = String::from_utf8_lossy(&output.stdout).split_whitespace().next() {
+                return Ok(hash.to_lowercase());
+            }
+        }
+    }
+    Err(anyhow!("self-update needs sha256sum or shasum on PATH to verify the download"))
+}
+
+/// Make `staged` executable and rename it over `exe`, which is atomic: anything
+/// starting ft meanwhile gets the old binary or the new one, never half of one.
+fn install(staged: &Path, exe: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(staged, std::fs::Permissions::from_mode(0o755))
+            .with_context(|| format!("Failed to make {:?} executable", staged))?;
+    }
+    std::fs::rename(staged, exe).map_err(|e| match e.kind() {
+        std::io::ErrorKind::PermissionDenied => anyhow!("No permission to replace {:?}; try again with sudo", exe),
+        _ => anyhow!("Failed to replace {:?}: {}", exe, e),
+    })
+}
@@ -0,0 +1,204 @@
+//! Terminal column widths for display strings, shared by panes, status bars
+//! and popups. ANSI escape sequences take no columns.
+
+/// Columns a character occupies: 0 for combining marks, 2 for wide CJK and emoji.
+pub fn char_width(ch: char) -> usize {
+    let c = ch as u32;
+    match c {
+        0x0300..=0x036F | 0x200B..=0x200F | 0xFE00..=0xFE0F => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD => 2,
+        _ if ch.is_control() => 0,
+        _ => 1,
+    }
+}
+
+/// Columns `s` occupies on screen, skipping ANSI escape sequences.
+pub fn visible_width(s: &str) -> usize {
+    let mut visible = 0usize;
+    let mut in_escape = false;
+    for ch in s.chars() {
+        if in_escape {
+            if ch.is_ascii_alphabetic() {
+                in_escape = false;
+            }
+        } else if ch == '\x1b' {
+            in_escape = true;
+        } else {
+            visible += char_width(ch);
+        }
+    }
+    visible
+}
+
+/// Cut plain text to at most `max_width` columns, marking the cut with '…'.
+pub fn truncate(s: &str, max_width: usize) -> String {
+    if visible_width(s) <= max_width {
+        return s.to_string();
+    }
+    let mut result = String::new();
+    let mut visible = 0usize;
+    let budget = max_width.saturating_sub(1);
+    for ch in s.chars() {
+        let w = char_width(ch);
+        if visible + w > budget {
+            break;
+        }
+        result.push(ch);
+        visible += w;
+    }
+    if max_width > 0 {
+        result.push('…');
+    }
+    result
+}
+
+/// Cut an ANSI-colored line to `max_width` columns and pad it with spaces to exactly that width.
+pub fn pad_ansi(s: &str, max_width: usize) -> String {
+    let mut result = String::with_capacity(s.len() + max_width);
+    let mut visible = 0usize;
+    let mut in_escape = false;
+
+    for ch in s.chars() {
+        if in_escape {
+            result.push(ch);
+            if ch.is_ascii_alphabetic() {
+                in_escape = false;
+            }
+            continue;
+        }
+
+        if ch == '\x1b' {
+            if visible >= max_width {
+                break;
+            }
+            in_escape = true;
+            result.push(ch);
+            continue;
+        }
+
+        if ch == '\t' {
+            let spaces = 4 - (visible % 4);
+            for _ in 0..spaces {
+                if visible >= max_width { break; }
+                result.push(' ');
+                visible += 1;
+            }
+            continue;
+        }
+
+        let w = char_width(ch);
+        if visible + w > max_width {
+            break;
+        }
+
+        if !ch.is_control() {
+            result.push(ch);
+            visible += w;
+        }
+    }
+
+    // Reset color, then pad remaining width with spaces
+    result.push_str("\x1b[0m");
+    while visible < max_width {
+        result.push(' ');
+        visible += 1;
+    }
+
+    result
+}
+
+/// Drop the first `columns` columns of an ANSI-colored line, for scrolling it
+/// sideways. Escape sequences are kept so what's left is colored as before.
+pub fn skip_columns(s: &str, columns: usize) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut visible = 0usize;
+    let mut in_escape = false;
+
+    for ch in s.chars() {
+        if in_escape {
+            result.push(ch);
+            if ch.is_ascii_alphabetic() {
+                in_escape = false;
+            }
+            continue;
+        }
+        if ch == '\x1b' {
+            in_escape = true;
+            result.push(ch);
+            continue;
+        }
+
+        let (text, w) = match ch {
+            '\t' => (" ".repeat(4 - (visible % 4)), 4 - (visible % 4)),
+            _ if ch.is_control() => continue,
+            _ => (ch.to_string(), char_width(ch)),
+        };
+        if visible >= columns {
+            result.push_str(&text);
+        } else if visible + w > columns {
+            // A character cut in half by the edge shows as blanks
+            result.push_str(&" ".repeat(visible + w - columns));
+        }
+        visible += w;
+    }
+    result
+}
+
+/// Split an ANSI-colored line into rows of at most `width` columns, for wrapping it.
+/// Each row starts with the escape sequences in effect where it begins, so rows drawn
+/// on their own keep their colors.
+pub fn wrap_ansi(s: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut rows = Vec::new();
+    let mut row = String::new();
+    let mut active = String::new();
+    let mut escape = String::new();
+    let mut visible = 0usize;
+
+    for ch in s.chars() {
+        if !escape.is_empty() {
+            escape.push(ch);
+            if ch.is_ascii_alphabetic() {
+                if escape == "\x1b[0m" || escape == "\x1b[m" {
+                    active.clear();
+                } else {
+                    active.push_str(&escape);
+                }
+                row.push_str(&escape);
+                escape.clear();
+            }
+            continue;
+        }
+        if ch == '\x1b' {
+            escape.push(ch);
+            continue;
+        }
+
+        let (text, w) = match ch {
+            '\t' => (" ".repeat(4 - (visible % 4)), 4 - (visible % 4)),
+            _ if ch.is_control() => continue,
+            _ => (ch.to_string(), char_width(ch)),
+        };
+        if visible + w > width && visible > 0 {
+            rows.push(std::mem::replace(&mut row, active.clone()));
+            visible = 0;
+        }
+        row.push_str(&text);
+        visible += w;
+    }
+    rows.push(row);
+    rows
+}
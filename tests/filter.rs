@@ -0,0 +1,144 @@
+//! Behaviour of the line filters, checked by running `ft` on sample logs.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Write `contents` to a log file of its own for one test.
+fn sample_log(name: &str, contents: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ft-filter-{}-{}", std::process::id(), name));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{}.log", name));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+/// Run `ft --no-follow` with `args` on `log`.
+fn run(args: &[&str], log: &PathBuf) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ft"))
+        .arg("--no-follow")
+        .args(args)
+        .arg(log)
+        .env("NO_COLOR", "1")
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .unwrap()
+}
+
+/// Run `ft --no-follow` with `args` on `log` and return the lines it kept.
+fn ft(args: &[&str], log: &PathBuf) -> Vec<String> {
+    let output = run(args, log);
+    assert!(output.status.success(), "ft {:?} failed: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap().lines().map(str::to_string).collect()
+}
+
+const ACCESS_LOG: &str = "\
+1.2.3.4 - - [10/Oct/2024:13:55:36 +0000] \"GET /a HTTP/1.1\" 503 12 \"-\" \"curl\"
+1.2.3.4 - - [10/Oct/2024:13:55:37 +0000] \"GET /b HTTP/1.1\" 200 12 \"-\" \"curl\"
+1.2.3.4 - - [10/Oct/2024:13:55:38 +0000] \"GET /ERROR HTTP/1.1\" 404 12 \"-\" \"curl\"
+";
+
+#[test]
+fn level_comes_from_the_input_format() {
+    let log = sample_log("access-level", ACCESS_LOG);
+    let errors = ft(&["--input-format", "access-log", "--level", "ERROR"], &log);
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(errors[0].contains("503"));
+
+    // Without an input format the keyword in the path is all there is to go on
+    let keyword = ft(&["--level", "ERROR"], &log);
+    assert_eq!(keyword.len(), 1, "{:?}", keyword);
+    assert!(keyword[0].contains("/ERROR"));
+}
+
+#[test]
+fn since_reads_the_timestamp_the_parser_finds() {
+    // The first timestamp in each line isn't the record's own `time`
+    let log = sample_log("json-since", "\
+{\"started\":\"2020-01-01T00:00:00Z\",\"time\":\"2024-05-01T10:00:00Z\",\"msg\":\"recent\"}
+{\"started\":\"2024-05-01T00:00:00Z\",\"time\":\"2020-05-01T10:00:00Z\",\"msg\":\"old\"}
+");
+    let kept = ft(&["--input-format", "json", "--since", "2024-01-01"], &log);
+    assert_eq!(kept.len(), 1, "{:?}", kept);
+    assert!(kept[0].contains("recent"));
+}
+
+#[test]
+fn where_and_field_follow_paths_into_arrays() {
+    let log = sample_log("json-paths", "\
+{\"items\":[{\"id\":7},{\"id\":8}],\"user\":{\"id\":\"a\"}}
+{\"items\":[{\"id\":9}],\"user\":{\"id\":\"b\"}}
+");
+    for args in [&["--where", "items.0.id=7"][..], &["--field", "items.0.id=7"][..], &["--where", "user.id=a"][..]] {
+        let kept = ft(args, &log);
+        assert_eq!(kept.len(), 1, "{:?}: {:?}", args, kept);
+        assert!(kept[0].contains("\"id\":7"), "{:?}: {:?}", args, kept);
+    }
+}
+
+const APP_LOG: &str = "\
+2024-01-01 12:00:00 DEBUG cache warm
+2024-01-01 12:00:01 INFO GET /a.b took 12ms
+2024-01-01 12:00:02 WARN GET /axb took 950ms
+2024-01-01 12:00:03 ERROR db down
+";
+
+#[test]
+fn invalid_filters_are_rejected_with_the_value_named() {
+    let log = sample_log("invalid", APP_LOG);
+    for (args, message) in [
+        (&["--level", "LOUD"][..], "Invalid log level"),
+        (&["--where", "status"][..], "Invalid --where condition: status"),
+        (&["--field", "=5"][..], "missing key"),
+        (&["--since", "bogus"][..], "Invalid --since value"),
+        (&["--match-mode", "some", "--include", "a"][..], "Invalid match mode: some"),
+        (&["--filter", "level >>"][..], "Invalid --filter expression"),
+        (&["--include", "("][..], "Invalid include regex pattern: ("),
+    ] {
+        let output = run(args, &log);
+        assert!(!output.status.success(), "{:?} was accepted", args);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains(message), "{:?}: {}", args, stderr);
+    }
+}
+
+#[test]
+fn levels_take_ranges_lists_and_inversions() {
+    let log = sample_log("levels", APP_LOG);
+    let levels = |args: &[&str]| -> Vec<String> {
+        ft(args, &log).iter().map(|line| line.split(' ').nth(2).unwrap().to_string()).collect()
+    };
+    assert_eq!(levels(&["--level", "WARN"]), ["WARN", "ERROR"]);
+    assert_eq!(levels(&["--level", "INFO..WARN"]), ["INFO", "WARN"]);
+    assert_eq!(levels(&["--level", "DEBUG,ERROR"]), ["DEBUG", "ERROR"]);
+    assert_eq!(levels(&["--level", "WARN", "--level-exact"]), ["WARN"]);
+    assert_eq!(levels(&["--level", "WARN", "--level-invert"]), ["DEBUG", "INFO"]);
+}
+
+#[test]
+fn patterns_combine_as_asked() {
+    let log = sample_log("patterns", APP_LOG);
+    assert_eq!(ft(&["--include", "a.b"], &log).len(), 2);
+    assert_eq!(ft(&["-F", "--include", "a.b"], &log).len(), 1);
+    assert_eq!(ft(&["--include", "GET", "--include", "ms", "--match-mode", "all", "--exclude", "950"], &log).len(), 1);
+    assert_eq!(ft(&["--include", "get", "--ignore-case", "--invert-match"], &log).len(), 2);
+}
+
+#[test]
+fn where_compares_numbers_with_units() {
+    let log = sample_log("where", APP_LOG);
+    let kept = ft(&["--include", r"took (?P<took>\d+ms)", "--where", "took > 100"], &log);
+    assert_eq!(kept.len(), 1, "{:?}", kept);
+    assert!(kept[0].contains("950ms"));
+}
+
+#[test]
+fn max_count_and_count_stop_at_matching_lines() {
+    let log = sample_log("counts", APP_LOG);
+    assert_eq!(ft(&["--include", "GET", "-m", "1"], &log), ["2024-01-01 12:00:01 INFO GET /a.b took 12ms"]);
+    assert_eq!(ft(&["--include", "GET", "-C"], &log), ["2"]);
+
+    // Like grep -c, no matching lines is an exit status of 1
+    let none = run(&["--include", "nothing", "-C"], &log);
+    assert_eq!(none.status.code(), Some(1));
+    assert_eq!(String::from_utf8_lossy(&none.stdout).trim(), "0");
+}
@@ -0,0 +1,120 @@
+//! Behaviour of `--format` output, checked by running `ft` on sample logs.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use serde_json::Value;
+
+/// Write `contents` to a log file of its own for one test.
+fn sample_log(name: &str, contents: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ft-test-{}-{}", std::process::id(), name));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{}.log", name));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+/// Run `ft` with `args`, feeding it `input` on stdin, and return what it printed.
+fn ft(args: &[&str], input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ft"))
+        .args(args)
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "ft {:?} failed: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Each line of ndjson output, parsed.
+fn records(output: &str) -> Vec<Value> {
+    output.lines()
+        .map(|line| serde_json::from_str(line).unwrap_or_else(|e| panic!("not JSON ({}): {}", e, line)))
+        .collect()
+}
+
+#[test]
+fn ndjson_names_stdin_by_its_label_among_files() {
+    let log = sample_log("stdin-label", "2024-01-01 12:00:00 INFO from the file\n");
+    let log = log.to_str().unwrap();
+    for follow in [&["-m", "2"][..], &["--no-follow"][..]] {
+        let mut args = vec!["--format", "ndjson", "--stdin-label", "web", "-"];
+        args.push(log);
+        args.extend(follow);
+        let records = records(&ft(&args, "x ERROR\n"));
+        assert_eq!(records.len(), 2, "{:?}", records);
+        let from_stdin = records.iter().find(|r| r["record"]["raw"] == "x ERROR").unwrap();
+        assert_eq!(from_stdin["path"], "web");
+        assert_eq!(from_stdin["record"]["source"], "web");
+    }
+}
+
+const APP_LOG: &str = "\
+2024-01-01 12:00:00 ERROR db down
+2024-01-01 12:00:01 INFO ok
+";
+
+#[test]
+fn ndjson_wraps_each_record_in_an_envelope() {
+    let log = sample_log("envelope", APP_LOG);
+    let records = records(&ft(&["--no-follow", "--format", "ndjson", log.to_str().unwrap()], ""));
+    assert_eq!(records.len(), 2, "{:?}", records);
+    for (record, (offset, line)) in records.iter().zip([(0, 1), (34, 2)]) {
+        assert_eq!(record["path"], log.to_str().unwrap());
+        assert_eq!(record["offset"], offset);
+        assert_eq!(record["line"], line);
+        assert!(record["host"].as_str().is_some_and(|host| !host.is_empty()), "{}", record);
+        assert!(record["captured_at"].as_str().is_some_and(|time| time.ends_with('Z')), "{}", record);
+    }
+    assert_eq!(records[0]["record"]["level"], "ERROR");
+    assert_eq!(records[1]["record"]["raw"], "2024-01-01 12:00:01 INFO ok");
+}
+
+#[test]
+fn structured_output_from_several_files_has_no_headers() {
+    let first = sample_log("several-a", "2024-01-01 12:00:00 INFO from a\n");
+    let second = sample_log("several-b", "2024-01-01 12:00:00 INFO from b\n");
+    let output = ft(&["--no-follow", "--format", "ndjson", first.to_str().unwrap(), second.to_str().unwrap()], "");
+    assert!(!output.contains("==>"), "{}", output);
+    let records = records(&output);
+    assert_eq!(records.len(), 2, "{:?}", records);
+    for (record, path) in records.iter().zip([&first, &second]) {
+        assert_eq!(record["path"], path.to_str().unwrap());
+        assert_eq!(record["record"]["source"], path.to_str().unwrap());
+    }
+}
+
+#[test]
+fn csv_prints_its_header_once() {
+    let log = sample_log("csv", APP_LOG);
+    let output = ft(&["--no-follow", "--format", "csv", log.to_str().unwrap()], "");
+    let rows: Vec<&str> = output.lines().collect();
+    assert_eq!(rows, [
+        "timestamp,level,service,message,ip,status_code",
+        "2024-01-01 12:00:00,ERROR,,2024-01-01 12:00:00 ERROR db down,,",
+        "2024-01-01 12:00:01,INFO,,2024-01-01 12:00:01 INFO ok,,",
+    ]);
+}
+
+#[test]
+fn siem_formats_carry_severity_and_source() {
+    let log = sample_log("siem", APP_LOG);
+    let path = log.to_str().unwrap();
+    let cef = ft(&["--no-follow", "--format", "cef", path], "");
+    let cef: Vec<&str> = cef.lines().collect();
+    assert_eq!(cef.len(), 2, "{:?}", cef);
+    assert!(cef[0].starts_with("CEF:0|fuzzytail|ft|"), "{}", cef[0]);
+    assert!(cef[0].contains("|ERROR|") && cef[0].contains("|7|"), "{}", cef[0]);
+    assert!(cef[0].contains(&format!("fname={}", path)), "{}", cef[0]);
+
+    let leef = ft(&["--no-follow", "--format", "leef", path], "");
+    let leef: Vec<&str> = leef.lines().collect();
+    assert_eq!(leef.len(), 2, "{:?}", leef);
+    assert!(leef[1].starts_with("LEEF:2.0|fuzzytail|ft|"), "{}", leef[1]);
+    assert!(leef[1].contains("sev=2^"), "{}", leef[1]);
+}